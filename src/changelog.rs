@@ -0,0 +1,454 @@
+//! 更新日志
+//!
+//! 从提交历史生成更新日志：按约定式提交的类型和范围分组，单独列出不兼容变更，
+//! 从合并提交和 squash 提交的标题中找出 PR 编号并链接到代码托管平台。
+//!
+//! 只沿第一父提交回溯，合并进来的 PR 以合并提交的标题（PR 标题）代表。
+
+use crate::error::{GtResult, GtError};
+use crate::git::{Commit, ConventionalCommit, Repository};
+use crate::forge::Forge;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// 更新日志中各类型的标题，按显示顺序排列
+const SECTIONS: [(&str, &str); 11] = [
+    ("feat", "新功能"),
+    ("fix", "问题修复"),
+    ("perf", "性能优化"),
+    ("refactor", "重构"),
+    ("revert", "回退"),
+    ("docs", "文档"),
+    ("style", "代码格式"),
+    ("test", "测试"),
+    ("build", "构建"),
+    ("ci", "持续集成"),
+    ("chore", "杂项"),
+];
+
+/// 不符合约定式提交的提交所在分组
+const OTHER_SECTION: (&str, &str) = ("other", "其他");
+
+/// 新建 CHANGELOG.md 时的标题
+const CHANGELOG_HEADER: &str = "# 更新日志\n";
+
+/// 一条变更
+#[derive(Debug, Clone, Serialize)]
+pub struct ChangelogEntry {
+    /// 提交 ID
+    pub commit: String,
+    /// 约定式提交的类型，不符合规范时为 None
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    /// 范围
+    pub scope: Option<String>,
+    /// 描述
+    pub description: String,
+    /// 是否为不兼容变更
+    pub breaking: bool,
+    /// 不兼容变更的说明（`BREAKING CHANGE` 脚注）
+    pub breaking_note: Option<String>,
+    /// PR 编号
+    pub pr_number: Option<u32>,
+    /// PR 地址
+    pub pr_url: Option<String>,
+}
+
+/// 同一范围的变更
+#[derive(Debug, Clone, Serialize)]
+pub struct ScopeGroup {
+    /// 范围，没有范围的变更为 None
+    pub scope: Option<String>,
+    pub entries: Vec<ChangelogEntry>,
+}
+
+/// 同一类型的变更
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeGroup {
+    /// 类型，不符合约定式提交的为 `other`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// 标题
+    pub title: String,
+    pub scopes: Vec<ScopeGroup>,
+}
+
+/// 一个版本的更新日志
+#[derive(Debug, Clone, Serialize)]
+pub struct Changelog {
+    /// 标题，如版本号
+    pub title: String,
+    /// 生成日期
+    pub date: String,
+    /// 起点（不包含），None 表示从第一个提交开始
+    pub from: Option<String>,
+    /// 终点（包含）
+    pub to: String,
+    /// 不兼容变更
+    pub breaking: Vec<ChangelogEntry>,
+    /// 按类型和范围分组的变更
+    pub groups: Vec<TypeGroup>,
+}
+
+impl Changelog {
+    /// 生成 from（不包含）到 to 之间的更新日志
+    ///
+    /// forge 用于生成 PR 链接，为 None 时只显示编号。
+    pub fn generate(
+        repo: &Repository,
+        from: Option<&str>,
+        to: &str,
+        title: impl Into<String>,
+        forge: Option<&dyn Forge>,
+    ) -> GtResult<Self> {
+        let commits = repo.first_parent_commits(from, to).map_err(|e| GtError::InvalidInput {
+            input: format!("无法读取 {} 的提交: {}", range_label(from, to), e)
+        })?;
+
+        let mut entries: Vec<ChangelogEntry> = commits.iter().filter_map(ChangelogEntry::from_commit).collect();
+        for entry in &mut entries {
+            entry.pr_url = entry.pr_number.and_then(|number| forge?.change_request_url(number));
+        }
+
+        let breaking = entries.iter().filter(|entry| entry.breaking).cloned().collect();
+        let groups = SECTIONS.iter()
+            .chain([&OTHER_SECTION])
+            .filter_map(|(kind, title)| {
+                let matches: Vec<ChangelogEntry> = entries.iter()
+                    .filter(|entry| entry.kind.as_deref().unwrap_or(OTHER_SECTION.0) == *kind)
+                    .cloned()
+                    .collect();
+                (!matches.is_empty()).then(|| TypeGroup {
+                    kind: kind.to_string(),
+                    title: title.to_string(),
+                    scopes: group_by_scope(matches),
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            title: title.into(),
+            date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+            from: from.map(str::to_string),
+            to: to.to_string(),
+            breaking,
+            groups,
+        })
+    }
+
+    /// 版本范围，如 `v1.0.0..HEAD`
+    pub fn range(&self) -> String {
+        range_label(self.from.as_deref(), &self.to)
+    }
+
+    /// 是否没有任何变更
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// 渲染为 Markdown，以二级标题开头
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("## {} ({})\n", self.title, self.date);
+        if self.is_empty() {
+            out.push_str("\n没有变更。\n");
+            return out;
+        }
+
+        if !self.breaking.is_empty() {
+            out.push_str("\n### ⚠️ 不兼容变更\n\n");
+            for entry in &self.breaking {
+                let note = entry.breaking_note.as_deref().unwrap_or(&entry.description);
+                out.push_str(&format!("- {}{}\n", scope_prefix(&entry.scope), note));
+            }
+        }
+
+        for group in &self.groups {
+            out.push_str(&format!("\n### {}\n\n", group.title));
+            for scope in &group.scopes {
+                for entry in &scope.entries {
+                    out.push_str(&format!("- {}{}\n", scope_prefix(&scope.scope), entry.to_markdown()));
+                }
+            }
+        }
+        out
+    }
+
+    /// 把本次的更新日志插入到 path 中已有的版本之前，文件不存在时新建
+    pub fn prepend_to(&self, path: &Path) -> GtResult<()> {
+        let existing = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => CHANGELOG_HEADER.to_string(),
+            Err(e) => return Err(GtError::IoError {
+                operation: format!("读取 {}", path.display()),
+                error: e.to_string(),
+            }),
+        };
+
+        // 插入到第一个版本标题之前，保留文件开头的标题和说明
+        let section = self.to_markdown();
+        let content = match existing.find("\n## ") {
+            Some(index) => format!("{}\n{}\n{}", &existing[..index], section, &existing[index + 1..]),
+            None if existing.starts_with("## ") => format!("{}\n{}", section, existing),
+            None => format!("{}\n\n{}", existing.trim_end(), section),
+        };
+
+        fs::write(path, content).map_err(|e| GtError::IoError {
+            operation: format!("写入 {}", path.display()),
+            error: e.to_string(),
+        })
+    }
+}
+
+impl ChangelogEntry {
+    /// 从提交生成变更，普通的合并提交（没有 PR 编号）返回 None
+    fn from_commit(commit: &Commit) -> Option<Self> {
+        let (subject, body) = match commit.message.split_once('\n') {
+            Some((subject, body)) => (subject.trim(), body.trim()),
+            None => (commit.message.trim(), ""),
+        };
+
+        let (message, pr_number) = if commit.parents.len() > 1 {
+            // 合并提交以 PR 标题作为描述
+            let (title, number) = merged_pull_request(subject, body)?;
+            let rest = body.split_once('\n').map(|(_, rest)| rest.trim()).unwrap_or_default();
+            (join_message(&title, rest), Some(number))
+        } else {
+            let (title, number) = strip_pr_suffix(subject);
+            (join_message(title, body), number)
+        };
+
+        let short_id = commit.id.chars().take(7).collect();
+        let header = message.lines().next().unwrap_or_default();
+        let parsed = ConventionalCommit::parse(&message).or_else(|_| ConventionalCommit::parse(header));
+        let entry = match parsed {
+            Ok(parsed) => Self {
+                commit: short_id,
+                breaking: parsed.is_breaking(),
+                breaking_note: parsed.footers.iter()
+                    .find(|footer| footer.is_breaking())
+                    .map(|footer| footer.value.clone()),
+                kind: Some(parsed.kind),
+                scope: parsed.scope,
+                description: parsed.description,
+                pr_number,
+                pr_url: None,
+            },
+            Err(_) => Self {
+                commit: short_id,
+                kind: None,
+                scope: None,
+                description: header.to_string(),
+                breaking: false,
+                breaking_note: None,
+                pr_number,
+                pr_url: None,
+            },
+        };
+        Some(entry)
+    }
+
+    /// 渲染为一行 Markdown（不含范围）
+    fn to_markdown(&self) -> String {
+        let pr = match (self.pr_number, self.pr_url.as_ref()) {
+            (Some(number), Some(url)) => format!(" ([#{}]({}))", number, url),
+            (Some(number), None) => format!(" (#{})", number),
+            _ => String::new(),
+        };
+        format!("{}{} ({})", self.description, pr, self.commit)
+    }
+}
+
+/// 按范围分组，没有范围的在前，其余按范围名排序，组内保持提交顺序
+fn group_by_scope(entries: Vec<ChangelogEntry>) -> Vec<ScopeGroup> {
+    let mut groups: Vec<ScopeGroup> = Vec::new();
+    for entry in entries {
+        match groups.iter_mut().find(|group| group.scope == entry.scope) {
+            Some(group) => group.entries.push(entry),
+            None => groups.push(ScopeGroup { scope: entry.scope.clone(), entries: vec![entry] }),
+        }
+    }
+    groups.sort_by(|a, b| a.scope.cmp(&b.scope));
+    groups
+}
+
+/// 版本范围的显示形式，没有起点时只显示终点
+fn range_label(from: Option<&str>, to: &str) -> String {
+    match from {
+        Some(from) => format!("{}..{}", from, to),
+        None => to.to_string(),
+    }
+}
+
+/// 范围前缀，如 `**save:** `
+fn scope_prefix(scope: &Option<String>) -> String {
+    scope.as_ref().map(|scope| format!("**{}:** ", scope)).unwrap_or_default()
+}
+
+/// 拼接标题和正文
+fn join_message(title: &str, body: &str) -> String {
+    if body.is_empty() {
+        title.to_string()
+    } else {
+        format!("{}\n\n{}", title, body)
+    }
+}
+
+/// 去掉 squash 合并标题末尾的 ` (#123)`，返回标题和 PR 编号
+fn strip_pr_suffix(subject: &str) -> (&str, Option<u32>) {
+    let parsed = subject.strip_suffix(')')
+        .and_then(|rest| rest.rsplit_once(" (#"))
+        .and_then(|(title, number)| Some((title, number.parse().ok()?)));
+    match parsed {
+        Some((title, number)) => (title, Some(number)),
+        None => (subject, None),
+    }
+}
+
+/// 从合并提交中找出 PR 标题和编号
+///
+/// 支持 GitHub（`Merge pull request #12 from ...`，正文第一行为 PR 标题）、
+/// Gitea（`Merge pull request '<标题>' (#12) from ...`）和
+/// GitLab（正文中的 `See merge request group/project!12`）。
+fn merged_pull_request(subject: &str, body: &str) -> Option<(String, u32)> {
+    let first_line = body.lines().next().unwrap_or_default().trim();
+
+    if let Some(rest) = subject.strip_prefix("Merge pull request #") {
+        let number = rest.split_whitespace().next()?.parse().ok()?;
+        let title = if first_line.is_empty() { subject } else { first_line };
+        return Some((title.to_string(), number));
+    }
+
+    if let Some(rest) = subject.strip_prefix("Merge pull request '") {
+        let (title, rest) = rest.rsplit_once("' (#")?;
+        let number = rest.split_once(')')?.0.parse().ok()?;
+        return Some((title.to_string(), number));
+    }
+
+    let number = body.lines()
+        .find_map(|line| line.trim().strip_prefix("See merge request "))
+        .and_then(|reference| reference.rsplit_once('!'))
+        .and_then(|(_, number)| number.parse().ok())?;
+    let title = if first_line.is_empty() || first_line.starts_with("See merge request") { subject } else { first_line };
+    Some((title.to_string(), number))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 以 parents 为父提交创建一个空改动的提交，并移动 HEAD
+    fn commit(git: &git2::Repository, message: &str, parents: &[git2::Oid]) -> git2::Oid {
+        let signature = git2::Signature::now("gt", "gt@example.com").unwrap();
+        let tree = git.find_tree(git.index().unwrap().write_tree().unwrap()).unwrap();
+        let parents: Vec<git2::Commit> = parents.iter().map(|id| git.find_commit(*id).unwrap()).collect();
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        let id = git.commit(None, &signature, &signature, message, &tree, &parents).unwrap();
+        git.set_head_detached(id).unwrap();
+        id
+    }
+
+    /// 在 head 上合并一个分支提交
+    fn merge(git: &git2::Repository, head: git2::Oid, message: &str) -> git2::Oid {
+        let side = commit(git, "wip", &[head]);
+        commit(git, message, &[head, side])
+    }
+
+    #[test]
+    fn pr_number_from_subjects() {
+        assert_eq!(strip_pr_suffix("feat: 登录 (#7)"), ("feat: 登录", Some(7)));
+        assert_eq!(strip_pr_suffix("feat: 登录 (#abc)"), ("feat: 登录 (#abc)", None));
+        assert_eq!(strip_pr_suffix("feat: 登录"), ("feat: 登录", None));
+
+        assert_eq!(
+            merged_pull_request("Merge pull request #12 from user/feature/login", "feat(auth): 支持登录"),
+            Some(("feat(auth): 支持登录".to_string(), 12))
+        );
+        assert_eq!(
+            merged_pull_request("Merge pull request #12 from user/feature/login", ""),
+            Some(("Merge pull request #12 from user/feature/login".to_string(), 12))
+        );
+        assert_eq!(
+            merged_pull_request("Merge pull request 'fix(auth): 修复 (#3) 的问题' (#13) from fix/token into main", ""),
+            Some(("fix(auth): 修复 (#3) 的问题".to_string(), 13))
+        );
+        assert_eq!(
+            merged_pull_request("Merge branch 'docs' into 'main'", "docs: 更新 README\n\nSee merge request group/project!14"),
+            Some(("docs: 更新 README".to_string(), 14))
+        );
+        assert_eq!(merged_pull_request("Merge branch 'main' into dev", ""), None);
+    }
+
+    #[test]
+    fn generate_groups_by_type_and_scope() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+        let start = commit(&git, "chore: 初始化", &[]);
+        let head = commit(&git, "feat(save): 自动暂存新文件 (#7)", &[start]);
+        let head = commit(&git, "fix: 修复空仓库崩溃", &[head]);
+        let head = commit(&git, "feat(api)!: 移除 v1 接口\n\nBREAKING CHANGE: 需要改用 v2 接口", &[head]);
+        let head = merge(&git, head, "Merge pull request #12 from user/feature/login\n\nfeat(auth): 支持登录");
+        let head = merge(&git, head, "Merge branch 'main' into dev");
+        let head = merge(&git, head, "Merge pull request 'fix(auth): 修复令牌过期' (#13) from fix/token into main");
+        let head = merge(&git, head, "Merge branch 'docs' into 'main'\n\ndocs: 更新 README\n\nSee merge request group/project!14");
+        commit(&git, "随手改了点东西", &[head]);
+        let repo = Repository::open_path(dir.path()).unwrap();
+
+        let from = start.to_string();
+        let mut changelog = Changelog::generate(&repo, Some(&from), "HEAD", "v1.1.0", None).unwrap();
+        assert_eq!(changelog.range(), format!("{}..HEAD", from));
+
+        let kinds: Vec<&str> = changelog.groups.iter().map(|group| group.kind.as_str()).collect();
+        assert_eq!(kinds, ["feat", "fix", "docs", "other"]);
+
+        // 没有范围的在前，其余按范围名排序
+        let scopes = |kind: &str| -> Vec<(Option<String>, Vec<Option<u32>>)> {
+            changelog.groups.iter().find(|group| group.kind == kind).unwrap().scopes.iter()
+                .map(|scope| (scope.scope.clone(), scope.entries.iter().map(|entry| entry.pr_number).collect()))
+                .collect()
+        };
+        assert_eq!(scopes("feat"), [
+            (Some("api".to_string()), vec![None]),
+            (Some("auth".to_string()), vec![Some(12)]),
+            (Some("save".to_string()), vec![Some(7)]),
+        ]);
+        assert_eq!(scopes("fix"), [(None, vec![None]), (Some("auth".to_string()), vec![Some(13)])]);
+        assert_eq!(scopes("docs"), [(None, vec![Some(14)])]);
+        assert_eq!(scopes("other"), [(None, vec![None])]);
+
+        assert_eq!(changelog.breaking.len(), 1);
+        assert_eq!(changelog.breaking[0].scope.as_deref(), Some("api"));
+        assert_eq!(changelog.breaking[0].breaking_note.as_deref(), Some("需要改用 v2 接口"));
+
+        changelog.date = "2026-01-01".to_string();
+        let markdown = changelog.to_markdown();
+        assert!(markdown.starts_with("## v1.1.0 (2026-01-01)\n\n### ⚠️ 不兼容变更\n\n- **api:** 需要改用 v2 接口\n\n### 新功能\n\n"), "{}", markdown);
+        for line in [
+            "- **save:** 自动暂存新文件 (#7) (",
+            "- **auth:** 支持登录 (#12) (",
+            "\n### 问题修复\n\n- 修复空仓库崩溃 (",
+            "- **auth:** 修复令牌过期 (#13) (",
+            "\n### 文档\n\n- 更新 README (#14) (",
+            "\n### 其他\n\n- 随手改了点东西 (",
+        ] {
+            assert!(markdown.contains(line), "缺少 {:?}:\n{}", line, markdown);
+        }
+        assert!(!markdown.contains("Merge branch 'main'"), "{}", markdown);
+        assert!(!markdown.contains("初始化"), "{}", markdown);
+    }
+
+    #[test]
+    fn empty_changelog() {
+        let changelog = Changelog {
+            title: "v1.0.0".to_string(),
+            date: "2026-01-01".to_string(),
+            from: None,
+            to: "HEAD".to_string(),
+            breaking: Vec::new(),
+            groups: Vec::new(),
+        };
+        assert!(changelog.is_empty());
+        assert_eq!(changelog.range(), "HEAD");
+        assert_eq!(changelog.to_markdown(), "## v1.0.0 (2026-01-01)\n\n没有变更。\n");
+    }
+}
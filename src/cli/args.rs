@@ -2,8 +2,12 @@
 //! 
 //! 使用 clap 定义所有命令和参数，支持工作流命令和Git兼容封装。
 
-use clap::{Parser, Subcommand};
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand};
+use crate::commands::Bump;
+use crate::commands::custom::{CustomCommands, CUSTOM_ARGS};
 use crate::error::GtResult;
+use crate::ui::OutputFormat;
+use std::path::PathBuf;
 
 /// GT (Git Toolkit) - 下一代 Git 工作流工具
 /// 
@@ -47,6 +51,16 @@ GT 是一个现代化的 Git 工作流工具，专为开发者日常工作流程
 #[command(version)]
 #[command(author = "GT Team <gt@lyzno1.dev>")]
 pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+    
+    #[command(subcommand)]
+    pub command: Commands,
+}
+
+/// 全局参数
+#[derive(Args)]
+pub struct GlobalArgs {
     /// 启用详细输出
     #[arg(short, long, global = true)]
     pub verbose: bool,
@@ -59,16 +73,64 @@ pub struct Cli {
     #[arg(short = 'y', long, global = true)]
     pub yes: bool,
     
-    #[command(subcommand)]
-    pub command: Commands,
+    /// 临时覆盖配置项，如 -c remote=upstream（可多次使用）
+    #[arg(short = 'c', long = "config", value_name = "KEY=VALUE", global = true)]
+    pub config_overrides: Vec<String>,
+    
+    /// 输出格式，json 时每个命令输出一个 JSON 文档
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t = OutputFormat::Text, global = true)]
+    pub format: OutputFormat,
 }
 
 impl Cli {
+    /// 解析命令行参数
+    /// 
+    /// 先展开配置中的别名，再把自定义工作流注册为子命令，使其出现在 `gt --help` 中。
+    /// 读取配置失败时忽略别名和工作流，错误留给执行命令时报告。
+    pub fn parse_with_config() -> Self {
+        let builtin = Self::command();
+        let custom = CustomCommands::load().unwrap_or_default();
+        
+        let args = custom.expand_alias(&builtin, std::env::args_os().collect())
+            .unwrap_or_else(|message| {
+                builtin.clone().error(clap::error::ErrorKind::InvalidValue, message).exit()
+            });
+        
+        let mut command = custom.register(builtin.clone());
+        let matches = command.try_get_matches_from_mut(args).unwrap_or_else(|e| e.exit());
+        
+        let command_name = matches.subcommand_name().unwrap_or_default();
+        let parsed = if custom.is_workflow(&builtin, command_name) {
+            GlobalArgs::from_arg_matches(&matches).map(|global| {
+                let args = matches.subcommand_matches(command_name)
+                    .and_then(|sub| sub.get_many::<String>(CUSTOM_ARGS))
+                    .map(|args| args.cloned().collect())
+                    .unwrap_or_default();
+                Self {
+                    global,
+                    command: Commands::Custom { name: command_name.to_string(), args },
+                }
+            })
+        } else {
+            Self::from_arg_matches(&matches)
+        };
+        
+        parsed.unwrap_or_else(|e| e.format(&mut command).exit())
+    }
+    
     /// 执行命令
     pub async fn execute(self) -> GtResult<()> {
         use crate::cli::CommandRouter;
+        use crate::config::{ConfigFile, ConfigManager};
         
-        let router = CommandRouter::new(self.verbose, self.dry_run, !self.yes);
+        let global = self.global;
+        crate::ui::set_output_format(global.format);
+        
+        if !global.config_overrides.is_empty() {
+            ConfigManager::set_cli_overrides(ConfigFile::from_pairs(&global.config_overrides)?);
+        }
+        
+        let router = CommandRouter::new(global.verbose, global.dry_run, !global.yes);
         router.route(self.command).await
     }
 }
@@ -96,16 +158,24 @@ pub enum Commands {
     ///   gt start feature/user-auth      # 标准功能分支
     ///   gt start hotfix/login-bug -b develop  # 从develop分支创建hotfix
     ///   gt start experiment/new-ui -l  # 仅本地分支
+    ///   gt start '#123'                # 根据议题 #123 的标题生成分支名称
+    ///   gt start --issue 123 fix/crash # 使用指定名称并关联议题 #123
     #[command(visible_alias = "new")]
     Start {
         /// 分支名称 (建议格式: feature/name, hotfix/name, experiment/name)
-        #[arg(help = "分支名称，建议使用 feature/name 格式")]
-        branch: String,
+        #[arg(help = "分支名称，建议使用 feature/name 格式；写作 #123 时根据议题生成")]
+        #[arg(required_unless_present = "issue")]
+        branch: Option<String>,
         
-        /// 基础分支 (默认: main)
-        #[arg(short = 'b', long, default_value = "main")]
-        #[arg(help = "基础分支，新分支将从此分支创建")]
-        base: String,
+        /// 关联的议题编号
+        #[arg(long, value_name = "NUMBER")]
+        #[arg(help = "关联议题，没有指定分支名称时根据议题标题生成，gt ship --pr 时自动引用")]
+        issue: Option<u32>,
+        
+        /// 基础分支 (默认: 检测到的主分支)
+        #[arg(short = 'b', long)]
+        #[arg(help = "基础分支，新分支将从此分支创建（默认为远程的默认分支）")]
+        base: Option<String>,
         
         /// 本地模式：不拉取远程更新，不推送到远程
         #[arg(short = 'l', long)]
@@ -136,6 +206,16 @@ pub enum Commands {
         #[arg(help = "使用编辑器编辑提交信息")]
         edit: bool,
         
+        /// 使用约定式提交 (Conventional Commits)
+        #[arg(long)]
+        #[arg(help = "按约定式提交规范逐项输入或校验提交信息，也可以在配置中设置 commit.conventional")]
+        conventional: bool,
+        
+        /// 逐个选择要保存的 hunk
+        #[arg(short = 'p', long)]
+        #[arg(help = "逐个选择要保存的 hunk，不自动添加所有变更")]
+        patch: bool,
+        
         /// 要添加的文件 (默认为所有变更)
         #[arg(help = "指定要提交的文件，留空则提交所有变更")]
         files: Vec<String>,
@@ -173,15 +253,29 @@ pub enum Commands {
     /// • 将当前分支rebase到最新主分支
     /// • 恢复之前暂存的变更
     /// 
+    /// 遇到冲突时进度会保存下来，解决后可继续或整体回滚。
+    /// 
     /// 示例：
     ///   gt update                       # 标准同步
     ///   gt update -f                    # 强制同步(忽略未提交变更)
+    ///   gt update --continue            # 解决冲突后继续同步
+    ///   gt update --abort               # 回滚到同步前的状态
     #[command(visible_alias = "sync")]
     Update {
         /// 强制模式：忽略未提交的变更进行同步
         #[arg(short = 'f', long)]
         #[arg(help = "强制同步，忽略未提交的变更")]
         force: bool,
+        
+        /// 继续被中断的同步
+        #[arg(long = "continue", conflicts_with_all = ["abort", "force"])]
+        #[arg(help = "解决冲突后继续被中断的同步")]
+        continue_update: bool,
+        
+        /// 回滚被中断的同步
+        #[arg(long, conflicts_with = "force")]
+        #[arg(help = "放弃同步并回滚到同步前的状态")]
+        abort: bool,
     },
     
     /// 🚢 提交工作成果 (ship to production)
@@ -209,9 +303,9 @@ pub enum Commands {
         #[arg(help = "在GitHub上创建Pull Request")]
         pr: bool,
         
-        /// 自动合并 (使用rebase策略，推荐)
+        /// 自动合并 (使用配置的默认策略，未配置时为rebase)
         #[arg(short = 'a', long)]
-        #[arg(help = "创建PR并自动合并，使用rebase策略")]
+        #[arg(help = "创建PR并自动合并，使用配置的默认策略（默认rebase）")]
         auto_merge: bool,
         
         /// 自动合并 (使用squash策略)
@@ -233,14 +327,15 @@ pub enum Commands {
     /// 🗑️ 删除分支
     /// 
     /// 安全地删除本地或远程分支：
-    /// • 检查分支是否已合并
-    /// • 提供强制删除选项
-    /// • 支持批量删除
+    /// • 识别普通合并、squash 合并和 rebase 合并
+    /// • 同时删除远程分支，删除前列出并确认
+    /// • 不会删除主分支、branch.protected 中的分支和当前分支
     /// 
     /// 示例：
     ///   gt rm feature/old-feature       # 删除已合并的分支
     ///   gt rm feature/broken -f         # 强制删除分支
     ///   gt rm all                       # 删除所有已合并的分支
+    ///   gt rm all --pr                  # 同时根据已合并的 PR 判断
     Rm {
         /// 分支名称，或 "all" 删除所有已合并分支
         #[arg(help = "分支名称，或使用 'all' 删除所有已合并分支")]
@@ -250,6 +345,21 @@ pub enum Commands {
         #[arg(short = 'f', long)]
         #[arg(help = "强制删除，即使分支未合并")]
         force: bool,
+        
+        /// 查询代码托管平台上已合并的 PR
+        #[arg(long)]
+        #[arg(help = "同时查询代码托管平台上已合并的 PR")]
+        pr: bool,
+        
+        /// 只删除本地分支
+        #[arg(long)]
+        #[arg(help = "只删除本地分支，保留远程分支")]
+        local: bool,
+        
+        /// 不确认直接删除
+        #[arg(short = 'y', long)]
+        #[arg(help = "不确认直接删除")]
+        yes: bool,
     },
     
     /// 🧹 清理和重置分支
@@ -263,6 +373,23 @@ pub enum Commands {
         branch: String,
     },
     
+    /// 📚 管理分支栈
+    /// 
+    /// 基于其他功能分支创建的分支会记录父分支，组成分支栈：
+    /// • 以树形显示分支栈及领先/落后的提交数
+    /// • 父分支变化或被合并后，按顺序 restack 所有后代分支
+    /// 
+    /// 示例：
+    ///   gt start feature/b -b feature/a # 基于 feature/a 创建栈分支
+    ///   gt stack                        # 显示分支栈
+    ///   gt stack restack                # restack 当前分支所在的栈
+    ///   gt stack restack --continue     # 解决冲突后继续
+    Stack {
+        /// stack 子命令
+        #[command(subcommand)]
+        action: Option<StackAction>,
+    },
+    
     // 🛠️ Git操作增强封装 (Enhanced Git Wrappers) 🛠️
     
     /// 显示仓库状态 (增强版 git status)
@@ -277,7 +404,17 @@ pub enum Commands {
     },
     
     /// 添加文件到暂存区 (增强版 git add)
+    /// 
+    /// 示例：
+    ///   gt add                          # 交互式选择文件
+    ///   gt add -p                       # 逐个选择要暂存的 hunk
+    ///   gt add -p src/main.rs           # 只处理指定文件中的 hunk
     Add {
+        /// 逐个选择 hunk
+        #[arg(short = 'p', long)]
+        #[arg(help = "逐个显示 hunk，选择暂存、跳过、拆分或手动编辑")]
+        patch: bool,
+        
         /// 要添加的文件 (无参数则交互式选择)
         files: Vec<String>,
     },
@@ -319,15 +456,27 @@ pub enum Commands {
     },
     
     /// 分支操作 (增强版 git branch)
+    /// 
+    /// 创建和重命名分支（gt branch -- -m <新名称>）时按 [branch] 中的命名规范检查新名称。
     Branch {
-        /// 其他 branch 参数
+        /// 其他 branch 参数（不提供时列出本地分支）
         #[arg(last = true)]
         args: Vec<String>,
     },
     
     /// 切换分支 (增强版 git checkout)
+    /// 
+    /// 示例：
+    ///   gt checkout feature/login       # 切换到已有分支
+    ///   gt checkout -b feature/login    # 按命名规范创建并切换到新分支
+    ///   gt checkout -b fix/crash main   # 基于 main 创建
     Checkout {
-        /// 分支名称
+        /// 创建并切换到新分支
+        #[arg(short = 'b', long = "create", value_name = "NEW_BRANCH")]
+        #[arg(help = "创建并切换到新分支，分支名称按命名规范检查")]
+        create: Option<String>,
+        
+        /// 分支名称；使用 -b 时为新分支的起点
         branch: Option<String>,
         
         /// 其他 checkout 参数
@@ -347,7 +496,11 @@ pub enum Commands {
     
     /// 显示提交历史 (增强版 git log)
     Log {
-        /// 其他 log 参数
+        /// 显示的提交数
+        #[arg(short = 'l', long, default_value_t = 20)]
+        limit: usize,
+        
+        /// 其他 log 参数（提供时直接交给 git log）
         #[arg(last = true)]
         args: Vec<String>,
     },
@@ -402,10 +555,17 @@ pub enum Commands {
         args: Vec<String>,
     },
     
-    /// 撤销上一次提交 (增强操作)
+    /// 撤销上一次 gt 操作
+    /// 
+    /// 根据操作日志恢复分支和 HEAD，并取回操作时暂存的变更。
+    /// 
+    /// 示例：
+    ///   gt undo                         # 撤销最近一次操作，变更保留在工作区（要求工作区干净）
+    ///   gt undo --soft                  # 只恢复分支，保留暂存区和工作区
+    ///   gt undo --hard                  # 撤销并丢弃工作区的所有变更
     Undo {
-        /// 软撤销 (保留暂存)
-        #[arg(long)]
+        /// 软撤销 (保留暂存区和工作区)
+        #[arg(long, conflicts_with = "hard")]
         soft: bool,
         
         /// 硬撤销 (丢弃变更)
@@ -413,6 +573,59 @@ pub enum Commands {
         hard: bool,
     },
     
+    /// 查看 gt 操作历史
+    History {
+        /// 显示的条数
+        #[arg(short = 'l', long, default_value_t = 20)]
+        limit: usize,
+    },
+    
+    /// 📝 从提交历史生成更新日志
+    /// 
+    /// 按约定式提交的类型和范围分组，单独列出不兼容变更，
+    /// 并链接合并提交和 squash 提交标题中的 PR 编号。
+    /// 
+    /// 示例：
+    ///   gt changelog                    # 最近的标签到 HEAD
+    ///   gt changelog v1.0.0..v1.1.0     # 两个版本之间
+    ///   gt changelog --prepend          # 插入到 CHANGELOG.md 开头
+    ///   gt --format json changelog      # 输出 JSON
+    Changelog {
+        /// 版本范围
+        #[arg(help = "版本范围 <from>..<to>，只写 <from> 时到 HEAD 为止，默认从最近的标签开始")]
+        range: Option<String>,
+        
+        /// 标题
+        #[arg(short = 't', long)]
+        #[arg(help = "更新日志的标题，默认为终点的标签名或“未发布”")]
+        title: Option<String>,
+        
+        /// 插入到文件开头
+        #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = "CHANGELOG.md")]
+        #[arg(help = "把更新日志插入到文件中已有的版本之前（默认 CHANGELOG.md）")]
+        prepend: Option<PathBuf>,
+    },
+    
+    /// 🏷️ 发布新版本
+    /// 
+    /// 根据已有的版本标签计算下一个语义化版本，更新 release.version_files 中的版本号并提交，
+    /// 创建以更新日志为说明的注释标签，然后推送提交和标签。
+    /// 
+    /// 示例：
+    ///   gt release                      # 根据约定式提交决定升级哪一位
+    ///   gt release minor                # 升级次版本号
+    ///   gt release patch --no-push      # 只在本地提交和打标签
+    Release {
+        /// 升级方式
+        #[arg(value_enum, default_value_t = Bump::Auto)]
+        #[arg(help = "升级方式：auto 时有不兼容变更升级主版本号，有 feat 升级次版本号，否则升级修订号")]
+        bump: Bump,
+        
+        /// 不推送
+        #[arg(long)]
+        no_push: bool,
+    },
+    
     /// 将暂存区更改移回工作区
     Unstage {
         /// 交互式选择
@@ -487,6 +700,15 @@ pub enum Commands {
         #[arg(help = "跳过确认提示，直接更新")]
         yes: bool,
     },
+    
+    /// 配置中定义的工作流，由 `Cli::parse_with_config` 注册和解析
+    #[command(skip)]
+    Custom {
+        /// 工作流名称
+        name: String,
+        /// 调用时的参数
+        args: Vec<String>,
+    },
 }
 
 /// Stash 子命令
@@ -497,10 +719,24 @@ pub enum StashAction {
         /// stash 消息
         #[arg(short = 'm', long)]
         message: Option<String>,
+        
+        /// 同时暂存未追踪的文件
+        #[arg(short = 'u', long)]
+        include_untracked: bool,
+        
+        /// 保留暂存区的内容
+        #[arg(short = 'k', long)]
+        keep_index: bool,
+        
+        /// 只暂存指定的文件
+        paths: Vec<String>,
     },
     
     /// 恢复最近的stash
-    Pop,
+    Pop {
+        /// stash 索引 (如 0 或 stash@{0})
+        index: Option<String>,
+    },
     
     /// 应用stash但不删除
     Apply {
@@ -525,15 +761,53 @@ pub enum StashAction {
     
     /// 清空所有stash
     Clear,
+    
+    /// 基于stash创建新分支并恢复变更
+    Branch {
+        /// 新分支名称
+        name: String,
+        
+        /// stash 索引
+        index: Option<String>,
+    },
+}
+
+/// Stack 子命令
+#[derive(Subcommand)]
+pub enum StackAction {
+    /// 以树形显示分支栈
+    Show,
+    
+    /// 将当前分支所在的栈 restack 到最新的父分支上
+    Restack {
+        /// 解决冲突后继续
+        #[arg(long = "continue", conflicts_with = "abort")]
+        continue_restack: bool,
+        
+        /// 中止并回滚
+        #[arg(long)]
+        abort: bool,
+    },
+    
+    /// 修改分支的父分支
+    #[command(name = "set-parent")]
+    SetParent {
+        /// 新的父分支（主分支表示移出栈）
+        parent: String,
+        
+        /// 要修改的分支（默认当前分支）
+        #[arg(short = 'b', long)]
+        branch: Option<String>,
+    },
 }
 
 /// 配置操作
 #[derive(Subcommand)]
 pub enum ConfigAction {
-    /// 显示当前配置
+    /// 显示当前生效的配置及其来源
     Show,
     
-    /// 列出配置
+    /// 列出各配置文件中的配置
     List,
     
     /// 设置远程URL
@@ -565,13 +839,35 @@ pub enum ConfigAction {
         global: bool,
     },
     
+    /// 重新检测远程的默认分支
+    /// 
+    /// 从远程查询默认分支并更新 refs/remotes/<remote>/HEAD。
+    /// 远程的默认分支优先于配置的 main_branch，配置只在远程没有默认分支时使用
+    Detect {
+        /// 将检测结果写入当前仓库的 .gt.toml
+        #[arg(long)]
+        save: bool,
+    },
+    
     /// 从 gw 迁移配置
-    Migrate,
+    /// 
+    /// 默认写入全局配置 ~/.config/gt/config.toml
+    Migrate {
+        /// gw 安装目录或 config_vars.sh 路径（默认自动查找）
+        path: Option<String>,
+        /// 写入当前仓库的 .gt.toml
+        #[arg(long)]
+        repo: bool,
+    },
     
     /// 其他git config操作
+    /// 
+    /// 示例：
+    ///   gt config git --list
+    ///   gt config git --global core.editor vim
     Git {
         /// git config 参数
-        #[arg(last = true)]
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
 } 
\ No newline at end of file
@@ -2,9 +2,13 @@
 //! 
 //! 负责将命令分发到对应的处理器。
 
-use crate::cli::args::{Commands, ConfigAction};
+use crate::cli::args::{Commands, ConfigAction, StackAction, StashAction};
+use crate::commands::Bump;
 use crate::error::{GtResult, GtError};
 use crate::error::ErrorHandler;
+use crate::git::OperationRecorder;
+use crate::ui::{is_json, print_warning};
+use std::path::PathBuf;
 
 /// 命令路由器
 pub struct CommandRouter {
@@ -27,27 +31,71 @@ impl CommandRouter {
     
     /// 路由命令到对应的处理器
     pub async fn route(&self, command: Commands) -> GtResult<()> {
-        if self.verbose {
+        self.route_recorded(command, Self::command_line()).await
+    }
+    
+    /// 路由命令，操作日志中记录为 command_line（不含 `gt`）
+    pub async fn route_recorded(&self, command: Commands, command_line: String) -> GtResult<()> {
+        if self.verbose && !is_json() {
             println!("🔧 执行命令: {:?}", std::any::type_name_of_val(&command));
         }
         
-        if self.dry_run {
-            println!("🔍 预演模式: 不会执行实际操作");
+        if self.dry_run || !Self::is_journaled(&command) {
+            return self.dispatch(command).await;
+        }
+        
+        // 记录会修改仓库的操作，供 gt undo 使用
+        let recorder = OperationRecorder::begin(command_line).ok();
+        let result = self.dispatch(command).await;
+        
+        if let Some(recorder) = recorder {
+            if let Err(e) = recorder.finish(result.is_ok()) {
+                print_warning(&format!("记录操作日志失败: {}", e));
+            }
         }
         
+        result
+    }
+    
+    /// 需要写入操作日志的命令
+    fn is_journaled(command: &Commands) -> bool {
+        matches!(command,
+            Commands::Start { .. }
+            | Commands::Save { .. }
+            | Commands::Sp { .. }
+            | Commands::Update { .. }
+            | Commands::Ship { .. }
+            | Commands::Release { .. }
+            | Commands::Rm { .. }
+            | Commands::Clean { .. }
+            | Commands::Stack { .. }
+            | Commands::Commit { .. }
+            | Commands::Merge { .. }
+            | Commands::Reset { .. }
+            | Commands::Rebase { .. }
+        )
+    }
+    
+    /// 当前命令行（不含程序名），用于操作日志
+    fn command_line() -> String {
+        std::env::args().skip(1).collect::<Vec<_>>().join(" ")
+    }
+    
+    /// 分发命令到对应的处理器
+    async fn dispatch(&self, command: Commands) -> GtResult<()> {
         match command {
             // ⭐ 核心工作流命令
-            Commands::Start { branch, base, local } => {
-                self.handle_start(branch, base, local).await
+            Commands::Start { branch, issue, base, local } => {
+                self.handle_start(branch, issue, base, local).await
             }
-            Commands::Save { message, edit, files } => {
-                self.handle_save(message, edit, files).await
+            Commands::Save { message, edit, conventional, patch, files } => {
+                self.handle_save(message, edit, conventional, patch, files).await
             }
             Commands::Sp { message, edit, files } => {
                 self.handle_sp(message, edit, files).await
             }
-            Commands::Update { force } => {
-                self.handle_update(force).await
+            Commands::Update { force, continue_update, abort } => {
+                self.handle_update(force, continue_update, abort).await
             }
             Commands::Ship { 
                 no_switch, 
@@ -59,21 +107,24 @@ impl CommandRouter {
             } => {
                 self.handle_ship(no_switch, pr, auto_merge, squash, merge, delete_branch).await
             }
-            Commands::Rm { branch, force } => {
-                self.handle_rm(branch, force).await
+            Commands::Rm { branch, force, pr, local, yes } => {
+                self.handle_rm(branch, force, pr, local, yes).await
             }
             Commands::Clean { branch } => {
                 self.handle_clean(branch).await
             }
+            Commands::Stack { action } => {
+                self.handle_stack(action).await
+            }
             
             // 🛠️ Git操作增强封装
             Commands::Status { remote, log } => {
-                self.handle_status().await
+                self.handle_status(remote, log).await
             }
             
             // 暂时返回未实现错误的Git封装命令
-            Commands::Add { files: _ } => {
-                Err(GtError::NotImplemented { feature: "add command".to_string() })
+            Commands::Add { patch, files } => {
+                self.handle_add(patch, files).await
             }
             Commands::AddAll => {
                 Err(GtError::NotImplemented { feature: "add-all command".to_string() })
@@ -90,17 +141,17 @@ impl CommandRouter {
             Commands::Fetch { args: _ } => {
                 Err(GtError::NotImplemented { feature: "fetch command".to_string() })
             }
-            Commands::Branch { args: _ } => {
-                Err(GtError::NotImplemented { feature: "branch command".to_string() })
+            Commands::Branch { args } => {
+                self.handle_branch(args).await
             }
-            Commands::Checkout { branch: _, args: _ } => {
-                Err(GtError::NotImplemented { feature: "checkout command".to_string() })
+            Commands::Checkout { create, branch, args } => {
+                self.handle_checkout(create, branch, args).await
             }
             Commands::Merge { source: _, args: _ } => {
                 Err(GtError::NotImplemented { feature: "merge command".to_string() })
             }
-            Commands::Log { args: _ } => {
-                Err(GtError::NotImplemented { feature: "log command".to_string() })
+            Commands::Log { limit, args } => {
+                self.handle_log(limit, args).await
             }
             Commands::Diff { args: _ } => {
                 Err(GtError::NotImplemented { feature: "diff command".to_string() })
@@ -108,14 +159,23 @@ impl CommandRouter {
             Commands::Reset { target: _, args: _ } => {
                 Err(GtError::NotImplemented { feature: "reset command".to_string() })
             }
-            Commands::Stash { action: _ } => {
-                Err(GtError::NotImplemented { feature: "stash command".to_string() })
+            Commands::Stash { action } => {
+                self.handle_stash(action).await
             }
             Commands::Rebase { target: _, interactive: _, continue_rebase: _, abort: _, skip: _, args: _ } => {
                 Err(GtError::NotImplemented { feature: "rebase command".to_string() })
             }
-            Commands::Undo { soft: _, hard: _ } => {
-                Err(GtError::NotImplemented { feature: "undo command".to_string() })
+            Commands::Undo { soft, hard } => {
+                self.handle_undo(soft, hard).await
+            }
+            Commands::History { limit } => {
+                self.handle_history(limit).await
+            }
+            Commands::Changelog { range, title, prepend } => {
+                self.handle_changelog(range, title, prepend).await
+            }
+            Commands::Release { bump, no_push } => {
+                self.handle_release(bump, no_push).await
             }
             Commands::Unstage { interactive: _, files: _ } => {
                 Err(GtError::NotImplemented { feature: "unstage command".to_string() })
@@ -140,19 +200,25 @@ impl CommandRouter {
             Commands::UpdateSelf { check, yes } => {
                 self.handle_update_self(check, yes).await
             }
+            
+            // ⚙️ 配置中定义的工作流
+            Commands::Custom { name, args } => {
+                self.handle_custom(name, args).await
+            }
         }
     }
     
     /// 处理 start 命令
-    async fn handle_start(&self, branch: String, base: String, local: bool) -> GtResult<()> {
+    async fn handle_start(&self, branch: Option<String>, issue: Option<u32>, base: Option<String>, local: bool) -> GtResult<()> {
         use crate::commands::{StartCommand, start::StartOptions};
         
         let options = StartOptions {
-            branch,
-            base: if base.is_empty() { None } else { Some(base) },
+            branch: branch.unwrap_or_default(),
+            issue,
+            base: base.filter(|base| !base.is_empty()),
             local,
             force: false,
-            skip_update: self.dry_run,
+            skip_update: false,
             description: None,
             dry_run: self.dry_run,
         };
@@ -162,19 +228,51 @@ impl CommandRouter {
     }
     
     /// 处理 save 命令
-    async fn handle_save(&self, message: Option<String>, edit: bool, files: Vec<String>) -> GtResult<()> {
+    async fn handle_save(&self, message: Option<String>, edit: bool, conventional: bool, patch: bool, files: Vec<String>) -> GtResult<()> {
         use crate::commands::SaveCommand;
         
-        let cmd = SaveCommand::new(message, edit, files);
+        let cmd = SaveCommand::new(message, edit, files).conventional(conventional).patch(patch);
+        if self.dry_run {
+            return cmd.plan().map(|plan| plan.print());
+        }
+        cmd.execute().await
+    }
+    
+    /// 处理 add 命令
+    async fn handle_add(&self, patch: bool, files: Vec<String>) -> GtResult<()> {
+        use crate::commands::AddCommand;
+        
+        let cmd = AddCommand::new(files, patch);
+        if self.dry_run {
+            return cmd.plan().map(|plan| plan.print());
+        }
         cmd.execute().await
     }
     
     /// 处理 update 命令
-    async fn handle_update(&self, force: bool) -> GtResult<()> {
+    async fn handle_update(&self, force: bool, continue_update: bool, abort: bool) -> GtResult<()> {
         use crate::commands::UpdateCommand;
         
         let cmd = UpdateCommand::new(force);
-        cmd.execute().await
+        if self.dry_run {
+            let plan = if continue_update {
+                cmd.plan_resume()?
+            } else if abort {
+                cmd.plan_abort()?
+            } else {
+                cmd.plan()?
+            };
+            plan.print();
+            return Ok(());
+        }
+        
+        if continue_update {
+            cmd.resume().await
+        } else if abort {
+            cmd.abort().await
+        } else {
+            cmd.execute().await
+        }
     }
     
     /// 处理 ship 命令
@@ -189,18 +287,19 @@ impl CommandRouter {
     ) -> GtResult<()> {
         use crate::commands::ShipCommand;
         
-        // 确定合并策略
+        // 确定合并策略，-a 使用配置中的默认策略
         let merge_strategy = if squash {
             Some(crate::commands::MergeStrategy::Squash)
         } else if merge {
             Some(crate::commands::MergeStrategy::Merge)
-        } else if auto_merge {
-            Some(crate::commands::MergeStrategy::Rebase)
         } else {
             None
         };
         
-        let cmd = ShipCommand::new(no_switch, pr, merge_strategy, delete_branch);
+        let cmd = ShipCommand::new(no_switch, pr, auto_merge, merge_strategy, delete_branch);
+        if self.dry_run {
+            return cmd.plan().map(|plan| plan.print());
+        }
         cmd.execute().await
     }
     
@@ -212,25 +311,71 @@ impl CommandRouter {
         cmd.execute().await
     }
     
+    /// 处理 stack 命令
+    async fn handle_stack(&self, action: Option<StackAction>) -> GtResult<()> {
+        use crate::commands::StackCommand;
+        
+        let cmd = StackCommand::new(action);
+        if self.dry_run {
+            if let Some(plan) = cmd.plan()? {
+                plan.print();
+                return Ok(());
+            }
+        }
+        cmd.execute().await
+    }
+    
     /// 处理 status 命令
-    async fn handle_status(&self) -> GtResult<()> {
-        // 直接使用 GitOps 来检查状态，不再需要 StatusCommand
-        let git_ops = crate::git::GitOps::new()?;
-        let status = git_ops.check_status()?;
+    async fn handle_status(&self, remote: bool, log: bool) -> GtResult<()> {
+        use crate::commands::StatusCommand;
         
-        println!("工作区状态:");
-        println!("  修改的文件: {}", status.modified_files);
-        println!("  新增的文件: {}", status.added_files);
-        println!("  删除的文件: {}", status.deleted_files);
-        println!("  未追踪的文件: {}", status.untracked_files);
+        let cmd = StatusCommand::new(remote, log);
+        cmd.execute().await
+    }
+    
+    /// 处理 branch 命令
+    async fn handle_branch(&self, args: Vec<String>) -> GtResult<()> {
+        use crate::commands::BranchCommand;
         
-        if status.is_clean() {
-            println!("✅ 工作区干净");
-        } else {
-            println!("⚠️ 工作区有未处理的变更");
+        let cmd = BranchCommand::new(args);
+        if let (true, Some(plan)) = (self.dry_run, cmd.plan()) {
+            plan.print();
+            return Ok(());
         }
+        cmd.execute().await
+    }
+    
+    /// 处理 checkout 命令
+    async fn handle_checkout(&self, create: Option<String>, branch: Option<String>, args: Vec<String>) -> GtResult<()> {
+        use crate::commands::CheckoutCommand;
         
-        Ok(())
+        let cmd = CheckoutCommand::new(create, branch, args);
+        if self.dry_run {
+            return cmd.plan().map(|plan| plan.print());
+        }
+        cmd.execute().await
+    }
+    
+    /// 处理 log 命令
+    async fn handle_log(&self, limit: usize, args: Vec<String>) -> GtResult<()> {
+        use crate::commands::LogCommand;
+        
+        let cmd = LogCommand::new(limit, args);
+        cmd.execute().await
+    }
+    
+    /// 处理 stash 命令
+    async fn handle_stash(&self, action: Option<StashAction>) -> GtResult<()> {
+        use crate::commands::StashCommand;
+        
+        let cmd = StashCommand::new(action);
+        if self.dry_run {
+            if let Some(plan) = cmd.plan()? {
+                plan.print();
+                return Ok(());
+            }
+        }
+        cmd.execute().await
     }
     
     /// 处理 init 命令
@@ -241,11 +386,58 @@ impl CommandRouter {
         cmd.execute().await
     }
     
+    /// 处理 undo 命令
+    async fn handle_undo(&self, soft: bool, hard: bool) -> GtResult<()> {
+        use crate::commands::UndoCommand;
+        
+        let cmd = UndoCommand::new(soft, hard);
+        if self.dry_run {
+            return cmd.plan().map(|plan| plan.print());
+        }
+        cmd.execute().await
+    }
+    
+    /// 处理 history 命令
+    async fn handle_history(&self, limit: usize) -> GtResult<()> {
+        use crate::commands::HistoryCommand;
+        
+        let cmd = HistoryCommand::new(limit);
+        cmd.execute().await
+    }
+    
+    /// 处理 changelog 命令
+    async fn handle_changelog(&self, range: Option<String>, title: Option<String>, prepend: Option<PathBuf>) -> GtResult<()> {
+        use crate::commands::ChangelogCommand;
+        
+        let cmd = ChangelogCommand::new(range, title, prepend);
+        if self.dry_run {
+            return cmd.plan().map(|plan| plan.print());
+        }
+        cmd.execute().await
+    }
+    
+    /// 处理 release 命令
+    async fn handle_release(&self, bump: Bump, no_push: bool) -> GtResult<()> {
+        use crate::commands::ReleaseCommand;
+        
+        let cmd = ReleaseCommand::new(bump, no_push);
+        if self.dry_run {
+            return cmd.plan().map(|plan| plan.print());
+        }
+        cmd.execute().await
+    }
+    
     /// 处理 config 命令
     async fn handle_config(&self, action: Option<ConfigAction>) -> GtResult<()> {
         use crate::commands::ConfigCommand;
         
         let cmd = ConfigCommand::new(action);
+        if self.dry_run {
+            if let Some(plan) = cmd.plan()? {
+                plan.print();
+                return Ok(());
+            }
+        }
         cmd.execute().await
     }
     
@@ -258,9 +450,25 @@ impl CommandRouter {
     }
     
     /// 处理 rm 命令
-    async fn handle_rm(&self, _branch: String, _force: bool) -> GtResult<()> {
-        // TODO: 实现 RmCommand
-        Err(GtError::NotImplemented { feature: "rm command".to_string() })
+    async fn handle_rm(&self, branch: String, force: bool, pr: bool, local: bool, yes: bool) -> GtResult<()> {
+        use crate::commands::RmCommand;
+        
+        let cmd = RmCommand::new(branch, force).pr(pr).local(local).yes(yes);
+        if self.dry_run {
+            return cmd.plan().await.map(|plan| plan.print());
+        }
+        cmd.execute().await
+    }
+    
+    /// 处理配置中定义的工作流
+    async fn handle_custom(&self, name: String, args: Vec<String>) -> GtResult<()> {
+        use crate::commands::CustomCommand;
+        
+        let cmd = CustomCommand::new(name, args);
+        if self.dry_run {
+            return cmd.plan().map(|plan| plan.print());
+        }
+        cmd.execute(self).await
     }
     
     /// 处理 update-self 命令
@@ -275,16 +483,19 @@ impl CommandRouter {
             return Ok(());
         }
         
-        if !yes && !confirm_action("确定要更新 GT 到最新版本吗？", false) {
-            return Err(GtError::UserCancelled);
-        }
-        
         if self.dry_run {
-            print_step("🔍 [预演] 更新 GT 自身");
-            print_success("🔍 [预演] GT 更新完成");
+            let mut plan = crate::plan::Plan::new("update-self");
+            plan.step("pull --rebase 拉取 GT 的最新代码")
+                .step("cargo install --path . --force")
+                .step("把 ~/.cargo/bin/gt 复制到 ~/.local/bin/gt（目录存在时）");
+            plan.print();
             return Ok(());
         }
         
+        if !yes && !confirm_action("确定要更新 GT 到最新版本吗？", false) {
+            return Err(GtError::UserCancelled);
+        }
+        
         print_step("🔄 开始更新 GT...");
         
         // 1. 使用 GT 自己来同步最新代码
@@ -294,10 +505,11 @@ impl CommandRouter {
         let config = config_manager.repo_config();
         
         // 拉取最新更新
-        crate::git::network::pull_rebase_with_retry(
+        crate::git::NetworkOps::new(config.network_config()).pull_with_retry(
             git_ops.repository(),
             &config.remote_name,
-            Some(&config.main_branch)
+            Some(&config.main_branch),
+            true
         )?;
         
         // 2. 重新编译和安装
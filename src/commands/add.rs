@@ -0,0 +1,240 @@
+//! Add 命令实现
+//!
+//! 把文件或其中的部分变更添加到暂存区。`-p` 时逐个显示工作区中的 hunk，
+//! 可以暂存、跳过、拆分或手动编辑，结果直接通过 git2 写入暂存区。
+
+use crate::config::{ConfigManager, RepoConfig};
+use crate::error::GtResult;
+use crate::git::{FilePatch, GitOps, Hunk};
+use crate::plan::Plan;
+use crate::ui::{bold, cyan, emit_json, green, is_json, print_info, print_success, print_warning, prompt_key, red, select_files, Editor};
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// `-p` 时每个 hunk 的操作说明
+const PATCH_HELP: &str = "y - 暂存此 hunk
+n - 不暂存此 hunk
+s - 把此 hunk 拆成更小的 hunk
+e - 手动编辑此 hunk
+q - 退出，不再处理剩下的 hunk（已选择的会暂存）
+? - 显示帮助";
+
+/// Add 的结果，`--format json` 时作为输出
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AddReport {
+    /// 暂存了变更的文件
+    pub files: Vec<String>,
+    /// `-p` 时暂存的 hunk 数
+    pub hunks: Option<usize>,
+}
+
+/// Add 命令
+pub struct AddCommand {
+    /// 要添加的文件
+    files: Vec<String>,
+    /// 逐个选择 hunk
+    patch: bool,
+}
+
+impl AddCommand {
+    /// 创建新的 Add 命令
+    pub fn new(files: Vec<String>, patch: bool) -> Self {
+        Self { files, patch }
+    }
+
+    /// 执行命令
+    pub async fn execute(self) -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+
+        let report = if self.patch {
+            let config_manager = ConfigManager::load(git_ops.repository())?;
+            stage_patches(&git_ops, config_manager.repo_config(), &self.files)?
+        } else {
+            // 没有指定文件时交互式选择
+            let files = if self.files.is_empty() {
+                select_files("选择要暂存的文件:", &git_ops.changed_paths()?)
+            } else {
+                self.files
+            };
+            let paths: Vec<&str> = files.iter().map(String::as_str).collect();
+            git_ops.add_files(&paths)?;
+            AddReport { files, hunks: None }
+        };
+
+        emit_json(&report);
+        match report.hunks {
+            Some(0) => print_info("没有暂存任何变更"),
+            Some(hunks) => print_success(&format!("已暂存 {} 个文件中的 {} 个 hunk", report.files.len(), hunks)),
+            None if report.files.is_empty() => print_info("没有暂存任何文件"),
+            None => print_success(&format!("已暂存 {} 个文件", report.files.len())),
+        }
+        Ok(())
+    }
+
+    /// 生成执行计划，不修改暂存区
+    pub fn plan(&self) -> GtResult<Plan> {
+        let git_ops = GitOps::new()?;
+        let mut plan = Plan::new("add");
+        if self.patch {
+            let paths: Vec<&str> = self.files.iter().map(String::as_str).collect();
+            let patches = git_ops.unstaged_patches(&paths)?;
+            let hunks: usize = patches.iter().map(|patch| patch.hunks.len()).sum();
+            plan.step(format!("逐个选择 {} 个文件中的 {} 个 hunk 并暂存", patches.len(), hunks));
+        } else if self.files.is_empty() {
+            plan.step(format!("从 {} 个有变更的文件中选择要暂存的文件", git_ops.changed_paths()?.len()));
+        } else {
+            plan.step(format!("add {}", self.files.join(" ")));
+        }
+        Ok(plan)
+    }
+}
+
+/// 逐个选择工作区中的 hunk 并写入暂存区，paths 为空时处理所有文件
+///
+/// 每个文件处理完后立即写入暂存区，中途退出时已选择的 hunk 仍然会暂存。
+pub fn stage_patches(git_ops: &GitOps, config: &RepoConfig, paths: &[String]) -> GtResult<AddReport> {
+    let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+    let patches = git_ops.unstaged_patches(&paths)?;
+    let mut report = AddReport { files: Vec::new(), hunks: Some(0) };
+    if patches.is_empty() {
+        return Ok(report);
+    }
+
+    let mut selector = HunkSelector { git_ops, config, editor: None };
+    for patch in &patches {
+        let selection = selector.select(patch)?;
+        if selection.whole_file || !selection.hunks.is_empty() {
+            git_ops.stage_hunks(patch, &selection.hunks)?;
+            report.files.push(patch.path.clone());
+            report.hunks = report.hunks.map(|hunks| hunks + selection.hunks.len());
+        }
+        if selection.quit {
+            break;
+        }
+    }
+    Ok(report)
+}
+
+/// 一个文件中选择的结果
+#[derive(Default)]
+struct Selection {
+    /// 选中的 hunk
+    hunks: Vec<Hunk>,
+    /// 整体暂存（没有 hunk 的文件）
+    whole_file: bool,
+    /// 不再处理剩下的文件
+    quit: bool,
+}
+
+/// 交互式选择一个文件中的 hunk
+struct HunkSelector<'a> {
+    git_ops: &'a GitOps,
+    config: &'a RepoConfig,
+    /// 第一次编辑 hunk 时选择的编辑器
+    editor: Option<Editor>,
+}
+
+impl HunkSelector<'_> {
+    /// 逐个询问文件中的 hunk，二进制等没有 hunk 的文件询问是否整体暂存
+    fn select(&mut self, patch: &FilePatch) -> GtResult<Selection> {
+        show(bold(format!("{} {}", file_label(patch), patch.path)).to_string());
+        if patch.is_binary {
+            return Ok(match prompt_key("整体暂存此文件", "y,n,q") {
+                Some('y') => Selection { whole_file: true, ..Selection::default() },
+                Some('n') => Selection::default(),
+                _ => Selection { quit: true, ..Selection::default() },
+            });
+        }
+
+        let mut queue: VecDeque<Hunk> = patch.hunks.iter().cloned().collect();
+        let mut selected = Vec::new();
+        let mut done = 0;
+        while let Some(hunk) = queue.pop_front() {
+            show_hunk(&hunk);
+            let splittable = hunk.split().is_some();
+            let keys = if splittable { "y,n,s,e,q,?" } else { "y,n,e,q,?" };
+            let prompt = format!("({}/{}) 暂存此 hunk", done + 1, done + 1 + queue.len());
+
+            match prompt_key(&prompt, keys) {
+                Some('y') => selected.push(hunk),
+                Some('n') => {}
+                Some('s') if splittable => {
+                    let parts = hunk.split().unwrap_or_default();
+                    show(cyan(format!("拆分为 {} 个 hunk", parts.len())).to_string());
+                    parts.into_iter().rev().for_each(|part| queue.push_front(part));
+                    continue;
+                }
+                Some('e') => match self.edit(&hunk) {
+                    Ok(Some(edited)) if edited.has_changes() => selected.push(edited),
+                    Ok(Some(_)) => {}
+                    Ok(None) => {
+                        queue.push_front(hunk);
+                        continue;
+                    }
+                    Err(e) => {
+                        print_warning(&e.to_string());
+                        queue.push_front(hunk);
+                        continue;
+                    }
+                },
+                None | Some('q') => return Ok(Selection { hunks: selected, quit: true, ..Selection::default() }),
+                _ => {
+                    show(PATCH_HELP.to_string());
+                    queue.push_front(hunk);
+                    continue;
+                }
+            }
+            done += 1;
+        }
+        Ok(Selection { hunks: selected, ..Selection::default() })
+    }
+
+    /// 在编辑器中编辑 hunk，删除了所有行时返回 None
+    fn edit(&mut self, hunk: &Hunk) -> GtResult<Option<Hunk>> {
+        let repo = self.git_ops.repository();
+        let editor = match self.editor {
+            Some(ref editor) => editor,
+            None => {
+                let core_editor = repo.config_value("core.editor")?;
+                self.editor.insert(Editor::resolve(self.config.editor.as_deref(), core_editor.as_deref())?)
+            }
+        };
+        let edited = editor.edit_raw(&repo.git_dir().join("ADD_EDIT.patch"), &hunk.to_edit_text())?;
+        hunk.parse_edited(&edited)
+    }
+}
+
+/// 文件变更的类型
+fn file_label(patch: &FilePatch) -> &'static str {
+    match (patch.is_new, patch.is_deleted, patch.is_binary) {
+        (true, _, _) => "新文件",
+        (_, true, _) => "已删除",
+        (_, _, true) => "二进制文件",
+        _ => "修改",
+    }
+}
+
+/// 带颜色显示 hunk
+fn show_hunk(hunk: &Hunk) {
+    show(cyan(hunk.header()).to_string());
+    for line in &hunk.lines {
+        let text = format!("{}{}", line.origin, String::from_utf8_lossy(&line.content).trim_end_matches('\n'));
+        match line.origin {
+            '+' => show(green(text).to_string()),
+            '-' => show(red(text).to_string()),
+            _ => show(text),
+        }
+        if !line.content.ends_with(b"\n") {
+            show("\\ No newline at end of file".to_string());
+        }
+    }
+}
+
+/// 显示一行，JSON 输出模式下写到标准错误
+fn show(line: String) {
+    if is_json() {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
+}
@@ -0,0 +1,105 @@
+//! Branch 命令实现
+//!
+//! 列出本地分支及其上游、领先/落后的提交数；带参数时交给 git branch，
+//! 创建、重命名和复制分支时先按命名规范检查新名称。
+
+use crate::config::ConfigManager;
+use crate::error::GtResult;
+use crate::git::{BranchPolicy, GitOps};
+use crate::plan::Plan;
+use crate::utils::run_git;
+use crate::ui::{emit_json, is_json, print_info, green, yellow};
+
+/// 重命名和复制分支的参数，最后一个位置参数是新名称
+const MOVE_FLAGS: [&str; 6] = ["-m", "-M", "--move", "-c", "-C", "--copy"];
+
+/// Branch 命令
+pub struct BranchCommand {
+    /// 透传给 git branch 的参数
+    args: Vec<String>,
+}
+
+impl BranchCommand {
+    /// 创建新的 Branch 命令
+    pub fn new(args: Vec<String>) -> Self {
+        Self { args }
+    }
+
+    /// 生成执行计划；不带参数时只列出分支，返回 None
+    pub fn plan(&self) -> Option<Plan> {
+        if self.args.is_empty() {
+            return None;
+        }
+        let mut plan = Plan::new("branch");
+        plan.step(format!("git branch {}", self.args.join(" ")));
+        Some(plan)
+    }
+
+    /// 执行命令
+    pub async fn execute(self) -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+        if !self.args.is_empty() {
+            let args = Self::apply_policy(&git_ops, self.args)?;
+            return run_git("branch", &args);
+        }
+
+        let mut branches = git_ops.list_branches()?;
+        branches.sort_by(|a, b| a.name.cmp(&b.name));
+
+        if is_json() {
+            emit_json(&branches);
+            return Ok(());
+        }
+
+        if branches.is_empty() {
+            print_info("还没有任何分支");
+            return Ok(());
+        }
+
+        let width = branches.iter().map(|branch| branch.name.len()).max().unwrap_or(0);
+        for branch in &branches {
+            let marker = if branch.is_current { "*" } else { " " };
+            let name = format!("{:<width$}", branch.name, width = width);
+            let name = if branch.is_current { green(name).to_string() } else { name };
+            let commit = branch.last_commit.as_deref().map(|oid| &oid[..8]).unwrap_or("");
+
+            let tracking = match branch.upstream {
+                Some(ref upstream) if branch.ahead > 0 || branch.behind > 0 => {
+                    yellow(format!("[{}: 领先 {}, 落后 {}]", upstream, branch.ahead, branch.behind)).to_string()
+                }
+                Some(ref upstream) => format!("[{}]", upstream),
+                None => String::new(),
+            };
+
+            println!("{} {}  {}  {}", marker, name, commit, tracking);
+        }
+
+        Ok(())
+    }
+
+    /// 按命名规范规范化新分支名称，返回替换后的参数
+    ///
+    /// `-m [<旧名称>] <新名称>` 等检查最后一个位置参数；不带选项时
+    /// `<新名称> [<起点>]` 检查第一个位置参数；其他用法原样交给 git。
+    fn apply_policy(git_ops: &GitOps, mut args: Vec<String>) -> GtResult<Vec<String>> {
+        let positional: Vec<usize> = (0..args.len()).filter(|&index| !args[index].starts_with('-')).collect();
+        let index = if args.iter().any(|arg| MOVE_FLAGS.contains(&arg.as_str())) {
+            positional.last()
+        } else if positional.len() == args.len() {
+            positional.first()
+        } else {
+            None
+        };
+        let Some(&index) = index else {
+            return Ok(args);
+        };
+
+        let config_manager = ConfigManager::load(git_ops.repository())?;
+        let name = BranchPolicy::from_config(config_manager.repo_config())?.enforce(&args[index])?;
+        if name != args[index] {
+            print_info(&format!("分支名称已规范化为 '{}'", name));
+            args[index] = name;
+        }
+        Ok(args)
+    }
+}
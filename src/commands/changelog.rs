@@ -0,0 +1,112 @@
+//! Changelog 命令实现
+//!
+//! 从提交历史生成更新日志，打印 Markdown 或插入到 CHANGELOG.md 的开头。
+
+use crate::changelog::Changelog;
+use crate::config::ConfigManager;
+use crate::error::GtResult;
+use crate::forge;
+use crate::git::GitOps;
+use crate::plan::Plan;
+use crate::ui::{emit_json, is_json, print_info, print_success};
+use std::path::PathBuf;
+
+/// 没有指定终点时使用的版本
+const DEFAULT_TO: &str = "HEAD";
+
+/// 终点不是标签时的标题
+const UNRELEASED_TITLE: &str = "未发布";
+
+/// Changelog 命令
+pub struct ChangelogCommand {
+    /// 版本范围，`<from>..<to>` 或 `<from>`
+    range: Option<String>,
+    /// 标题
+    title: Option<String>,
+    /// 插入到的文件
+    prepend: Option<PathBuf>,
+}
+
+impl ChangelogCommand {
+    /// 创建新的 Changelog 命令
+    pub fn new(range: Option<String>, title: Option<String>, prepend: Option<PathBuf>) -> Self {
+        Self { range, title, prepend }
+    }
+
+    /// 执行命令
+    pub async fn execute(self) -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+        let repo = git_ops.repository();
+
+        let (from, to) = self.resolve_range(&git_ops)?;
+        let title = match self.title {
+            Some(ref title) => title.clone(),
+            None if repo.tag_exists(&to) => to.clone(),
+            None => UNRELEASED_TITLE.to_string(),
+        };
+
+        // 没有配置远程时不生成 PR 链接
+        let forge = ConfigManager::load(repo)
+            .and_then(|config_manager| forge::from_config(config_manager.repo_config(), repo, false))
+            .ok();
+        let changelog = Changelog::generate(repo, from.as_deref(), &to, title, forge.as_deref())?;
+
+        emit_json(&changelog);
+        match self.prepend {
+            Some(ref path) => {
+                changelog.prepend_to(path)?;
+                if !is_json() {
+                    print_success(&format!("已把 '{}' 的更新日志写入 {}", changelog.title, path.display()));
+                }
+            }
+            None if !is_json() => print!("{}", changelog.to_markdown()),
+            None => {}
+        }
+
+        if changelog.is_empty() {
+            print_info(&format!("{} 中没有提交", changelog.range()));
+        }
+        Ok(())
+    }
+
+    /// 生成执行计划，只有 `--prepend` 会写入文件
+    pub fn plan(&self) -> GtResult<Plan> {
+        let git_ops = GitOps::new()?;
+        let (from, to) = self.resolve_range(&git_ops)?;
+        let range = match from {
+            Some(from) => format!("{}..{}", from, to),
+            None => to,
+        };
+
+        let mut plan = Plan::new("changelog");
+        match self.prepend {
+            Some(ref path) => plan.step(format!("在 {} 中插入 {} 的更新日志", path.display(), range)),
+            None => plan.step(format!("打印 {} 的更新日志", range)),
+        };
+        Ok(plan)
+    }
+
+    /// 解析版本范围，没有指定起点时使用终点之前最近的标签
+    fn resolve_range(&self, git_ops: &GitOps) -> GtResult<(Option<String>, String)> {
+        let (from, to) = match self.range.as_deref() {
+            Some(range) => match range.split_once("..") {
+                Some((from, to)) => (non_empty(from), non_empty(to)),
+                None => (non_empty(range), None),
+            },
+            None => (None, None),
+        };
+        let to = to.unwrap_or_else(|| DEFAULT_TO.to_string());
+
+        let from = match from {
+            Some(from) => Some(from),
+            // 从终点的父提交开始找，终点本身是标签时得到上一个标签
+            None => git_ops.repository().latest_tag(&format!("{}^", to)).unwrap_or(None),
+        };
+        Ok((from, to))
+    }
+}
+
+/// 空字符串视为未指定
+fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
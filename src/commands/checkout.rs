@@ -0,0 +1,93 @@
+//! Checkout 命令实现
+//!
+//! 切换到已有分支；`-b` 时按命名规范检查新分支名称后创建并切换。
+//! 带其他参数或本地没有该分支时交给 git checkout。
+
+use crate::config::ConfigManager;
+use crate::error::{GtResult, GtError};
+use crate::git::{BranchPolicy, GitOps};
+use crate::plan::Plan;
+use crate::utils::run_git;
+use crate::ui::{emit_json, print_info, print_success};
+
+/// Checkout 命令
+pub struct CheckoutCommand {
+    /// 要创建的新分支
+    create: Option<String>,
+    /// 要切换到的分支，创建时为新分支的起点
+    branch: Option<String>,
+    /// 透传给 git checkout 的参数
+    args: Vec<String>,
+}
+
+impl CheckoutCommand {
+    /// 创建新的 Checkout 命令
+    pub fn new(create: Option<String>, branch: Option<String>, args: Vec<String>) -> Self {
+        Self { create, branch, args }
+    }
+
+    /// 执行命令
+    pub async fn execute(self) -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+
+        if let Some(ref create) = self.create {
+            let name = Self::branch_name(&git_ops, create)?;
+            if git_ops.repository().branch_exists(&name)? {
+                return Err(GtError::BranchAlreadyExists { branch: name });
+            }
+            git_ops.create_and_checkout_branch(&name, self.branch.as_deref())?;
+
+            emit_json(&serde_json::json!({ "branch": name, "created": true }));
+            print_success(&format!("已创建并切换到分支 '{}'", name));
+            return Ok(());
+        }
+
+        let branch = match self.branch {
+            Some(branch) if self.args.is_empty() && git_ops.repository().branch_exists(&branch)? => branch,
+            Some(branch) => {
+                let args: Vec<String> = std::iter::once(branch).chain(self.args).collect();
+                return run_git("checkout", &args);
+            }
+            None if !self.args.is_empty() => return run_git("checkout", &self.args),
+            None => return Err(GtError::InvalidInput {
+                input: "请指定要切换的分支，或使用 -b 创建新分支".to_string()
+            }),
+        };
+        git_ops.checkout_branch(&branch)?;
+
+        emit_json(&serde_json::json!({ "branch": branch, "created": false }));
+        print_success(&format!("已切换到分支 '{}'", branch));
+        Ok(())
+    }
+
+    /// 生成执行计划，不修改仓库
+    pub fn plan(&self) -> GtResult<Plan> {
+        let mut plan = Plan::new("checkout");
+        match (&self.create, &self.branch) {
+            (Some(create), start) => {
+                let git_ops = GitOps::new()?;
+                let name = Self::branch_name(&git_ops, create)?;
+                let start = start.as_deref().unwrap_or("HEAD");
+                plan.step(format!("基于 '{}' 创建并切换到新分支 '{}'", start, name));
+            }
+            (None, Some(branch)) => {
+                let args: Vec<&str> = std::iter::once(branch.as_str()).chain(self.args.iter().map(String::as_str)).collect();
+                plan.step(format!("checkout {}", args.join(" ")));
+            }
+            (None, None) => {
+                plan.step(format!("git checkout {}", self.args.join(" ")));
+            }
+        }
+        Ok(plan)
+    }
+
+    /// 按命名规范规范化并检查新分支名称
+    fn branch_name(git_ops: &GitOps, name: &str) -> GtResult<String> {
+        let config_manager = ConfigManager::load(git_ops.repository())?;
+        let branch = BranchPolicy::from_config(config_manager.repo_config())?.enforce(name)?;
+        if branch != name {
+            print_info(&format!("分支名称已规范化为 '{}'", branch));
+        }
+        Ok(branch)
+    }
+}
@@ -1,9 +1,20 @@
 //! Config 命令实现
-//! 
-//! 配置管理功能。
+//!
+//! 配置管理功能：查看 gt 的合并配置，管理远程仓库、用户信息，
+//! 从 gw 迁移配置，以及透传 git config。
 
 use crate::cli::args::ConfigAction;
+use crate::config::{ConfigFile, ConfigManager, RepoConfig};
+use crate::config::file::{global_config_path, repo_config_path};
+use crate::config::migrate::{find_gw_config, GwConfig, GW_CONFIG_SCRIPT};
 use crate::error::{GtResult, GtError};
+use crate::forge::{self, RemoteUrl};
+use crate::git::GitOps;
+use crate::plan::Plan;
+use crate::ui::{emit_json, is_json, print_separator, print_step, print_success, print_info, print_warning, confirm_action, cyan, green, red, yellow};
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 /// Config 命令
 pub struct ConfigCommand {
@@ -15,12 +26,469 @@ impl ConfigCommand {
     pub fn new(action: Option<ConfigAction>) -> Self {
         Self { action }
     }
-    
+
     /// 执行命令
     pub async fn execute(self) -> GtResult<()> {
-        // TODO: 实现 config 命令逻辑
-        Err(GtError::NotImplemented { 
-            feature: "config command".to_string() 
+        // 不带子命令时等同于 show
+        match self.action.unwrap_or(ConfigAction::Show) {
+            ConfigAction::Show => Self::show(),
+            ConfigAction::List => Self::list(),
+            ConfigAction::SetUrl { remote_or_url, url } => Self::set_url(remote_or_url, url),
+            ConfigAction::AddRemote { name, url } => Self::add_remote(&name, &url),
+            ConfigAction::User { name, email, global } => Self::set_user(&name, &email, global),
+            ConfigAction::Detect { save } => Self::detect(save),
+            ConfigAction::Migrate { path, repo } => Self::migrate(path.as_deref(), repo),
+            ConfigAction::Git { args } => Self::git_config(&args),
+        }
+    }
+
+    /// 生成执行计划，不修改仓库；只读的 show / list 返回 None
+    pub fn plan(&self) -> GtResult<Option<Plan>> {
+        let mut plan = Plan::new("config");
+
+        match self.action {
+            None | Some(ConfigAction::Show) | Some(ConfigAction::List) => return Ok(None),
+            Some(ConfigAction::SetUrl { ref remote_or_url, ref url }) => {
+                let (remote, url) = match url {
+                    Some(url) => (remote_or_url.clone(), url.clone()),
+                    None => {
+                        let manager = ConfigManager::load(GitOps::new()?.repository())?;
+                        (manager.repo_config().remote_name.clone(), remote_or_url.clone())
+                    }
+                };
+                plan.step(format!("remote set-url {} {}", remote, url));
+            }
+            Some(ConfigAction::AddRemote { ref name, ref url }) => {
+                plan.step(format!("remote add {} {}", name, url));
+            }
+            Some(ConfigAction::User { ref name, ref email, global }) => {
+                let scope = if global { "config --global" } else { "config" };
+                plan.step(format!("{} user.name \"{}\"", scope, name));
+                plan.step(format!("{} user.email \"{}\"", scope, email));
+            }
+            Some(ConfigAction::Detect { save }) => {
+                let git_ops = GitOps::new()?;
+                let manager = ConfigManager::load(git_ops.repository())?;
+                let remote = &manager.repo_config().remote_name;
+                plan.step(format!("查询远程 '{}' 的默认分支，更新 refs/remotes/{}/HEAD", remote, remote));
+                if save {
+                    let path = repo_config_path(git_ops.repository().path());
+                    plan.step(format!("把检测到的主分支写入 {}", path.display()));
+                }
+            }
+            Some(ConfigAction::Migrate { ref path, repo }) => {
+                let script = Self::gw_script(path.as_deref())?;
+                let target = Self::migrate_target(GitOps::new().ok().as_ref(), repo)?;
+                plan.step(format!("读取 gw 配置 {}", script.display()));
+                plan.step(format!("把与 {} 不同的配置项写入该文件", target.display()));
+            }
+            Some(ConfigAction::Git { ref args }) => {
+                plan.step(format!("git config {}", args.join(" ")));
+            }
+        }
+
+        Ok(Some(plan))
+    }
+
+    /// 显示生效的配置及其来源
+    fn show() -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+        let manager = ConfigManager::load(git_ops.repository())?;
+        let config = manager.repo_config();
+
+        if is_json() {
+            return Self::show_json(&git_ops, &manager);
+        }
+
+        print_separator(Some("gt 配置"));
+        for key in ConfigFile::KEYS {
+            let value = config.value_of(key)
+                .map(|value| Self::display_value(key, value))
+                .unwrap_or_else(|| "(未设置)".to_string());
+            let source = manager.source_of(key)
+                .map(|source| source.to_string())
+                .unwrap_or_default();
+            println!("  {:<20} = {} {}", key, cyan(format!("{:<24}", value)), yellow(format!("[{}]", source)));
+        }
+
+        if !config.aliases.is_empty() || !config.workflows.is_empty() {
+            print_separator(Some("别名和工作流"));
+            for (name, expansion) in &config.aliases {
+                println!("  {:<20} = gt {}", name, cyan(expansion));
+            }
+            for (name, workflow) in &config.workflows {
+                println!("  {:<20} = {}", name, cyan(workflow.steps.join(" → ")));
+            }
+        }
+
+        print_separator(Some("Git 用户"));
+        let repo = git_ops.repository();
+        let name = repo.config_value("user.name")?.unwrap_or_else(|| "(未设置)".to_string());
+        let email = repo.config_value("user.email")?.unwrap_or_else(|| "(未设置)".to_string());
+        println!("  {} <{}>", name, email);
+
+        print_separator(Some("远程仓库"));
+        let remotes = git_ops.list_remotes()?;
+        if remotes.is_empty() {
+            println!("  (无)");
+        }
+        for remote in &remotes {
+            println!("  {:<12} {}", remote.name, remote.url);
+            if let Some(ref push_url) = remote.push_url {
+                println!("  {:<12} {} (push)", "", push_url);
+            }
+        }
+
+        match remotes.iter().find(|remote| remote.name == config.remote_name) {
+            Some(remote) => {
+                if let Some(url) = RemoteUrl::parse(&remote.url) {
+                    println!("  代码托管平台: {}", forge::detect_kind(config, &url));
+                }
+            }
+            None => print_warning(&format!("配置的远程仓库 '{}' 不存在", config.remote_name)),
+        }
+
+        Ok(())
+    }
+
+    /// 以 JSON 输出生效的配置、Git 用户和远程仓库
+    fn show_json(git_ops: &GitOps, manager: &ConfigManager) -> GtResult<()> {
+        let config = manager.repo_config();
+        let mut values = Map::new();
+        for key in ConfigFile::KEYS {
+            values.insert(key.to_string(), json!({
+                "value": config.value_of(key).map(|value| Self::display_value(key, value)),
+                "source": manager.source_of(key).map(|source| source.to_string()),
+            }));
+        }
+
+        let repo = git_ops.repository();
+        let remotes: Vec<Value> = git_ops.list_remotes()?
+            .into_iter()
+            .map(|remote| json!({ "name": remote.name, "url": remote.url, "push_url": remote.push_url }))
+            .collect();
+        let forge = git_ops.get_remote(&config.remote_name).ok()
+            .and_then(|remote| RemoteUrl::parse(&remote.url))
+            .map(|url| forge::detect_kind(config, &url).to_string());
+
+        emit_json(&json!({
+            "config": values,
+            "aliases": config.aliases,
+            "workflows": config.workflows,
+            "user": {
+                "name": repo.config_value("user.name")?,
+                "email": repo.config_value("user.email")?,
+            },
+            "remotes": remotes,
+            "forge": forge,
+        }));
+        Ok(())
+    }
+
+    /// 按层列出各配置来源中显式设置的值
+    fn list() -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+        let manager = ConfigManager::load(git_ops.repository())?;
+
+        if is_json() {
+            let repo_path = repo_config_path(git_ops.repository().path());
+            emit_json(&json!({
+                "global": global_config_path()
+                    .map(|path| Self::layer_json(&path, manager.global_file().map(|(_, file)| file))),
+                "repo": Self::layer_json(&repo_path, manager.repo_file().map(|(_, file)| file)),
+                "cli": manager.cli_overrides().map(Self::entries_json),
+            }));
+            return Ok(());
+        }
+
+        if let Some(path) = global_config_path() {
+            Self::print_layer("全局配置", &path, manager.global_file().map(|(_, file)| file));
+        }
+
+        let repo_path = repo_config_path(git_ops.repository().path());
+        Self::print_layer("仓库配置", &repo_path, manager.repo_file().map(|(_, file)| file));
+
+        if let Some(file) = manager.cli_overrides() {
+            print_separator(Some("命令行参数 (-c)"));
+            Self::print_entries(file);
+        }
+
+        Ok(())
+    }
+
+    /// 打印一个配置文件层
+    fn print_layer(title: &str, path: &Path, file: Option<&ConfigFile>) {
+        print_separator(Some(&format!("{} {}", title, path.display())));
+        match file {
+            Some(file) => Self::print_entries(file),
+            None => println!("  (文件不存在)"),
+        }
+    }
+
+    /// 配置文件层的 JSON 表示，文件不存在时 entries 为 null
+    fn layer_json(path: &Path, file: Option<&ConfigFile>) -> Value {
+        json!({
+            "path": path.display().to_string(),
+            "entries": file.map(Self::entries_json),
         })
     }
-} 
\ No newline at end of file
+
+    /// 配置文件中设置了的配置项
+    fn entries_json(file: &ConfigFile) -> Value {
+        let mut entries = Map::new();
+        for key in ConfigFile::KEYS {
+            if let Some(value) = file.get(key) {
+                entries.insert(key.to_string(), Value::String(Self::display_value(key, value)));
+            }
+        }
+        Value::Object(entries)
+    }
+
+    /// 打印配置文件中设置了的配置项
+    fn print_entries(file: &ConfigFile) {
+        let mut empty = true;
+        for key in ConfigFile::KEYS {
+            if let Some(value) = file.get(key) {
+                println!("  {} = {}", key, cyan(Self::display_value(key, value)));
+                empty = false;
+            }
+        }
+        if empty {
+            println!("  (无配置项)");
+        }
+    }
+
+    /// 显示用的配置值，令牌等敏感值只保留开头几位
+    fn display_value(key: &str, value: String) -> String {
+        if !ConfigFile::is_secret(key) {
+            return value;
+        }
+        let prefix: String = value.chars().take(4).collect();
+        format!("{}****", prefix)
+    }
+
+    /// 重新检测远程的默认分支
+    fn detect(save: bool) -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+        let repo = git_ops.repository();
+        let manager = ConfigManager::load(repo)?;
+        let config = manager.repo_config();
+
+        print_step(&format!("查询远程 '{}' 的默认分支...", config.remote_name));
+        if let Err(e) = repo.refresh_remote_head(&config.remote_name) {
+            // 离线时仍可以根据本地已有的引用检测
+            print_warning(&format!("{}，使用本地已有的远程引用检测", e));
+        }
+
+        // 远程的默认分支优先，配置的主分支只在远程引用中没有默认分支时使用
+        let (branch, reason) = match (
+            RepoConfig::detect_remote_main_branch(repo, &config.remote_name)?,
+            manager.configured_main_branch(),
+        ) {
+            (Some((branch, reason)), configured) => {
+                print_success(&format!("检测到主分支: {} ({})", branch, reason));
+                if let Some((value, source)) = configured.filter(|(value, _)| *value != branch) {
+                    print_warning(&format!(
+                        "{} 中配置的主分支 '{}' 不会生效：远程的默认分支优先，配置只在远程没有默认分支时使用",
+                        source, value
+                    ));
+                }
+                (branch, reason)
+            }
+            (None, Some((value, source))) => {
+                print_warning(&format!("没有检测到远程的默认分支，使用{} 中配置的主分支 '{}'", source, value));
+                (value, source.to_string())
+            }
+            (None, None) => {
+                let (branch, reason) = RepoConfig::detect_main_branch(repo, &config.remote_name)?;
+                print_success(&format!("检测到主分支: {} ({})", branch, reason));
+                (branch, reason)
+            }
+        };
+        emit_json(&json!({ "main_branch": branch, "reason": reason }));
+
+        if save {
+            let path = repo_config_path(repo.path());
+            let mut file = ConfigFile::load(&path)?.unwrap_or_default();
+            file.set("main_branch", &branch)?;
+            file.save(&path)?;
+            print_success(&format!("已写入 {}", path.display()));
+        }
+
+        Ok(())
+    }
+
+    /// 从 gw 迁移配置
+    fn migrate(path: Option<&str>, to_repo: bool) -> GtResult<()> {
+        let script = Self::gw_script(path)?;
+
+        print_step(&format!("读取 gw 配置: {}", script.display()));
+        let gw = GwConfig::load(&script)?;
+        let (imported, skipped) = gw.to_config_file();
+
+        for entry in &skipped {
+            print_warning(&format!("跳过无法迁移的变量: {}", entry));
+        }
+
+        let git_ops = GitOps::new().ok();
+        let target = Self::migrate_target(git_ops.as_ref(), to_repo)?;
+
+        // 与当前生效的配置对比
+        let manager = match git_ops {
+            Some(ref git_ops) => Some(ConfigManager::load(git_ops.repository())?),
+            None => None,
+        };
+        let mut target_file = ConfigFile::load(&target)?.unwrap_or_default();
+        let mut changes = Vec::new();
+
+        print_separator(Some(&format!("迁移到 {}", target.display())));
+        for key in ConfigFile::KEYS {
+            let Some(new_value) = imported.get(key) else { continue };
+            let current = manager.as_ref()
+                .and_then(|manager| manager.repo_config().value_of(key))
+                .or_else(|| target_file.get(key));
+
+            if target_file.get(key).as_deref() == Some(new_value.as_str()) {
+                if !is_json() {
+                    println!("  {:<20} {} (不变)", key, new_value);
+                }
+                continue;
+            }
+
+            if !is_json() {
+                if current.as_deref() == Some(new_value.as_str()) {
+                    println!("  {:<20} {} (与当前生效值相同，写入配置文件)", key, green(&new_value));
+                } else {
+                    println!("  {:<20} {} -> {}",
+                        key,
+                        red(current.clone().unwrap_or_else(|| "(未设置)".to_string())),
+                        green(&new_value)
+                    );
+                }
+            }
+            target_file.set(key, &new_value)?;
+            changes.push(json!({
+                "key": key,
+                "from": current.map(|value| Self::display_value(key, value)),
+                "to": Self::display_value(key, new_value),
+            }));
+        }
+
+        let changed = changes.len();
+        emit_json(&json!({ "path": target.display().to_string(), "changes": changes, "skipped": skipped }));
+        if changed == 0 {
+            print_info("没有需要迁移的配置");
+            return Ok(());
+        }
+
+        if !confirm_action(&format!("写入 {} 项配置到 {}?", changed, target.display()), true) {
+            return Err(GtError::UserCancelled);
+        }
+
+        target_file.save(&target)?;
+        print_success(&format!("已迁移 {} 项 gw 配置", changed));
+        Ok(())
+    }
+
+    /// 查找 gw 的配置脚本
+    fn gw_script(path: Option<&str>) -> GtResult<PathBuf> {
+        find_gw_config(path.map(Path::new)).ok_or_else(|| GtError::ConfigError {
+            message: match path {
+                Some(path) => format!("在 {} 中没有找到 gw 配置 ({})", path, GW_CONFIG_SCRIPT),
+                None => format!("没有找到 gw 安装，请指定 gw 目录或 {} 路径", GW_CONFIG_SCRIPT),
+            }
+        })
+    }
+
+    /// 迁移的目标配置文件
+    fn migrate_target(git_ops: Option<&GitOps>, to_repo: bool) -> GtResult<PathBuf> {
+        if to_repo {
+            let git_ops = git_ops.ok_or(GtError::NotGitRepository)?;
+            Ok(repo_config_path(git_ops.repository().path()))
+        } else {
+            global_config_path().ok_or_else(|| GtError::ConfigError {
+                message: "无法确定全局配置目录".to_string()
+            })
+        }
+    }
+
+    /// 修改远程仓库 URL
+    ///
+    /// 只给一个参数时视为 URL，修改配置的默认远程仓库
+    fn set_url(remote_or_url: String, url: Option<String>) -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+
+        let (remote, url) = match url {
+            Some(url) => (remote_or_url, url),
+            None => {
+                let manager = ConfigManager::load(git_ops.repository())?;
+                (manager.repo_config().remote_name.clone(), remote_or_url)
+            }
+        };
+
+        let old_url = git_ops.get_remote(&remote)?.url;
+        git_ops.set_remote_url(&remote, &url)?;
+
+        print_success(&format!("远程仓库 '{}' 的 URL 已更新", remote));
+        print_info(&format!("{} -> {}", old_url, url));
+        Ok(())
+    }
+
+    /// 添加远程仓库
+    fn add_remote(name: &str, url: &str) -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+
+        if git_ops.repository().remote_exists(name)? {
+            return Err(GtError::InvalidInput {
+                input: format!("远程仓库 '{}' 已存在，可使用 'gt config set-url {} <url>' 修改", name, name)
+            });
+        }
+
+        git_ops.add_remote(name, url)?;
+        print_success(&format!("已添加远程仓库 '{}': {}", name, url));
+        Ok(())
+    }
+
+    /// 设置用户名和邮箱
+    fn set_user(name: &str, email: &str, global: bool) -> GtResult<()> {
+        if !email.contains('@') {
+            return Err(GtError::InvalidInput {
+                input: format!("无效的邮箱地址: {}", email)
+            });
+        }
+
+        let git_ops = GitOps::new()?;
+        let repo = git_ops.repository();
+        repo.set_config_value("user.name", name, global)?;
+        repo.set_config_value("user.email", email, global)?;
+
+        let scope = if global { "全局" } else { "当前仓库" };
+        print_success(&format!("已设置{}用户: {} <{}>", scope, name, email));
+        Ok(())
+    }
+
+    /// 透传给 git config
+    fn git_config(args: &[String]) -> GtResult<()> {
+        if args.is_empty() {
+            return Err(GtError::InvalidInput {
+                input: "请提供 git config 参数，例如: gt config git --list".to_string()
+            });
+        }
+
+        let status = Command::new("git")
+            .arg("config")
+            .args(args)
+            .status()
+            .map_err(|e| GtError::GitOperation {
+                message: format!("执行 git config 失败: {}", e)
+            })?;
+
+        if !status.success() {
+            return Err(GtError::GitOperation {
+                message: format!("git config {} 执行失败", args.join(" "))
+            });
+        }
+
+        Ok(())
+    }
+}
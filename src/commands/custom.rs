@@ -0,0 +1,414 @@
+//! 自定义命令
+//!
+//! 配置中可以定义两类命令：
+//! - `[aliases]`：别名，展开为一条 gt 命令，调用时的参数追加在后面
+//! - `[workflows.<名称>]`：工作流，按顺序执行 gt 命令和 shell 命令
+//!
+//! ```toml
+//! [aliases]
+//! pr = "ship --pr --squash"
+//!
+//! [workflows.hotfix]
+//! description = "保存、格式化并推送，然后创建带标签的草稿 PR"
+//! steps = [
+//!     "gt save -m {1}",
+//!     "cargo fmt --all",
+//!     "gt ship --no-switch",
+//!     "gh pr create --draft --fill --label hotfix",
+//! ]
+//! ```
+//!
+//! 步骤中的 `{1}`、`{2}` 等引用调用时的第 N 个参数，`{args}` 引用全部参数。
+//! 与内置命令同名的别名和工作流不会生效。
+
+use crate::cli::{Cli, CommandRouter};
+use crate::cli::args::Commands;
+use crate::config::{ConfigFile, ConfigManager, WorkflowSection};
+use crate::config::file::global_config_path;
+use crate::error::{GtResult, GtError};
+use crate::git::Repository;
+use crate::plan::Plan;
+use crate::ui::{is_json, print_success, OutputFormat};
+use crate::workflow::{Step, WorkflowEngine};
+use async_trait::async_trait;
+use clap::{Arg, Command, Parser};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fmt;
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// 自定义子命令接收参数的 id
+pub const CUSTOM_ARGS: &str = "args";
+
+/// 配置中的别名和工作流
+#[derive(Debug, Clone, Default)]
+pub struct CustomCommands {
+    aliases: BTreeMap<String, String>,
+    workflows: BTreeMap<String, WorkflowSection>,
+}
+
+impl CustomCommands {
+    /// 读取配置中的别名和工作流，不在 Git 仓库中时只读取全局配置
+    pub fn load() -> GtResult<Self> {
+        if let Ok(repo) = Repository::discover() {
+            let config = ConfigManager::load(&repo)?.repo_config().clone();
+            return Ok(Self {
+                aliases: config.aliases,
+                workflows: config.workflows,
+            });
+        }
+
+        let file = match global_config_path() {
+            Some(path) => ConfigFile::load(&path)?.unwrap_or_default(),
+            None => ConfigFile::default(),
+        };
+        Ok(Self {
+            aliases: file.aliases,
+            workflows: file.workflows,
+        })
+    }
+
+    /// 别名
+    pub fn aliases(&self) -> &BTreeMap<String, String> {
+        &self.aliases
+    }
+
+    /// 工作流
+    pub fn workflows(&self) -> &BTreeMap<String, WorkflowSection> {
+        &self.workflows
+    }
+
+    /// 是否为生效的工作流（不与内置命令或别名同名）
+    pub fn is_workflow(&self, command: &Command, name: &str) -> bool {
+        self.workflows.contains_key(name)
+            && !self.aliases.contains_key(name)
+            && command.find_subcommand(name).is_none()
+    }
+
+    /// 把别名和工作流注册为子命令，使其出现在 `gt --help` 中
+    pub fn register(&self, mut command: Command) -> Command {
+        let builtin = command.clone();
+
+        for (name, expansion) in &self.aliases {
+            if builtin.find_subcommand(name).is_some() {
+                continue;
+            }
+            command = command.subcommand(
+                Command::new(name.clone())
+                    .about(format!("别名: gt {}", expansion))
+                    .arg(Self::args_arg())
+            );
+        }
+
+        for (name, workflow) in &self.workflows {
+            if !self.is_workflow(&builtin, name) {
+                continue;
+            }
+            let about = workflow.description.clone()
+                .unwrap_or_else(|| format!("工作流: {}", workflow.steps.join(" → ")));
+            let steps = workflow.steps.iter()
+                .enumerate()
+                .map(|(i, step)| format!("  {}. {}", i + 1, step))
+                .collect::<Vec<_>>()
+                .join("\n");
+            command = command.subcommand(
+                Command::new(name.clone())
+                    .about(about)
+                    .after_help(format!("工作流步骤:\n{}", steps))
+                    .arg(Self::args_arg())
+            );
+        }
+
+        command
+    }
+
+    /// 展开命令行中的别名
+    ///
+    /// 别名格式错误时返回错误信息，不是别名时原样返回。
+    pub fn expand_alias(&self, command: &Command, mut args: Vec<OsString>) -> Result<Vec<OsString>, String> {
+        let Some(index) = subcommand_index(&args) else {
+            return Ok(args);
+        };
+        let Some(name) = args[index].to_str() else {
+            return Ok(args);
+        };
+        if command.find_subcommand(name).is_some() {
+            return Ok(args);
+        }
+        let Some(expansion) = self.aliases.get(name) else {
+            return Ok(args);
+        };
+
+        let words = shell_words::split(expansion)
+            .map_err(|e| format!("别名 '{}' 格式错误: {}", name, e))?;
+        if words.is_empty() {
+            return Err(format!("别名 '{}' 为空", name));
+        }
+        args.splice(index..=index, words.into_iter().map(OsString::from));
+        Ok(args)
+    }
+
+    /// 自定义子命令的参数，原样交给别名或工作流
+    fn args_arg() -> Arg {
+        Arg::new(CUSTOM_ARGS)
+            .help("传给工作流的参数")
+            .num_args(0..)
+            .trailing_var_arg(true)
+            .allow_hyphen_values(true)
+    }
+}
+
+/// 命令行中子命令的位置，跳过全局参数
+fn subcommand_index(args: &[OsString]) -> Option<usize> {
+    let mut index = 1;
+    while let Some(arg) = args.get(index) {
+        let arg = arg.to_str()?;
+        if arg == "--" {
+            return None;
+        }
+        if !arg.starts_with('-') {
+            return Some(index);
+        }
+        // 带值的全局参数
+        if matches!(arg, "-c" | "--config" | "--format") {
+            index += 1;
+        }
+        index += 1;
+    }
+    None
+}
+
+/// 自定义工作流命令
+pub struct CustomCommand {
+    /// 工作流名称
+    name: String,
+    /// 调用时的参数
+    args: Vec<String>,
+}
+
+impl CustomCommand {
+    /// 创建新的工作流命令
+    pub fn new(name: String, args: Vec<String>) -> Self {
+        Self { name, args }
+    }
+
+    /// 生成执行计划
+    pub fn plan(&self) -> GtResult<Plan> {
+        let mut plan = Plan::new(self.name.clone());
+        for step in self.steps()? {
+            plan.step(step.to_string());
+        }
+        Ok(plan)
+    }
+
+    /// 执行命令，gt 步骤通过 router 在当前进程中执行
+    pub async fn execute(self, router: &CommandRouter) -> GtResult<()> {
+        let mut workflow = WorkflowEngine::new(self.name.clone());
+        for step in self.steps()? {
+            workflow = workflow.step(step);
+        }
+
+        let mut ctx = CustomContext {
+            router,
+            workdir: Repository::discover().ok().map(|repo| repo.path().to_path_buf()),
+        };
+        workflow.run(&mut ctx).await?;
+
+        print_success(&format!("工作流 '{}' 执行完成", self.name));
+        Ok(())
+    }
+
+    /// 读取工作流并展开步骤中的参数
+    ///
+    /// 在执行前解析所有步骤，避免执行到一半才发现后面的步骤写错了。
+    fn steps(&self) -> GtResult<Vec<CustomStep>> {
+        let commands = CustomCommands::load()?;
+        let workflow = commands.workflows.get(&self.name)
+            .ok_or_else(|| GtError::WorkflowValidationError {
+                reason: format!("配置中没有名为 '{}' 的工作流", self.name)
+            })?;
+
+        if workflow.steps.is_empty() {
+            return Err(GtError::WorkflowValidationError {
+                reason: format!("工作流 '{}' 没有定义步骤", self.name)
+            });
+        }
+
+        let takes_args = workflow.steps.iter().any(|step| !placeholders(step).is_empty());
+        if !takes_args && !self.args.is_empty() {
+            return Err(GtError::WorkflowValidationError {
+                reason: format!("工作流 '{}' 不接受参数（步骤中可以用 {{1}}、{{args}} 引用参数）", self.name)
+            });
+        }
+
+        workflow.steps.iter()
+            .map(|step| self.parse_step(step))
+            .collect()
+    }
+
+    /// 解析一个步骤，`gt ` 开头的按 gt 命令解析，其余作为 shell 命令
+    fn parse_step(&self, step: &str) -> GtResult<CustomStep> {
+        let words = shell_words::split(step).map_err(|e| GtError::WorkflowValidationError {
+            reason: format!("步骤 '{}' 格式错误: {}", step, e)
+        })?;
+
+        if words.first().map(String::as_str) != Some("gt") {
+            return Ok(CustomStep::Shell(self.expand(step, shell_words::quote)?));
+        }
+
+        let mut args = Vec::new();
+        for word in &words[1..] {
+            if word == "{args}" {
+                args.extend(self.args.iter().cloned());
+            } else {
+                args.push(self.expand(word, raw)?);
+            }
+        }
+
+        let invalid = |reason: String| GtError::WorkflowValidationError {
+            reason: format!("步骤 '{}' 不是有效的 gt 命令: {}", step, reason)
+        };
+        let cli = Cli::try_parse_from(std::iter::once("gt".to_string()).chain(args.iter().cloned()))
+            .map_err(|e| invalid(e.to_string().lines().next().unwrap_or_default().to_string()))?;
+
+        let global = &cli.global;
+        if global.verbose || global.dry_run || global.yes
+            || !global.config_overrides.is_empty()
+            || global.format != OutputFormat::Text
+        {
+            return Err(invalid("步骤中不能使用全局参数，请在调用工作流时指定".to_string()));
+        }
+
+        Ok(CustomStep::Gt {
+            line: shell_words::join(&args),
+            command: Some(cli.command),
+        })
+    }
+
+    /// 替换文本中的参数占位符，quote 决定参数值如何写入
+    fn expand(&self, text: &str, quote: fn(&str) -> Cow<'_, str>) -> GtResult<String> {
+        let mut result = String::new();
+        let mut last = 0;
+
+        for (range, name) in placeholders(text) {
+            result.push_str(&text[last..range.start]);
+            if name == "args" {
+                let args: Vec<Cow<str>> = self.args.iter().map(|arg| quote(arg)).collect();
+                result.push_str(&args.join(" "));
+            } else {
+                let index: usize = name.parse().unwrap_or_default();
+                let value = self.args.get(index - 1).ok_or_else(|| GtError::WorkflowValidationError {
+                    reason: format!("工作流 '{}' 需要至少 {} 个参数", self.name, index)
+                })?;
+                result.push_str(&quote(value));
+            }
+            last = range.end;
+        }
+
+        result.push_str(&text[last..]);
+        Ok(result)
+    }
+}
+
+/// 原样写入参数值
+fn raw(value: &str) -> Cow<'_, str> {
+    Cow::Borrowed(value)
+}
+
+/// 文本中的参数占位符 `{args}` / `{N}`（N 从 1 开始），返回位置和名称
+fn placeholders(text: &str) -> Vec<(Range<usize>, &str)> {
+    let mut found = Vec::new();
+    let mut start = 0;
+
+    while let Some(open) = text[start..].find('{').map(|i| start + i) {
+        let Some(close) = text[open..].find('}').map(|i| open + i) else {
+            break;
+        };
+        let name = &text[open + 1..close];
+        let is_index = name.parse::<usize>().map_or(false, |index| index > 0);
+        if name == "args" || is_index {
+            found.push((open..close + 1, name));
+            start = close + 1;
+        } else {
+            start = open + 1;
+        }
+    }
+
+    found
+}
+
+/// 工作流步骤之间共享的上下文
+struct CustomContext<'r> {
+    router: &'r CommandRouter,
+    /// shell 步骤的工作目录（仓库根目录）
+    workdir: Option<PathBuf>,
+}
+
+/// 工作流中的一个步骤
+enum CustomStep {
+    /// gt 命令，line 是不含 `gt` 的命令行，用于显示和操作日志
+    Gt { line: String, command: Option<Commands> },
+    /// shell 命令
+    Shell(String),
+}
+
+impl fmt::Display for CustomStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CustomStep::Gt { line, .. } => write!(f, "gt {}", line),
+            CustomStep::Shell(script) => write!(f, "执行: {}", script),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl<'r> Step<CustomContext<'r>> for CustomStep {
+    fn description(&self, _ctx: &CustomContext<'r>) -> String {
+        self.to_string()
+    }
+
+    fn check(&self, _ctx: &CustomContext<'r>) -> GtResult<()> {
+        if matches!(self, CustomStep::Shell(_)) && is_json() {
+            return Err(GtError::InvalidInput {
+                input: "--format json 不支持执行 shell 步骤".to_string()
+            });
+        }
+        Ok(())
+    }
+
+    async fn run(&mut self, ctx: &mut CustomContext<'r>) -> GtResult<()> {
+        match self {
+            CustomStep::Gt { line, command } => {
+                let command = command.take()
+                    .ok_or_else(|| GtError::internal_error(format!("步骤 'gt {}' 已经执行过", line)))?;
+                ctx.router.route_recorded(command, line.clone()).await
+            }
+            CustomStep::Shell(script) => {
+                let mut command = std::process::Command::new("sh");
+                command.arg("-c").arg(script.as_str());
+                if let Some(ref workdir) = ctx.workdir {
+                    command.current_dir(workdir);
+                }
+
+                let status = command.status().map_err(|e| GtError::CommandError {
+                    command: script.clone(),
+                    error: e.to_string(),
+                })?;
+                if !status.success() {
+                    return Err(GtError::WorkflowStepError {
+                        step: script.clone(),
+                        reason: status.to_string(),
+                    });
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// gt 命令自己负责失败时的回滚，执行成功后和 shell 命令一样无法撤销
+    fn reversible(&self) -> bool {
+        false
+    }
+}
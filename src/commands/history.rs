@@ -0,0 +1,60 @@
+//! History 命令实现
+//!
+//! 列出操作日志中记录的 gt 操作。
+
+use crate::error::GtResult;
+use crate::git::{GitOps, Journal};
+use crate::utils::format_timestamp;
+use crate::ui::{emit_json, is_json, print_info, green, red, yellow};
+
+/// History 命令
+pub struct HistoryCommand {
+    /// 显示的条数
+    limit: usize,
+}
+
+impl HistoryCommand {
+    /// 创建新的 History 命令
+    pub fn new(limit: usize) -> Self {
+        Self { limit }
+    }
+
+    /// 执行命令
+    pub async fn execute(self) -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+        let entries = Journal::new(git_ops.repository()).entries()?;
+
+        if is_json() {
+            let recent: Vec<_> = entries.iter().rev().take(self.limit).collect();
+            emit_json(&recent);
+            return Ok(());
+        }
+
+        if entries.is_empty() {
+            print_info("还没有记录任何 gt 操作");
+            return Ok(());
+        }
+
+        for entry in entries.iter().rev().take(self.limit) {
+            let status = if entry.undone {
+                yellow("已撤销")
+            } else if entry.success {
+                green("成功")
+            } else {
+                red("失败")
+            };
+
+            println!("#{:<4} {}  [{}]  gt {}", entry.id, format_timestamp(entry.time), status, entry.command);
+            for change in &entry.changes {
+                let before = change.before.as_deref().map(|oid| &oid[..8]).unwrap_or("(无)");
+                let after = change.after.as_deref().map(|oid| &oid[..8]).unwrap_or("(已删除)");
+                println!("        {}: {} -> {}", change.short_name(), before, after);
+            }
+            if entry.stash.is_some() {
+                println!("        创建了 stash");
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+//! Log 命令实现
+//!
+//! 列出当前分支最近的提交；带参数时交给 git log。
+
+use crate::error::GtResult;
+use crate::git::GitOps;
+use crate::utils::{format_timestamp, run_git};
+use crate::ui::{emit_json, is_json, print_info, yellow};
+
+/// Log 命令
+pub struct LogCommand {
+    /// 显示的提交数
+    limit: usize,
+    /// 透传给 git log 的参数
+    args: Vec<String>,
+}
+
+impl LogCommand {
+    /// 创建新的 Log 命令
+    pub fn new(limit: usize, args: Vec<String>) -> Self {
+        Self { limit, args }
+    }
+
+    /// 执行命令
+    pub async fn execute(self) -> GtResult<()> {
+        if !self.args.is_empty() {
+            return run_git("log", &self.args);
+        }
+
+        let git_ops = GitOps::new()?;
+        let commits = git_ops.get_commit_history(self.limit, 0)?;
+
+        if is_json() {
+            emit_json(&commits);
+            return Ok(());
+        }
+
+        if commits.is_empty() {
+            print_info("还没有任何提交");
+            return Ok(());
+        }
+
+        for commit in &commits {
+            println!(
+                "{}  {}  {}  {}",
+                yellow(&commit.id[..8]),
+                format_timestamp(commit.time),
+                commit.author,
+                commit.message.lines().next().unwrap_or("")
+            );
+        }
+
+        Ok(())
+    }
+}
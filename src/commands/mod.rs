@@ -8,25 +8,29 @@ pub mod save;
 pub mod sp;
 pub mod update;
 pub mod ship;
-// pub mod rm;  // TODO: 待实现
+pub mod rm;
 pub mod clean;
+pub mod stack;
 
 // Git操作增强封装 - TODO: 待实现
-// pub mod status;
-// pub mod add;
+pub mod status;
+pub mod add;
 // pub mod commit;
 // pub mod push;
 // pub mod pull;
 // pub mod fetch;
-// pub mod branch;
-// pub mod checkout;
+pub mod branch;
+pub mod checkout;
 // pub mod merge;
-// pub mod log;
+pub mod log;
 // pub mod diff;
 // pub mod reset;
-// pub mod stash;
+pub mod stash;
 // pub mod rebase;
-// pub mod undo;
+pub mod undo;
+pub mod history;
+pub mod changelog;
+pub mod release;
 // pub mod unstage;
 
 // 仓库管理与配置
@@ -36,6 +40,9 @@ pub mod config;
 // pub mod gh_create;
 // pub mod ide;
 
+// 配置中定义的别名和工作流
+pub mod custom;
+
 // 兼容性别名 - TODO: 待实现
 // pub mod push_aliases;
 
@@ -44,8 +51,20 @@ pub use start::{StartCommand, StartOptions};
 pub use save::SaveCommand;
 pub use sp::SpCommand;
 pub use update::UpdateCommand;
-pub use ship::{ShipCommand, MergeStrategy};
-// pub use rm::RmCommand;
+pub use ship::{ShipCommand, ShipReport, MergeStrategy};
+pub use rm::RmCommand;
 pub use clean::CleanCommand;
+pub use add::AddCommand;
+pub use status::StatusCommand;
+pub use stack::StackCommand;
 pub use init::InitCommand;
-pub use config::ConfigCommand; 
\ No newline at end of file
+pub use config::ConfigCommand;
+pub use stash::StashCommand;
+pub use undo::UndoCommand;
+pub use history::HistoryCommand;
+pub use changelog::ChangelogCommand;
+pub use release::{ReleaseCommand, Bump};
+pub use branch::BranchCommand;
+pub use checkout::CheckoutCommand;
+pub use log::LogCommand;
+pub use custom::CustomCommand; 
\ No newline at end of file
@@ -0,0 +1,511 @@
+//! Release 命令实现
+//!
+//! 根据已有的版本标签计算下一个语义化版本（`auto` 时根据上一个版本之后的约定式提交决定），
+//! 更新配置中列出的文件里的版本号并提交，创建以更新日志为说明的注释标签，
+//! 最后推送提交和标签。
+
+use crate::changelog::Changelog;
+use crate::config::{ConfigManager, RepoConfig};
+use crate::error::{GtResult, GtError};
+use crate::forge;
+use crate::git::{GitOps, NetworkOps};
+use crate::plan::Plan;
+use crate::ui::{emit_json, print_info, print_success, print_warning};
+use crate::workflow::{Step, WorkflowEngine};
+use async_trait::async_trait;
+use clap::ValueEnum;
+use regex::Regex;
+use serde::Serialize;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+/// 版本文件中的版本字段，匹配 `version = "1.2.3"`（TOML）和 `"version": "1.2.3"`（JSON）
+const VERSION_FIELD: &str = r#"(?m)^(\s*"?version"?\s*[:=]\s*")([^"]*)(")"#;
+
+/// 版本号升级方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+    /// 主版本号，不兼容的变更
+    Major,
+    /// 次版本号，新功能
+    Minor,
+    /// 修订号，问题修复
+    Patch,
+    /// 根据约定式提交决定：有不兼容变更时升级主版本号，有 feat 时升级次版本号，否则升级修订号
+    #[default]
+    Auto,
+}
+
+impl Bump {
+    /// 根据更新日志决定升级方式
+    fn infer(changelog: &Changelog) -> Self {
+        if !changelog.breaking.is_empty() {
+            Bump::Major
+        } else if changelog.groups.iter().any(|group| group.kind == "feat") {
+            Bump::Minor
+        } else {
+            Bump::Patch
+        }
+    }
+}
+
+impl fmt::Display for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bump::Major => write!(f, "major"),
+            Bump::Minor => write!(f, "minor"),
+            Bump::Patch => write!(f, "patch"),
+            Bump::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+/// 语义化版本号，不支持预发布和构建元数据
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    /// 解析 `1.2.3`，格式不符时返回 None
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.split('.').map(|part| part.parse::<u64>().ok());
+        let version = Self {
+            major: parts.next()??,
+            minor: parts.next()??,
+            patch: parts.next()??,
+        };
+        parts.next().is_none().then_some(version)
+    }
+
+    /// 升级版本号，`Bump::Auto` 需要先换成具体的升级方式
+    pub fn bump(self, bump: Bump) -> Self {
+        match bump {
+            Bump::Major => Self { major: self.major + 1, minor: 0, patch: 0 },
+            Bump::Minor => Self { minor: self.minor + 1, patch: 0, ..self },
+            Bump::Patch | Bump::Auto => Self { patch: self.patch + 1, ..self },
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Release 的结果，`--format json` 时作为输出
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseReport {
+    /// 新版本号
+    pub version: String,
+    /// 新标签
+    pub tag: String,
+    /// 上一个版本的标签
+    pub previous_tag: Option<String>,
+    /// 实际的升级方式
+    pub bump: Bump,
+    /// 更新了版本号的文件
+    pub files: Vec<String>,
+    /// 是否已推送
+    pub pushed: bool,
+    /// 标签中的更新日志
+    pub changelog: Changelog,
+}
+
+/// Release 命令
+pub struct ReleaseCommand {
+    bump: Bump,
+    /// 只在本地提交和打标签
+    no_push: bool,
+}
+
+impl ReleaseCommand {
+    /// 创建新的 Release 命令
+    pub fn new(bump: Bump, no_push: bool) -> Self {
+        Self { bump, no_push }
+    }
+
+    /// 执行命令
+    pub async fn execute(self) -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+        let config_manager = ConfigManager::new(git_ops.repository())?;
+        let config = config_manager.repo_config();
+
+        if !git_ops.is_clean()? {
+            return Err(GtError::DirtyWorkingDirectory);
+        }
+        let branch = git_ops.current_branch()?;
+        if branch != config.main_branch {
+            print_warning(&format!("当前在分支 '{}' 上发布，而不是主分支 '{}'", branch, config.main_branch));
+        }
+
+        let release = self.prepare(&git_ops, config)?;
+        print_info(&format!(
+            "{} → {} ({})",
+            release.previous_tag.as_deref().unwrap_or("无版本"), release.tag, release.bump
+        ));
+
+        let mut workflow = self.workflow(config);
+        let mut ctx = ReleaseContext { git_ops: &git_ops, config, branch, release };
+        workflow.run(&mut ctx).await?;
+
+        let release = ctx.release;
+        emit_json(&ReleaseReport {
+            version: release.version.to_string(),
+            tag: release.tag.clone(),
+            previous_tag: release.previous_tag,
+            bump: release.bump,
+            files: config.version_files.clone(),
+            pushed: !self.no_push,
+            changelog: release.changelog,
+        });
+        print_success(&format!("🏷️ 已发布 {}", release.tag));
+        Ok(())
+    }
+
+    /// 生成执行计划，不修改仓库
+    pub fn plan(&self) -> GtResult<Plan> {
+        let git_ops = GitOps::new()?;
+        let config_manager = ConfigManager::new(git_ops.repository())?;
+        let config = config_manager.repo_config();
+
+        let ctx = ReleaseContext {
+            git_ops: &git_ops,
+            config,
+            branch: git_ops.current_branch()?,
+            release: self.prepare(&git_ops, config)?,
+        };
+        let plan = self.workflow(config).plan(&ctx, 0);
+        Ok(plan)
+    }
+
+    /// 发布的步骤，没有配置版本文件时不创建提交
+    fn workflow<'a>(&self, config: &RepoConfig) -> WorkflowEngine<'a, ReleaseContext<'a>> {
+        let mut workflow = WorkflowEngine::new("release");
+        if !config.version_files.is_empty() {
+            workflow = workflow
+                .step(UpdateVersionFiles::default())
+                .step(CommitRelease::default());
+        }
+        workflow = workflow.step(CreateTag::default());
+        if !self.no_push {
+            workflow = workflow.step(PushRelease);
+        }
+        workflow
+    }
+
+    /// 找到上一个版本，计算新版本并生成更新日志
+    fn prepare(&self, git_ops: &GitOps, config: &RepoConfig) -> GtResult<Release> {
+        let repo = git_ops.repository();
+        let previous = latest_version(&git_ops.list_tags()?, &config.tag_prefix);
+        let previous_tag = previous.as_ref().map(|(tag, _)| tag.clone());
+
+        // 没有配置远程时不生成 PR 链接
+        let forge = forge::from_config(config, repo, false).ok();
+        let changelog = Changelog::generate(repo, previous_tag.as_deref(), "HEAD", String::new(), forge.as_deref())?;
+        if changelog.is_empty() {
+            return Err(GtError::PreconditionFailed {
+                condition: format!("{} 之后没有新的提交", previous_tag.as_deref().unwrap_or("HEAD")),
+            });
+        }
+
+        let bump = match self.bump {
+            Bump::Auto => Bump::infer(&changelog),
+            bump => bump,
+        };
+        let version = previous.map(|(_, version)| version).unwrap_or_default().bump(bump);
+        let tag = format!("{}{}", config.tag_prefix, version);
+        if repo.tag_exists(&tag) {
+            return Err(GtError::PreconditionFailed {
+                condition: format!("标签 '{}' 已存在", tag),
+            });
+        }
+
+        let changelog = Changelog { title: tag.clone(), ..changelog };
+        Ok(Release { version, tag, previous_tag, bump, changelog })
+    }
+}
+
+/// 前缀为 prefix 的标签中版本号最高的一个，跳过预发布等无法解析的标签
+fn latest_version(tags: &[String], prefix: &str) -> Option<(String, Version)> {
+    tags.iter()
+        .filter_map(|tag| Some((tag.clone(), Version::parse(tag.strip_prefix(prefix)?)?)))
+        .max_by_key(|(_, version)| *version)
+}
+
+/// 把第一个版本字段（如 Cargo.toml 中 [package] 的版本）改为 version，没有版本字段时返回 None
+fn replace_version(content: &str, version: &str) -> Option<String> {
+    let pattern = Regex::new(VERSION_FIELD).expect("版本字段的正则表达式有效");
+    pattern.is_match(content).then(|| {
+        pattern.replacen(content, 1, |caps: &regex::Captures| format!("{}{}{}", &caps[1], version, &caps[3]))
+            .into_owned()
+    })
+}
+
+/// 计算出的新版本
+struct Release {
+    version: Version,
+    tag: String,
+    previous_tag: Option<String>,
+    bump: Bump,
+    changelog: Changelog,
+}
+
+/// 在步骤之间共享的上下文
+struct ReleaseContext<'a> {
+    git_ops: &'a GitOps,
+    config: &'a RepoConfig,
+    /// 发布所在的分支
+    branch: String,
+    release: Release,
+}
+
+impl ReleaseContext<'_> {
+    /// 版本文件在工作区中的路径
+    fn version_file(&self, file: &str) -> PathBuf {
+        self.git_ops.repository().path().join(file)
+    }
+}
+
+/// 更新版本文件中的版本号，回滚时恢复原来的内容
+#[derive(Default)]
+struct UpdateVersionFiles {
+    /// 修改前的文件内容
+    originals: Vec<(PathBuf, String)>,
+}
+
+#[async_trait(?Send)]
+impl<'a> Step<ReleaseContext<'a>> for UpdateVersionFiles {
+    fn description(&self, ctx: &ReleaseContext<'a>) -> String {
+        format!("把 {} 中的版本号改为 {}", ctx.config.version_files.join(", "), ctx.release.version)
+    }
+
+    fn check(&self, ctx: &ReleaseContext<'a>) -> GtResult<()> {
+        let missing: Vec<&str> = ctx.config.version_files.iter()
+            .filter(|file| !ctx.version_file(file).is_file())
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(GtError::ConfigError {
+                message: format!("release.version_files 中的文件不存在: {}", missing.join(", ")),
+            });
+        }
+        Ok(())
+    }
+
+    async fn run(&mut self, ctx: &mut ReleaseContext<'a>) -> GtResult<()> {
+        let version = ctx.release.version.to_string();
+        for file in &ctx.config.version_files {
+            let path = ctx.version_file(file);
+            let content = fs::read_to_string(&path).map_err(|e| GtError::IoError {
+                operation: format!("读取 {}", path.display()),
+                error: e.to_string(),
+            })?;
+
+            let updated = replace_version(&content, &version).ok_or_else(|| GtError::ConfigError {
+                message: format!("{} 中没有找到版本字段", file),
+            })?;
+
+            self.originals.push((path.clone(), content.clone()));
+            fs::write(&path, updated).map_err(|e| GtError::IoError {
+                operation: format!("写入 {}", path.display()),
+                error: e.to_string(),
+            })?;
+        }
+        Ok(())
+    }
+
+    fn rollback(&mut self, _ctx: &mut ReleaseContext<'a>) -> GtResult<()> {
+        for (path, content) in self.originals.drain(..) {
+            fs::write(&path, content).map_err(|e| GtError::IoError {
+                operation: format!("恢复 {}", path.display()),
+                error: e.to_string(),
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// 提交版本文件，回滚时撤销提交并取消暂存
+#[derive(Default)]
+struct CommitRelease {
+    /// 提交前分支指向的提交
+    head: Option<String>,
+}
+
+#[async_trait(?Send)]
+impl<'a> Step<ReleaseContext<'a>> for CommitRelease {
+    fn description(&self, ctx: &ReleaseContext<'a>) -> String {
+        format!("提交 \"{}\"", release_message(ctx))
+    }
+
+    async fn run(&mut self, ctx: &mut ReleaseContext<'a>) -> GtResult<()> {
+        self.head = Some(ctx.git_ops.repository().branch_oid(&ctx.branch)?);
+        let files: Vec<&str> = ctx.config.version_files.iter().map(String::as_str).collect();
+        ctx.git_ops.add_files(&files)?;
+        ctx.git_ops.create_commit(&release_message(ctx))
+    }
+
+    fn rollback(&mut self, ctx: &mut ReleaseContext<'a>) -> GtResult<()> {
+        let Some(head) = self.head.take() else {
+            return Ok(());
+        };
+        ctx.git_ops.reset_mixed(&head)
+    }
+}
+
+/// 发布提交的提交信息
+fn release_message(ctx: &ReleaseContext<'_>) -> String {
+    format!("chore(release): {}", ctx.release.tag)
+}
+
+/// 创建注释标签，回滚时删除
+#[derive(Default)]
+struct CreateTag {
+    created: bool,
+}
+
+#[async_trait(?Send)]
+impl<'a> Step<ReleaseContext<'a>> for CreateTag {
+    fn description(&self, ctx: &ReleaseContext<'a>) -> String {
+        format!("创建标签 '{}'", ctx.release.tag)
+    }
+
+    async fn run(&mut self, ctx: &mut ReleaseContext<'a>) -> GtResult<()> {
+        let notes = ctx.release.changelog.to_markdown();
+        ctx.git_ops.create_annotated_tag(&ctx.release.tag, &notes, None)?;
+        self.created = true;
+        Ok(())
+    }
+
+    fn rollback(&mut self, ctx: &mut ReleaseContext<'a>) -> GtResult<()> {
+        if std::mem::take(&mut self.created) {
+            ctx.git_ops.delete_tag(&ctx.release.tag)?;
+        }
+        Ok(())
+    }
+}
+
+/// 推送发布提交和标签
+struct PushRelease;
+
+#[async_trait(?Send)]
+impl<'a> Step<ReleaseContext<'a>> for PushRelease {
+    fn description(&self, ctx: &ReleaseContext<'a>) -> String {
+        format!("推送分支 '{}' 和标签 '{}' 到 '{}'", ctx.branch, ctx.release.tag, ctx.config.remote_name)
+    }
+
+    async fn run(&mut self, ctx: &mut ReleaseContext<'a>) -> GtResult<()> {
+        let network = NetworkOps::new(ctx.config.network_config());
+        let repo = ctx.git_ops.repository();
+        network.push_with_retry(repo, &ctx.config.remote_name, Some(&ctx.branch))?;
+        network.push_tag_with_retry(repo, &ctx.config.remote_name, &ctx.release.tag)
+    }
+
+    fn reversible(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::changelog::{ChangelogEntry, ScopeGroup, TypeGroup};
+
+    fn tags(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_accepts_only_plain_versions() {
+        assert_eq!(Version::parse("1.20.3"), Some(Version { major: 1, minor: 20, patch: 3 }));
+        for text in ["1.2", "1.2.3.4", "1.2.3-rc.1", "v1.2.3", "1..3", ""] {
+            assert_eq!(Version::parse(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn bump_resets_lower_parts() {
+        let version = Version { major: 1, minor: 4, patch: 7 };
+        assert_eq!(version.bump(Bump::Major).to_string(), "2.0.0");
+        assert_eq!(version.bump(Bump::Minor).to_string(), "1.5.0");
+        assert_eq!(version.bump(Bump::Patch).to_string(), "1.4.8");
+        // 没有上一个版本时从 0.0.0 开始
+        assert_eq!(Version::default().bump(Bump::Minor).to_string(), "0.1.0");
+    }
+
+    #[test]
+    fn latest_version_skips_other_tags() {
+        let names = tags(&["v1.2.0", "v1.10.0", "v1.9.9", "v2.0.0-rc.1", "1.11.0", "nightly"]);
+        let (tag, version) = latest_version(&names, "v").unwrap();
+        assert_eq!(tag, "v1.10.0");
+        assert_eq!(version, Version { major: 1, minor: 10, patch: 0 });
+
+        let (tag, _) = latest_version(&names, "").unwrap();
+        assert_eq!(tag, "1.11.0");
+        assert_eq!(latest_version(&tags(&["release-1", "v2.0.0-rc.1"]), "v"), None);
+        assert_eq!(latest_version(&[], "v"), None);
+    }
+
+    /// 只包含给定类型变更的更新日志
+    fn changelog(kinds: &[&str], breaking: bool) -> Changelog {
+        let entry = |kind: &str| ChangelogEntry {
+            commit: "abc1234".to_string(),
+            kind: Some(kind.to_string()),
+            scope: None,
+            description: "变更".to_string(),
+            breaking,
+            breaking_note: None,
+            pr_number: None,
+            pr_url: None,
+        };
+        Changelog {
+            title: String::new(),
+            date: String::new(),
+            from: None,
+            to: "HEAD".to_string(),
+            breaking: if breaking { vec![entry(kinds[0])] } else { Vec::new() },
+            groups: kinds.iter()
+                .map(|kind| TypeGroup {
+                    kind: kind.to_string(),
+                    title: String::new(),
+                    scopes: vec![ScopeGroup { scope: None, entries: vec![entry(kind)] }],
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn infer_uses_conventional_types() {
+        assert_eq!(Bump::infer(&changelog(&["fix"], true)), Bump::Major);
+        assert_eq!(Bump::infer(&changelog(&["feat", "fix"], false)), Bump::Minor);
+        assert_eq!(Bump::infer(&changelog(&["fix", "docs"], false)), Bump::Patch);
+    }
+
+    #[test]
+    fn replace_version_changes_only_first_field() {
+        let cargo = concat!(
+            "[package]\n",
+            "name = \"gt\"\n",
+            "version = \"0.3.1\"\n",
+            "\n",
+            "[dependencies]\n",
+            "serde = { version = \"1.0\" }\n",
+            "\n",
+            "[dependencies.regex]\n",
+            "version = \"1.10\"\n",
+        );
+        let updated = replace_version(cargo, "0.4.0").unwrap();
+        assert_eq!(updated, cargo.replace("version = \"0.3.1\"", "version = \"0.4.0\""));
+
+        let package = "{\n  \"name\": \"gt\",\n  \"version\": \"1.0.0\"\n}\n";
+        assert_eq!(replace_version(package, "1.1.0").unwrap(), package.replace("1.0.0", "1.1.0"));
+
+        assert_eq!(replace_version("[package]\nname = \"gt\"\n", "1.0.0"), None);
+    }
+}
@@ -0,0 +1,331 @@
+//! Rm 命令实现
+//!
+//! 删除已合并的分支及其远程分支。除了普通合并，也能识别 squash 合并和 rebase 合并，
+//! 还可以查询代码托管平台上已合并的 PR（只有分支最新提交已包含在 PR 中时才算合并）。
+//! 删除前先列出要删除的分支并确认。
+
+use crate::config::{ConfigManager, RepoConfig};
+use crate::error::{GtResult, GtError};
+use crate::forge;
+use crate::git::{GitOps, MergeKind, Repository, Stack};
+use crate::git::network::NetworkOps;
+use crate::github::pr::PullRequest;
+use crate::plan::Plan;
+use crate::ui::{confirm_action, emit_json, is_json, print_info, print_success, print_warning};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// 删除所有已合并分支时使用的分支名
+const ALL: &str = "all";
+
+/// 查询已合并 PR 的数量上限
+const PR_LIMIT: u32 = 100;
+
+/// 要删除的分支
+#[derive(Debug, Clone, Serialize)]
+pub struct RemovedBranch {
+    /// 本地分支名
+    pub name: String,
+    /// 合并方式，`-f` 强制删除未合并的分支时为 None
+    pub merged: Option<MergeKind>,
+    /// 要一起删除的远程分支，如 `origin/feature/login`
+    pub remote_branch: Option<String>,
+    /// 远程分支是否已删除
+    pub remote_deleted: bool,
+    /// 删除本地分支失败的原因，删除成功时为 None
+    pub error: Option<String>,
+}
+
+/// Rm 的结果，`--format json` 时作为输出
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RmReport {
+    pub branches: Vec<RemovedBranch>,
+}
+
+/// Rm 命令
+pub struct RmCommand {
+    /// 分支名，或 `all`
+    branch: String,
+    /// 删除未合并的分支
+    force: bool,
+    /// 查询代码托管平台上已合并的 PR
+    pr: bool,
+    /// 只删除本地分支
+    local: bool,
+    /// 不确认
+    yes: bool,
+}
+
+impl RmCommand {
+    /// 创建新的 Rm 命令
+    pub fn new(branch: String, force: bool) -> Self {
+        Self { branch, force, pr: false, local: false, yes: false }
+    }
+
+    /// 同时查询已合并的 PR
+    pub fn pr(mut self, pr: bool) -> Self {
+        self.pr = pr;
+        self
+    }
+
+    /// 只删除本地分支
+    pub fn local(mut self, local: bool) -> Self {
+        self.local = local;
+        self
+    }
+
+    /// 不确认直接删除
+    pub fn yes(mut self, yes: bool) -> Self {
+        self.yes = yes;
+        self
+    }
+
+    /// 执行命令
+    pub async fn execute(self) -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+        let config_manager = ConfigManager::load(git_ops.repository())?;
+        let config = config_manager.repo_config();
+        let repo = git_ops.repository();
+
+        let mut report = RmReport { branches: self.candidates(repo, config).await? };
+        if report.branches.is_empty() {
+            emit_json(&report);
+            print_info("没有已合并的分支需要删除");
+            return Ok(());
+        }
+
+        if !is_json() {
+            println!("将删除以下分支:");
+            for branch in &report.branches {
+                println!("  {}", describe(branch));
+            }
+        }
+        if !self.yes && !confirm_action(&format!("删除以上 {} 个分支？", report.branches.len()), false) {
+            return Err(GtError::UserCancelled);
+        }
+
+        let network = NetworkOps::new(config.network_config());
+        let mut stack = Stack::load(repo)?;
+        for branch in &mut report.branches {
+            // 删除失败时继续删除其他分支，最后统一报告，已删除分支的栈信息仍然需要保存
+            if let Err(e) = repo.delete_branch(&branch.name, true) {
+                print_warning(&format!("删除分支 '{}' 失败: {}", branch.name, e));
+                branch.error = Some(e.to_string());
+                continue;
+            }
+            // 子分支改为挂到被删除分支的父分支上
+            stack.remove(&branch.name);
+
+            if branch.remote_branch.is_some() {
+                match network.delete_remote_branch_with_retry(repo, &config.remote_name, &branch.name) {
+                    Ok(()) => branch.remote_deleted = true,
+                    Err(e) => print_warning(&format!("删除远程分支 '{}' 失败: {}", branch.name, e)),
+                }
+            }
+        }
+        stack.save(repo)?;
+
+        emit_json(&report);
+        let failed: Vec<&str> = report.branches.iter()
+            .filter(|branch| branch.error.is_some())
+            .map(|branch| branch.name.as_str())
+            .collect();
+        let deleted = report.branches.len() - failed.len();
+        let remote_deleted = report.branches.iter().filter(|branch| branch.remote_deleted).count();
+        if deleted > 0 {
+            print_success(&format!("已删除 {} 个本地分支和 {} 个远程分支", deleted, remote_deleted));
+        }
+
+        if !failed.is_empty() {
+            return Err(GtError::GitOperation {
+                message: format!("以下分支删除失败: {}", failed.join("、"))
+            });
+        }
+        Ok(())
+    }
+
+    /// 生成执行计划，不删除分支
+    pub async fn plan(&self) -> GtResult<Plan> {
+        let git_ops = GitOps::new()?;
+        let config_manager = ConfigManager::load(git_ops.repository())?;
+        let config = config_manager.repo_config();
+
+        let mut plan = Plan::new("rm");
+        for branch in self.candidates(git_ops.repository(), config).await? {
+            plan.step(format!("删除本地分支 {}", describe(&branch)));
+            if branch.remote_branch.is_some() {
+                plan.step(format!("push {} :refs/heads/{}", config.remote_name, branch.name));
+            }
+        }
+        Ok(plan)
+    }
+
+    /// 找出要删除的分支
+    ///
+    /// 指定分支时，受保护的分支、当前分支和（未使用 `-f` 时）未合并的分支都会报错；
+    /// `all` 时只选择已合并的分支，跳过受保护的分支和当前分支。
+    async fn candidates(&self, repo: &Repository, config: &RepoConfig) -> GtResult<Vec<RemovedBranch>> {
+        let current = repo.current_branch().ok();
+        let targets = merge_targets(repo, config)?;
+        let merged_prs = if self.pr { merged_pull_requests(repo, config).await } else { HashMap::new() };
+
+        let names = if self.branch == ALL {
+            repo.list_branches()?.into_iter()
+                .map(|branch| branch.name)
+                .filter(|name| Some(name) != current.as_ref() && !config.is_protected_branch(name))
+                .collect()
+        } else {
+            if !repo.branch_exists(&self.branch)? {
+                return Err(GtError::BranchNotFound { branch: self.branch.clone() });
+            }
+            if config.is_protected_branch(&self.branch) {
+                return Err(GtError::PreconditionFailed {
+                    condition: format!("分支 '{}' 受保护，不能删除", self.branch)
+                });
+            }
+            if current.as_deref() == Some(self.branch.as_str()) {
+                return Err(GtError::PreconditionFailed {
+                    condition: format!("不能删除当前分支 '{}'，请先切换到其他分支", self.branch)
+                });
+            }
+            vec![self.branch.clone()]
+        };
+
+        let mut branches = Vec::new();
+        for name in names {
+            let merged = match merge_kind(repo, &name, &targets)? {
+                Some(kind) => Some(kind),
+                None if merged_by_pull_request(repo, &name, &merged_prs)? => Some(MergeKind::PullRequest),
+                None => None,
+            };
+            if merged.is_none() {
+                if self.branch == ALL {
+                    continue;
+                }
+                if !self.force {
+                    return Err(GtError::PreconditionFailed {
+                        condition: format!("分支 '{}' 尚未合并到 {}，使用 -f 强制删除", name, config.main_branch)
+                    });
+                }
+            }
+
+            let remote_branch = (!self.local && repo.remote_branch_exists(&config.remote_name, &name)?)
+                .then(|| format!("{}/{}", config.remote_name, name));
+            branches.push(RemovedBranch { name, merged, remote_branch, remote_deleted: false, error: None });
+        }
+        Ok(branches)
+    }
+}
+
+/// 判断合并时比较的分支：本地主分支，以及与它不同的远程主分支
+fn merge_targets(repo: &Repository, config: &RepoConfig) -> GtResult<Vec<String>> {
+    let mut targets = vec![config.main_branch.clone()];
+    if repo.remote_branch_exists(&config.remote_name, &config.main_branch)? {
+        let remote_main = format!("{}/{}", config.remote_name, config.main_branch);
+        let oid = |name: &str| repo.inner().revparse_single(name).map(|object| object.id());
+        if oid(&remote_main)? != oid(&config.main_branch)? {
+            targets.push(remote_main);
+        }
+    }
+    Ok(targets)
+}
+
+/// 分支合并到任一目标分支的方式
+fn merge_kind(repo: &Repository, branch: &str, targets: &[String]) -> GtResult<Option<MergeKind>> {
+    for target in targets {
+        if let Some(kind) = repo.merge_kind(branch, target)? {
+            return Ok(Some(kind));
+        }
+    }
+    Ok(None)
+}
+
+/// 分支是否通过已合并的 PR 合并
+///
+/// 同名分支可能被重用，PR 合并后也可能又有新提交，所以只有分支最新提交就是 PR 的最新提交
+/// 或者是它的祖先时才算合并；本地没有 PR 的提交时无法确认，视为未合并。
+fn merged_by_pull_request(repo: &Repository, branch: &str, merged_prs: &HashMap<String, Vec<git2::Oid>>) -> GtResult<bool> {
+    let Some(heads) = merged_prs.get(branch) else {
+        return Ok(false);
+    };
+    let git = repo.inner();
+    let tip = git.revparse_single(&format!("refs/heads/{}", branch))?.peel_to_commit()?.id();
+    Ok(heads.iter().any(|&head| head == tip || git.graph_descendant_of(head, tip).unwrap_or(false)))
+}
+
+/// 代码托管平台上已合并的 PR 的源分支及其最新提交，查询失败时只给出警告
+async fn merged_pull_requests(repo: &Repository, config: &RepoConfig) -> HashMap<String, Vec<git2::Oid>> {
+    let result = match forge::from_config(config, repo, false) {
+        Ok(forge) if forge.is_available() => forge.list_change_requests(Some("merged"), Some(PR_LIMIT)).await,
+        Ok(forge) => {
+            print_warning(&format!("{} 后端不可用，只根据提交历史判断是否已合并", forge.kind()));
+            return HashMap::new();
+        }
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(requests) => {
+            let mut heads: HashMap<String, Vec<git2::Oid>> = HashMap::new();
+            for request in requests {
+                let PullRequest { head_branch, head_sha, .. } = request;
+                if let Some(oid) = head_sha.and_then(|sha| git2::Oid::from_str(&sha).ok()) {
+                    heads.entry(head_branch).or_default().push(oid);
+                }
+            }
+            heads
+        }
+        Err(e) => {
+            print_warning(&format!("查询已合并的 PR 失败，只根据提交历史判断: {}", e));
+            HashMap::new()
+        }
+    }
+}
+
+/// 分支名、合并方式和远程分支
+fn describe(branch: &RemovedBranch) -> String {
+    let merged = branch.merged.map_or("未合并", MergeKind::description);
+    match branch.remote_branch {
+        Some(ref remote_branch) => format!("{}（{}，远程 {}）", branch.name, merged, remote_branch),
+        None => format!("{}（{}）", branch.name, merged),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 在 HEAD 上提交一个空改动，返回新提交
+    fn commit(git: &git2::Repository, message: &str) -> git2::Oid {
+        let signature = git2::Signature::now("gt", "gt@example.com").unwrap();
+        let tree = git.find_tree(git.index().unwrap().write_tree().unwrap()).unwrap();
+        let parent = git.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        git.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents).unwrap()
+    }
+
+    #[test]
+    fn pull_request_must_contain_branch_tip() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = git2::Repository::init(dir.path()).unwrap();
+        let first = commit(&git, "first");
+        let second = commit(&git, "second");
+        git.branch("feature/login", &git.find_commit(first).unwrap(), false).unwrap();
+        let repo = Repository::open_path(dir.path()).unwrap();
+
+        // PR 的最新提交就是分支最新提交，或者包含分支最新提交
+        for head in [first, second] {
+            let prs = HashMap::from([("feature/login".to_string(), vec![head])]);
+            assert!(merged_by_pull_request(&repo, "feature/login", &prs).unwrap());
+        }
+
+        // PR 合并后分支上又有新提交
+        git.set_head("refs/heads/feature/login").unwrap();
+        commit(&git, "after merge");
+        let prs = HashMap::from([("feature/login".to_string(), vec![first])]);
+        assert!(!merged_by_pull_request(&repo, "feature/login", &prs).unwrap());
+
+        // 没有同名分支的 PR
+        assert!(!merged_by_pull_request(&repo, "feature/login", &HashMap::new()).unwrap());
+    }
+}
@@ -4,8 +4,14 @@
 //! 增强功能：智能文件选择、交互式提交、编辑器集成
 
 use crate::error::{GtResult, GtError};
-use crate::git::GitOps;
-use crate::ui::{print_step, print_success, print_warning, print_info, confirm_action, prompt_input};
+use crate::config::{ConfigManager, RepoConfig};
+use crate::commands::add::stage_patches;
+use crate::git::{ConventionalCommit, Footer, GitOps, Restacker, Stack, COMMIT_TYPES};
+use crate::hooks::{Hook, HookContext};
+use crate::plan::Plan;
+use crate::ui::{print_step, print_success, print_warning, print_info, confirm_action, prompt_input, select_option, Editor};
+use std::fs;
+use std::path::Path;
 
 /// Save 命令选项
 #[derive(Debug, Clone)]
@@ -18,6 +24,10 @@ pub struct SaveOptions {
     pub files: Vec<String>,
     /// 是否添加所有文件
     pub add_all: bool,
+    /// 是否使用约定式提交
+    pub conventional: bool,
+    /// 逐个选择要保存的 hunk
+    pub patch: bool,
 }
 
 impl Default for SaveOptions {
@@ -27,6 +37,8 @@ impl Default for SaveOptions {
             edit: false,
             files: Vec::new(),
             add_all: true,
+            conventional: false,
+            patch: false,
         }
     }
 }
@@ -45,10 +57,25 @@ impl SaveCommand {
             edit,
             files,
             add_all,
+            conventional: false,
+            patch: false,
         };
         Self { options }
     }
     
+    /// 使用约定式提交（配置中的 commit.conventional 也会启用）
+    pub fn conventional(mut self, conventional: bool) -> Self {
+        self.options.conventional = conventional;
+        self
+    }
+    
+    /// 逐个选择要保存的 hunk，不自动添加所有变更
+    pub fn patch(mut self, patch: bool) -> Self {
+        self.options.patch = patch;
+        self.options.add_all = self.options.add_all && !patch;
+        self
+    }
+    
     /// 执行命令
     pub async fn execute(self) -> GtResult<()> {
         print_step("开始保存当前工作...");
@@ -60,13 +87,23 @@ impl SaveCommand {
             return Err(GtError::NotGitRepository);
         }
         
-        // 2. 添加文件到暂存区
-        self.add_files(&git_ops)?;
+        // 2. 校验提交信息后运行 pre-save 钩子，钩子修改的文件会一起保存
+        let config_manager = ConfigManager::load(git_ops.repository())?;
+        let config = config_manager.repo_config();
+        let conventional = self.options.conventional || config.conventional_commits;
+        self.validate_message(conventional)?;
+        let base = Self::base_branch(&git_ops, config)?;
+        HookContext::collect(git_ops.repository(), Some(&base)).run(config, Hook::PreSave)?;
+        
+        // 3. 添加文件到暂存区
+        self.add_files(&git_ops, config)?;
         
-        // 3. 检查是否有暂存的变更
+        // 4. 检查是否有暂存的变更
         let status = git_ops.check_status()?;
         if !status.has_staged_changes {
-            if self.options.add_all {
+            if self.options.patch {
+                print_warning("没有选择任何 hunk");
+            } else if self.options.add_all {
                 print_warning("没有检测到需要保存的变更");
             } else {
                 print_warning("指定的文件没有变更或未能添加到暂存区");
@@ -74,16 +111,113 @@ impl SaveCommand {
             return Ok(());
         }
         
-        // 4. 提交变更
-        self.commit_changes(&git_ops)?;
+        // 5. 提交变更
+        self.commit_changes(&git_ops, config, conventional)?;
         
         print_success("变更已成功保存！");
+        
+        // 6. 把依赖当前分支的栈分支移到新的提交上
+        self.restack_descendants(&git_ops)?;
+        
+        // 7. 运行 post-save 钩子
+        HookContext::collect(git_ops.repository(), Some(&base)).run(config, Hook::PostSave)
+    }
+    
+    /// 生成执行计划，不修改仓库
+    pub fn plan(&self) -> GtResult<Plan> {
+        let git_ops = GitOps::new()?;
+        let config_manager = ConfigManager::load(git_ops.repository())?;
+        let config = config_manager.repo_config();
+        let conventional = self.options.conventional || config.conventional_commits;
+        self.validate_message(conventional)?;
+        let mut plan = Plan::new("save");
+        Hook::PreSave.plan(&mut plan, config);
+        
+        if self.options.patch {
+            let paths: Vec<&str> = self.options.files.iter().map(String::as_str).collect();
+            let patches = git_ops.unstaged_patches(&paths)?;
+            if patches.is_empty() {
+                return Ok(plan);
+            }
+            let hunks: usize = patches.iter().map(|patch| patch.hunks.len()).sum();
+            plan.step(format!("逐个选择 {} 个文件中的 {} 个 hunk 并暂存", patches.len(), hunks));
+        } else if self.options.add_all {
+            let changed = git_ops.changed_paths()?;
+            if changed.is_empty() {
+                return Ok(plan);
+            }
+            plan.step(format!("add 所有变更 ({} 个文件)", changed.len()));
+        } else {
+            plan.step(format!("add {}", self.options.files.join(" ")));
+        }
+        
+        plan.step(match self.options.message {
+            Some(ref msg) if !self.options.edit => format!("commit -m \"{}\"", msg),
+            _ if self.options.edit => "在编辑器中编辑提交信息后 commit".to_string(),
+            _ if conventional => "按约定式提交规范逐项输入提交信息后 commit".to_string(),
+            _ => "交互式输入提交信息后 commit".to_string(),
+        });
+        
+        let current_branch = git_ops.current_branch()?;
+        let descendants = Stack::load(git_ops.repository())?.descendants_of(&current_branch);
+        if !descendants.is_empty() {
+            plan.step(format!("restack 依赖 '{}' 的分支: {}", current_branch, descendants.join(", ")));
+        }
+        Hook::PostSave.plan(&mut plan, config);
+        
+        Ok(plan)
+    }
+    
+    /// 约定式提交模式下校验 `-m` 提供的提交信息（`-e` 时可以在确认时修改）
+    fn validate_message(&self, conventional: bool) -> GtResult<()> {
+        match self.options.message {
+            Some(ref msg) if conventional && !self.options.edit => ConventionalCommit::parse(msg).map(|_| ()),
+            _ => Ok(()),
+        }
+    }
+    
+    /// 当前分支的基础分支：分支栈中的父分支，否则为主分支
+    fn base_branch(git_ops: &GitOps, config: &RepoConfig) -> GtResult<String> {
+        let stack = Stack::load(git_ops.repository())?;
+        let parent = git_ops.current_branch().ok()
+            .and_then(|branch| stack.parent_of(&branch).map(str::to_string));
+        Ok(parent.unwrap_or_else(|| config.main_branch.clone()))
+    }
+    
+    /// restack 当前分支的所有后代分支
+    fn restack_descendants(&self, git_ops: &GitOps) -> GtResult<()> {
+        let repo = git_ops.repository();
+        let current_branch = git_ops.current_branch()?;
+        let descendants = Stack::load(repo)?.descendants_of(&current_branch);
+        if descendants.is_empty() {
+            return Ok(());
+        }
+        
+        if !git_ops.is_clean()? {
+            print_warning(&format!("工作区还有未保存的变更，暂不 restack 依赖 '{}' 的 {} 个分支", current_branch, descendants.len()));
+            print_info("清理工作区后运行 'gt stack restack'");
+            return Ok(());
+        }
+        
+        print_step(&format!("restack 依赖 '{}' 的 {} 个分支...", current_branch, descendants.len()));
+        let config_manager = ConfigManager::new(repo)?;
+        let restacker = Restacker::new(repo, &config_manager.repo_config().main_branch);
+        if let Err(e) = restacker.restack(descendants) {
+            if restacker.in_progress() {
+                print_info("请解决冲突并 'git add' 后运行 'gt stack restack --continue'");
+                print_info("或者运行 'gt stack restack --abort' 回滚");
+            }
+            return Err(e);
+        }
         Ok(())
     }
     
     /// 添加文件到暂存区
-    fn add_files(&self, git_ops: &GitOps) -> GtResult<()> {
-        if self.options.add_all {
+    fn add_files(&self, git_ops: &GitOps, config: &RepoConfig) -> GtResult<()> {
+        if self.options.patch {
+            print_step("选择要保存的 hunk...");
+            stage_patches(git_ops, config, &self.options.files)?;
+        } else if self.options.add_all {
             print_step("添加所有变更到暂存区...");
             git_ops.add_all()?;
         } else if !self.options.files.is_empty() {
@@ -100,24 +234,24 @@ impl SaveCommand {
     }
     
     /// 提交变更
-    fn commit_changes(&self, git_ops: &GitOps) -> GtResult<()> {
-        let message = if let Some(ref msg) = self.options.message {
-            if self.options.edit {
-                // 有消息但要求编辑，使用交互式编辑
-                self.get_commit_message_interactive(Some(msg.clone()))?
-            } else {
+    fn commit_changes(&self, git_ops: &GitOps, config: &RepoConfig, conventional: bool) -> GtResult<()> {
+        let message = match self.options.message {
+            Some(ref msg) if !self.options.edit => {
                 // 直接使用提供的消息
                 print_step("使用提供的提交信息进行提交...");
                 msg.clone()
             }
-        } else if self.options.edit {
-            // 没有消息但要求编辑器，使用交互式编辑
-            self.get_commit_message_interactive(None)?
-        } else {
+            // 要求编辑时在编辑器中编辑，提供的消息作为初始内容
+            _ if self.options.edit => self.edit_commit_message(git_ops, config, conventional)?,
             // 交互式输入提交信息
-            self.get_commit_message_interactive(None)?
+            _ => self.get_commit_message_interactive(git_ops, conventional)?,
         };
         
+        // 约定式提交模式下，编辑器中写的信息也需要符合规范
+        if conventional {
+            ConventionalCommit::parse(&message)?;
+        }
+        
         // 执行提交
         print_step("提交变更...");
         git_ops.create_commit(&message)?;
@@ -125,25 +259,59 @@ impl SaveCommand {
         Ok(())
     }
     
+    /// 在编辑器中编辑提交信息，结果为空时取消提交
+    fn edit_commit_message(&self, git_ops: &GitOps, config: &RepoConfig, conventional: bool) -> GtResult<String> {
+        let repo = git_ops.repository();
+        let core_editor = repo.config_value("core.editor")?;
+        let editor = Editor::resolve(config.editor.as_deref(), core_editor.as_deref())?;
+        let template = self.commit_template(git_ops, conventional)?;
+        
+        print_step(&format!("使用 {} 编辑提交信息...", editor.command()));
+        let message = editor.edit(&repo.git_dir().join("COMMIT_EDITMSG"), &template)?;
+        if message.is_empty() {
+            return Err(GtError::EmptyCommitMessage);
+        }
+        Ok(message)
+    }
+    
+    /// 编辑器的初始内容
+    ///
+    /// 开头是提供的消息或 git 的 `commit.template`，后面的注释列出当前分支和要提交的文件。
+    fn commit_template(&self, git_ops: &GitOps, conventional: bool) -> GtResult<String> {
+        let repo = git_ops.repository();
+        let initial = match self.options.message {
+            Some(ref msg) => msg.clone(),
+            None => match repo.config_value("commit.template")? {
+                Some(path) => read_commit_template(repo.path(), &path)?,
+                None => String::new(),
+            },
+        };
+        
+        let branch = git_ops.current_branch().unwrap_or_else(|_| "HEAD".to_string());
+        let mut template = format!("{}\n\n", initial.trim_end());
+        template.push_str("# 请输入提交信息，以 '#' 开头的行会被忽略，提交信息为空时取消提交。\n");
+        if conventional {
+            let kinds: Vec<&str> = COMMIT_TYPES.iter().map(|(kind, _)| *kind).collect();
+            template.push_str("# 约定式提交: <类型>(<范围>): <描述>\n");
+            template.push_str(&format!("# 可用的类型: {}\n", kinds.join(", ")));
+        }
+        template.push_str("#\n");
+        template.push_str(&format!("# 当前分支: {}\n", branch));
+        template.push_str("# 要提交的文件:\n");
+        for path in git_ops.staged_paths()? {
+            template.push_str(&format!("#\t{}\n", path));
+        }
+        Ok(template)
+    }
+    
     /// 获取提交信息（交互式）
-    fn get_commit_message_interactive(&self, initial_message: Option<String>) -> GtResult<String> {
-        if let Some(msg) = initial_message {
-            print_info(&format!("当前提交信息: {}", msg));
-            if confirm_action("是否使用当前信息？", true) {
-                return Ok(msg);
-            }
+    fn get_commit_message_interactive(&self, git_ops: &GitOps, conventional: bool) -> GtResult<String> {
+        if conventional {
+            return self.get_conventional_message(git_ops);
         }
         
         print_info("请输入提交信息 (空行结束):");
-        
-        let mut lines = Vec::new();
-        loop {
-            let line = prompt_input("", None);
-            if line.is_empty() {
-                break;
-            }
-            lines.push(line);
-        }
+        let lines = read_lines();
         
         if lines.is_empty() {
             return Err(GtError::EmptyCommitMessage);
@@ -152,6 +320,86 @@ impl SaveCommand {
         let message = lines.join("\n");
         Ok(message)
     }
+    
+    /// 按约定式提交规范逐项输入提交信息：类型、范围、描述、正文、不兼容变更和脚注
+    fn get_conventional_message(&self, git_ops: &GitOps) -> GtResult<String> {
+        let descriptions: Vec<String> = COMMIT_TYPES.iter()
+            .map(|(kind, description)| format!("{:<9}{}", kind, description))
+            .collect();
+        let options: Vec<(&str, &str)> = COMMIT_TYPES.iter()
+            .zip(&descriptions)
+            .map(|((kind, _), description)| (*kind, description.as_str()))
+            .collect();
+        let index = select_option("选择提交类型:", &options, None).ok_or_else(|| GtError::InvalidInput {
+            input: "没有选择提交类型".to_string()
+        })?;
+        
+        // 根据变更的文件建议范围
+        let paths = if self.options.patch {
+            git_ops.staged_paths()?
+        } else if self.options.add_all {
+            git_ops.changed_paths()?
+        } else {
+            self.options.files.clone()
+        };
+        let suggestion = ConventionalCommit::suggest_scope(&paths);
+        let scope = prompt_input("范围 (可选，输入 - 表示不填)", suggestion.as_deref());
+        
+        let description = prompt_input("简短描述", None);
+        if description.is_empty() {
+            return Err(GtError::EmptyCommitMessage);
+        }
+        
+        let mut commit = ConventionalCommit::new(options[index].0, description);
+        commit.scope = Some(scope).filter(|scope| !scope.is_empty() && scope != "-");
+        
+        print_info("正文 (可选，空行结束):");
+        let body = read_lines();
+        if !body.is_empty() {
+            commit.body = Some(body.join("\n"));
+        }
+        
+        if confirm_action("是否包含不兼容变更 (BREAKING CHANGE)？", false) {
+            commit.breaking = true;
+            let note = prompt_input("不兼容变更说明 (可选)", None);
+            if !note.is_empty() {
+                commit.footers.push(Footer { token: "BREAKING CHANGE".to_string(), value: note });
+            }
+        }
+        
+        print_info("脚注 (可选，如 'Closes #123'、'Reviewed-by: 张三'，空行结束):");
+        for line in read_lines() {
+            let footer = Footer::parse(&line).ok_or_else(|| GtError::InvalidCommitMessage {
+                reason: format!("脚注 '{}' 应为 '<名称>: <内容>' 或 '<名称> #<编号>' 格式", line)
+            })?;
+            commit.footers.push(footer);
+        }
+        
+        print_info(&format!("提交信息: {}", commit.header()));
+        Ok(commit.to_string())
+    }
+}
+
+/// 读取 git 的 `commit.template`，相对路径基于仓库根目录
+fn read_commit_template(workdir: &Path, path: &str) -> GtResult<String> {
+    let path = workdir.join(shellexpand::tilde(path).as_ref());
+    fs::read_to_string(&path).map_err(|e| GtError::IoError {
+        operation: format!("读取提交模板 {}", path.display()),
+        error: e.to_string(),
+    })
+}
+
+/// 逐行读取输入，空行结束
+fn read_lines() -> Vec<String> {
+    let mut lines = Vec::new();
+    loop {
+        let line = prompt_input("", None);
+        if line.is_empty() {
+            break;
+        }
+        lines.push(line);
+    }
+    lines
 }
 
 /// 便捷函数：快速保存所有变更
@@ -2,13 +2,21 @@
 //! 
 //! 对应 gw submit，用于"发货"完成的功能到主分支
 //! 增强功能：智能 PR 创建、自动合并策略、分支清理、GitHub 集成
+//! 分支关联了议题（`gt start #123`）时，PR 使用议题标题并在描述中加上 `Closes #123`
 
 use crate::error::{GtResult, GtError};
-use crate::git::GitOps;
-use crate::config::ConfigManager;
-use crate::ui::{print_step, print_success, print_warning, print_info, confirm_action};
-use crate::git::network::push_with_retry;
-use crate::github::{GithubCli, PullRequestManager, CreatePrOptions, MergePrOptions};
+use crate::git::{GitOps, Stack};
+use crate::config::{ConfigManager, RepoConfig};
+use crate::ui::{emit_json, print_step, print_success, print_warning, print_info, confirm_action};
+use crate::git::NetworkOps;
+use crate::forge::{self, ForgeKind, Issue};
+use crate::github::{CreatePrOptions, MergePrOptions};
+use crate::github::pr::PrState;
+use crate::hooks::{Hook, HookContext};
+use crate::plan::Plan;
+use crate::workflow::{Step, WorkflowEngine};
+use async_trait::async_trait;
+use serde::Serialize;
 
 /// 合并策略
 #[derive(Debug, Clone, Copy)]
@@ -46,8 +54,8 @@ pub struct ShipOptions {
     pub create_pr: bool,
     /// 自动合并 PR
     pub auto_merge: bool,
-    /// 合并策略
-    pub merge_strategy: MergeStrategy,
+    /// 合并策略（None 时使用配置中的默认策略）
+    pub merge_strategy: Option<MergeStrategy>,
     /// 合并后删除分支
     pub delete_branch: bool,
     /// PR 标题（可选）
@@ -62,7 +70,7 @@ impl Default for ShipOptions {
             no_switch: false,
             create_pr: false,
             auto_merge: false,
-            merge_strategy: MergeStrategy::default(),
+            merge_strategy: None,
             delete_branch: false,
             pr_title: None,
             pr_body: None,
@@ -70,6 +78,27 @@ impl Default for ShipOptions {
     }
 }
 
+/// Ship 的结果，`--format json` 时作为输出
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ShipReport {
+    /// 提交的分支
+    pub branch: String,
+    /// PR 的目标分支
+    pub base: Option<String>,
+    /// PR 编号
+    pub pr_number: Option<u32>,
+    /// PR 地址
+    pub pr_url: Option<String>,
+    /// 平台后端不可用时手动创建 PR 的地址
+    pub compare_url: Option<String>,
+    /// PR 是否已合并
+    pub merged: bool,
+    /// 完成后切换到的分支
+    pub switched_to: Option<String>,
+    /// 是否删除了本地分支
+    pub branch_deleted: bool,
+}
+
 /// Ship 命令
 pub struct ShipCommand {
     options: ShipOptions,
@@ -80,17 +109,18 @@ impl ShipCommand {
     pub fn new(
         no_switch: bool, 
         pr: bool, 
+        auto_merge: bool,
         merge_strategy: Option<MergeStrategy>, 
         delete_branch: bool
     ) -> Self {
-        let auto_merge = merge_strategy.is_some();
+        let auto_merge = auto_merge || merge_strategy.is_some();
         let create_pr = pr || auto_merge;
         
         let options = ShipOptions {
             no_switch,
             create_pr,
             auto_merge,
-            merge_strategy: merge_strategy.unwrap_or_default(),
+            merge_strategy,
             delete_branch,
             pr_title: None,
             pr_body: None,
@@ -122,79 +152,145 @@ impl ShipCommand {
         let main_branch = &config.main_branch;
         
         // 3. 检查是否在主分支上
-        if current_branch == *main_branch {
-            self.handle_main_branch_ship(&git_ops, config, main_branch).await?;
+        let report = if current_branch == *main_branch {
+            self.handle_main_branch_ship(&git_ops, config, main_branch).await?
         } else {
-            self.handle_feature_branch_ship(&git_ops, config, &current_branch, main_branch).await?;
-        }
+            self.handle_feature_branch_ship(&git_ops, config, &current_branch, main_branch).await?
+        };
         
+        emit_json(&report);
         print_success("🚀 工作成果已成功提交！");
         Ok(())
     }
     
+    /// 生成执行计划，不修改仓库
+    pub fn plan(&self) -> GtResult<Plan> {
+        let git_ops = GitOps::new()?;
+        let config_manager = ConfigManager::new(git_ops.repository())?;
+        let config = config_manager.repo_config();
+        let repo = git_ops.repository();
+        
+        let current_branch = git_ops.current_branch()?;
+        let main_branch = &config.main_branch;
+        let remote = &config.remote_name;
+        let mut plan = Plan::new("ship");
+        Hook::PreShip.plan(&mut plan, config);
+        
+        if !git_ops.is_clean()? {
+            plan.step(format!("gt save 保存 {} 个文件的变更", git_ops.changed_paths()?.len()));
+        }
+        
+        if current_branch == *main_branch {
+            plan.step(format!("push {} {}", remote, main_branch));
+            Hook::PostShip.plan(&mut plan, config);
+            return Ok(plan);
+        }
+        
+        plan.step(format!("push {} {}", remote, current_branch));
+        
+        if self.options.create_pr {
+            let stack = Stack::load(repo)?;
+            let base = stack.parent_of(&current_branch).unwrap_or(main_branch);
+            let forge = forge::from_config(config, repo, false)?;
+            let request_name = forge.kind().request_name();
+            
+            if forge.is_available() {
+                plan.step(format!("在 {} 上创建 {}: {} → {}", forge.kind(), request_name, current_branch, base));
+                if let Some(issue) = Issue::linked_to(repo, &current_branch)? {
+                    plan.step(format!("在 {} 中引用议题 #{}: {}（{}）", request_name, issue.number, issue.title, issue.closing_reference()));
+                }
+                if self.options.auto_merge {
+                    plan.step(format!("等待检查通过后合并 {} (策略: {})", request_name, self.merge_strategy(config)));
+                }
+            } else {
+                plan.step(format!(
+                    "{} 后端不可用，提示手动创建 {}: {}",
+                    forge.kind(), request_name, forge.compare_url(base, &current_branch)
+                ));
+            }
+        }
+        
+        if !self.options.no_switch {
+            plan.step(format!("checkout {}", main_branch));
+            plan.step(format!("pull --rebase {} {}", remote, main_branch));
+            if self.options.delete_branch {
+                plan.step(format!("删除本地分支 '{}'", current_branch));
+            }
+        }
+        
+        Hook::PostShip.plan(&mut plan, config);
+        Ok(plan)
+    }
+    
     /// 处理在主分支上的 ship 操作
     async fn handle_main_branch_ship(
         &self, 
         git_ops: &GitOps, 
-        config: &crate::config::RepoConfig, 
+        config: &RepoConfig, 
         main_branch: &str
-    ) -> GtResult<()> {
+    ) -> GtResult<ShipReport> {
         print_warning(&format!("您当前在主分支 ({})，ship 命令通常用于功能分支", main_branch));
         
         if !confirm_action("是否仍要继续推送主分支？", false) {
             return Err(GtError::UserCancelled);
         }
         
-        // 检查未提交变更
-        self.check_uncommitted_changes(&git_ops).await?;
+        let repo = git_ops.repository();
+        HookContext::collect(repo, Some(main_branch)).run(config, Hook::PreShip)?;
         
-        // 推送主分支
-        print_step(&format!("推送主分支 '{}' 到远程...", main_branch));
-        push_with_retry(git_ops.repository(), &config.remote_name, Some(main_branch))?;
+        let mut workflow = WorkflowEngine::new("ship")
+            .step(SaveChanges::default())
+            .step(PushBranch);
+        let mut ctx = ShipContext::new(self, git_ops, config, main_branch.to_string(), main_branch.to_string());
+        workflow.run(&mut ctx).await?;
         
-        print_success(&format!("主分支 '{}' 已推送到远程", main_branch));
-        Ok(())
+        HookContext::collect(repo, Some(main_branch)).run(config, Hook::PostShip)?;
+        Ok(ctx.report)
     }
     
     /// 处理功能分支的 ship 操作
     async fn handle_feature_branch_ship(
         &self,
         git_ops: &GitOps,
-        config: &crate::config::RepoConfig,
+        config: &RepoConfig,
         current_branch: &str,
         main_branch: &str,
-    ) -> GtResult<()> {
+    ) -> GtResult<ShipReport> {
         print_info(&format!("准备提交功能分支 '{}' 的工作成果", current_branch));
         
-        // 1. 检查未提交变更
-        self.check_uncommitted_changes(&git_ops).await?;
-        
-        // 2. 推送当前分支
-        self.push_current_branch(&git_ops, config, current_branch).await?;
+        // 栈中的分支以父分支为目标分支
+        let stack = Stack::load(git_ops.repository())?;
+        let base = stack.parent_of(current_branch).unwrap_or(main_branch).to_string();
         
-        // 3. 创建 Pull Request（如果需要）
-        let pr_url = if self.options.create_pr {
-            Some(self.create_pull_request(&git_ops, config, current_branch, main_branch).await?)
-        } else {
-            None
-        };
+        let repo = git_ops.repository();
+        HookContext::collect(repo, Some(&base)).run(config, Hook::PreShip)?;
         
-        // 4. 自动合并 PR（如果需要）
-        if self.options.auto_merge && pr_url.is_some() {
-            self.auto_merge_pr(&pr_url.unwrap()).await?;
+        let mut workflow = WorkflowEngine::new("ship")
+            .step(SaveChanges::default())
+            .step(PushBranch);
+        if self.options.create_pr {
+            workflow = workflow.step(CreatePullRequest);
+        }
+        if self.options.create_pr && self.options.auto_merge {
+            workflow = workflow.step(MergePullRequest);
         }
-        
-        // 5. 切换回主分支（如果需要）
         if !self.options.no_switch {
-            self.switch_to_main_branch(&git_ops, config, main_branch).await?;
+            workflow = workflow.step(SwitchToMain::default());
+            if self.options.delete_branch {
+                workflow = workflow.step(DeleteBranch);
+            }
         }
         
-        // 6. 删除功能分支（如果需要）
-        if self.options.delete_branch && !self.options.no_switch {
-            self.cleanup_feature_branch(&git_ops, current_branch)?;
-        }
+        let mut ctx = ShipContext::new(self, git_ops, config, current_branch.to_string(), base.clone());
+        workflow.run(&mut ctx).await?;
         
-        Ok(())
+        // 完成后可能已经切回主分支并删除了本地分支，以推送的远程分支为准
+        HookContext::collect(repo, Some(&base))
+            .branch(current_branch)
+            .at(repo, &format!("{}/{}", config.remote_name, current_branch))
+            .pr_url(ctx.report.pr_url.clone())
+            .run(config, Hook::PostShip)?;
+        Ok(ctx.report)
     }
     
     /// 检查未提交的变更
@@ -236,38 +332,46 @@ impl ShipCommand {
     async fn push_current_branch(
         &self,
         git_ops: &GitOps,
-        config: &crate::config::RepoConfig,
+        config: &RepoConfig,
         current_branch: &str,
     ) -> GtResult<()> {
-        print_step(&format!("推送分支 '{}' 到远程...", current_branch));
-        
-        push_with_retry(git_ops.repository(), &config.remote_name, Some(current_branch))?;
+        NetworkOps::new(config.network_config()).push_with_retry(git_ops.repository(), &config.remote_name, Some(current_branch))?;
         
         print_success(&format!("分支 '{}' 已推送到远程", current_branch));
         Ok(())
     }
     
-    /// 创建 Pull Request
+    /// 创建 Pull Request（GitLab 上为 Merge Request）
+    ///
+    /// 平台后端不可用时提示并在结果中记录手动创建的地址。
     async fn create_pull_request(
         &self,
-        _git_ops: &GitOps,
-        _config: &crate::config::RepoConfig,
+        git_ops: &GitOps,
+        config: &RepoConfig,
         current_branch: &str,
-        main_branch: &str,
-    ) -> GtResult<String> {
-        print_step("创建 Pull Request...");
-        
-        // 检查 GitHub CLI 是否可用
-        let github_cli = GithubCli::new(false);
-        if !github_cli.is_available() {
-            print_warning("GitHub CLI (gh) 不可用，请手动在 GitHub 上创建 Pull Request");
-            return Ok(format!("https://github.com/your-org/repo/compare/{}...{}", main_branch, current_branch));
+        base_branch: &str,
+        report: &mut ShipReport,
+    ) -> GtResult<()> {
+        // 根据远程 URL 选择 GitHub / GitLab / Gitea 后端
+        let forge = forge::from_config(config, git_ops.repository(), false)?;
+        let request_name = forge.kind().request_name();
+        
+        if !forge.is_available() {
+            let hint = match forge.kind() {
+                ForgeKind::GitHub => "GitHub CLI (gh) 不可用，也没有配置 github.token".to_string(),
+                kind => format!("没有配置 {}.token", kind.config_prefix()),
+            };
+            let compare_url = forge.compare_url(base_branch, current_branch);
+            print_warning(&format!("{}，请手动创建 {}:", hint, request_name));
+            print_info(&compare_url);
+            report.compare_url = Some(compare_url);
+            return Ok(());
         }
         
         // 创建 PR 选项 - 使用正确的字段名称
         let mut pr_options = CreatePrOptions::new(
             current_branch.to_string(),
-            main_branch.to_string()
+            base_branch.to_string()
         );
         
         // 设置可选字段
@@ -279,54 +383,88 @@ impl ShipCommand {
             pr_options = pr_options.with_body(body.clone());
         }
         
-        // 创建 PR - 移除 await，因为这不是异步方法
-        let pr_manager = PullRequestManager::new(github_cli);
-        let pr = pr_manager.create_pr(pr_options)?;
+        if let Some(issue) = Issue::linked_to(git_ops.repository(), current_branch)? {
+            print_info(&format!("分支关联了议题 #{}: {}", issue.number, issue.title));
+            pr_options = self.reference_issue(pr_options, &issue)?;
+        }
+        
+        let pr = forge.create_change_request(&pr_options).await?;
         
-        print_success(&format!("Pull Request 已创建: {}", pr.url));
-        Ok(pr.url)
+        print_success(&format!("{} 已创建: {}", request_name, pr.url));
+        report.pr_number = Some(pr.number);
+        report.pr_url = Some(pr.url);
+        Ok(())
     }
     
-    /// 自动合并 PR
-    async fn auto_merge_pr(&self, pr_url: &str) -> GtResult<()> {
-        print_step(&format!("自动合并 PR (策略: {})...", self.options.merge_strategy));
-        
-        let github_cli = GithubCli::new(false);
-        let pr_manager = PullRequestManager::new(github_cli);
+    /// 在 PR 中引用分支关联的议题
+    ///
+    /// 没有指定标题时使用议题标题，描述仍从提交生成；描述末尾加上 `Closes #123`，合并后平台自动关闭议题。
+    fn reference_issue(&self, options: CreatePrOptions, issue: &Issue) -> GtResult<CreatePrOptions> {
+        let (title, body) = match options.title {
+            Some(ref title) => (title.clone(), options.body.clone()),
+            None => {
+                let (_, body) = forge::fill_from_commits(&options.head_branch, &options.base_branch)?;
+                (issue.title.clone(), options.body.clone().or(body))
+            }
+        };
         
-        // 创建合并选项 - 使用正确的字段名称
-        let mut merge_options = MergePrOptions::new(self.options.merge_strategy.into());
+        let closing = issue.closing_reference();
+        let body = match body {
+            Some(body) if body.contains(&closing) => body,
+            Some(body) if !body.trim().is_empty() => format!("{}\n\n{}", body.trim_end(), closing),
+            _ => closing,
+        };
         
+        Ok(options.with_title(title).with_body(body))
+    }
+    
+    /// 等待检查通过后合并 PR，返回是否已经合并
+    ///
+    /// gt 自己等待检查，不启用平台的自动合并；平台只是把合并排入队列时返回 false。
+    async fn auto_merge_pr(&self, git_ops: &GitOps, config: &RepoConfig, pr_url: &str) -> GtResult<bool> {
+        let strategy = self.merge_strategy(config);
+        let forge = forge::from_config(config, git_ops.repository(), false)?;
+        
+        let mut merge_options = MergePrOptions::new(strategy)
+            .with_checks_timeout(config.checks_timeout())
+            .with_checks_interval(config.checks_interval());
         if self.options.delete_branch {
             merge_options = merge_options.delete_branch();
         }
         
-        merge_options = merge_options.enable_auto_merge();
-        
-        // 合并 PR - 移除 await，使用正确的方法名
-        pr_manager.merge_pr(pr_url, merge_options)?;
-        
-        print_success("Pull Request 已自动合并");
-        Ok(())
+        let pr = forge.merge_change_request(pr_url, &merge_options).await?;
+        let merged = pr.state == PrState::Merged;
+        if merged {
+            print_success(&format!("{} 已合并", forge.kind().request_name()));
+        } else {
+            print_info(&format!("{} 已设置为检查通过后自动合并", forge.kind().request_name()));
+        }
+        Ok(merged)
+    }
+    
+    /// 合并策略，命令行没有指定时使用配置中的默认策略
+    fn merge_strategy(&self, config: &RepoConfig) -> crate::github::pr::MergeStrategy {
+        self.options.merge_strategy
+            .map(crate::github::pr::MergeStrategy::from)
+            .unwrap_or(config.merge_strategy)
     }
     
     /// 切换回主分支
     async fn switch_to_main_branch(
         &self,
         git_ops: &GitOps,
-        config: &crate::config::RepoConfig,
+        config: &RepoConfig,
         main_branch: &str,
     ) -> GtResult<()> {
-        print_step(&format!("切换回主分支 '{}'...", main_branch));
-        
         git_ops.checkout_branch(main_branch)?;
         
         // 拉取最新更新
         print_step("拉取主分支最新更新...");
-        crate::git::network::pull_rebase_with_retry(
+        NetworkOps::new(config.network_config()).pull_with_retry(
             git_ops.repository(),
             &config.remote_name,
-            Some(main_branch)
+            Some(main_branch),
+            true
         )?;
         
         print_success(&format!("已切换到主分支 '{}' 并更新到最新状态", main_branch));
@@ -335,15 +473,188 @@ impl ShipCommand {
     
     /// 清理功能分支
     fn cleanup_feature_branch(&self, git_ops: &GitOps, branch_name: &str) -> GtResult<()> {
-        print_step(&format!("删除本地功能分支 '{}'...", branch_name));
-        
         git_ops.delete_branch(branch_name, false)?;
         
+        // 子分支改为挂到被删除分支的父分支上
+        let mut stack = Stack::load(git_ops.repository())?;
+        if stack.remove(branch_name).is_some() {
+            stack.save(git_ops.repository())?;
+        }
+        
         print_success(&format!("本地分支 '{}' 已删除", branch_name));
         Ok(())
     }
 }
 
+/// ship 步骤之间共享的上下文
+struct ShipContext<'a> {
+    command: &'a ShipCommand,
+    git_ops: &'a GitOps,
+    config: &'a RepoConfig,
+    /// 提交的分支
+    branch: String,
+    /// PR 的目标分支
+    base: String,
+    report: ShipReport,
+}
+
+impl<'a> ShipContext<'a> {
+    fn new(command: &'a ShipCommand, git_ops: &'a GitOps, config: &'a RepoConfig, branch: String, base: String) -> Self {
+        let report = ShipReport {
+            branch: branch.clone(),
+            ..ShipReport::default()
+        };
+        Self { command, git_ops, config, branch, base, report }
+    }
+}
+
+/// 保存未提交的变更，回滚时撤销 gt save 创建的提交并保留变更
+#[derive(Default)]
+struct SaveChanges {
+    /// 保存前分支指向的提交
+    head: Option<String>,
+}
+
+#[async_trait(?Send)]
+impl<'a> Step<ShipContext<'a>> for SaveChanges {
+    fn description(&self, _ctx: &ShipContext<'a>) -> String {
+        "检查未提交的变更".to_string()
+    }
+    
+    async fn run(&mut self, ctx: &mut ShipContext<'a>) -> GtResult<()> {
+        self.head = Some(ctx.git_ops.repository().branch_oid(&ctx.branch)?);
+        ctx.command.check_uncommitted_changes(ctx.git_ops).await
+    }
+    
+    fn rollback(&mut self, ctx: &mut ShipContext<'a>) -> GtResult<()> {
+        let Some(head) = self.head.take() else {
+            return Ok(());
+        };
+        if ctx.git_ops.repository().branch_oid(&ctx.branch)? != head {
+            ctx.git_ops.reset_soft(&head)?;
+        }
+        Ok(())
+    }
+}
+
+/// 推送分支
+struct PushBranch;
+
+#[async_trait(?Send)]
+impl<'a> Step<ShipContext<'a>> for PushBranch {
+    fn description(&self, ctx: &ShipContext<'a>) -> String {
+        format!("推送分支 '{}' 到 '{}'", ctx.branch, ctx.config.remote_name)
+    }
+    
+    async fn run(&mut self, ctx: &mut ShipContext<'a>) -> GtResult<()> {
+        ctx.command.push_current_branch(ctx.git_ops, ctx.config, &ctx.branch).await
+    }
+    
+    fn reversible(&self) -> bool {
+        false
+    }
+}
+
+/// 创建 PR / MR
+struct CreatePullRequest;
+
+#[async_trait(?Send)]
+impl<'a> Step<ShipContext<'a>> for CreatePullRequest {
+    fn description(&self, ctx: &ShipContext<'a>) -> String {
+        format!("创建变更请求: {} → {}", ctx.branch, ctx.base)
+    }
+    
+    async fn run(&mut self, ctx: &mut ShipContext<'a>) -> GtResult<()> {
+        if ctx.base != ctx.config.main_branch {
+            print_info(&format!("分支 '{}' 位于分支栈中，PR 的目标分支为父分支 '{}'", ctx.branch, ctx.base));
+        }
+        ctx.report.base = Some(ctx.base.clone());
+        ctx.command.create_pull_request(ctx.git_ops, ctx.config, &ctx.branch, &ctx.base, &mut ctx.report).await
+    }
+    
+    fn reversible(&self) -> bool {
+        false
+    }
+}
+
+/// 等待检查通过后合并 PR
+struct MergePullRequest;
+
+#[async_trait(?Send)]
+impl<'a> Step<ShipContext<'a>> for MergePullRequest {
+    fn description(&self, ctx: &ShipContext<'a>) -> String {
+        format!("自动合并 (策略: {})", ctx.command.merge_strategy(ctx.config))
+    }
+    
+    async fn run(&mut self, ctx: &mut ShipContext<'a>) -> GtResult<()> {
+        // 平台后端不可用时 PR 需要手动创建，也就无法自动合并
+        let Some(pr_url) = ctx.report.pr_url.clone() else {
+            return Ok(());
+        };
+        ctx.report.merged = ctx.command.auto_merge_pr(ctx.git_ops, ctx.config, &pr_url).await?;
+        Ok(())
+    }
+    
+    fn reversible(&self) -> bool {
+        false
+    }
+}
+
+/// 切换回主分支并拉取，回滚时回到功能分支并恢复主分支
+#[derive(Default)]
+struct SwitchToMain {
+    /// 拉取前主分支指向的提交
+    main_head: Option<String>,
+}
+
+#[async_trait(?Send)]
+impl<'a> Step<ShipContext<'a>> for SwitchToMain {
+    fn description(&self, ctx: &ShipContext<'a>) -> String {
+        format!("切换回主分支 '{}' 并拉取", ctx.config.main_branch)
+    }
+    
+    async fn run(&mut self, ctx: &mut ShipContext<'a>) -> GtResult<()> {
+        let main_branch = &ctx.config.main_branch;
+        self.main_head = Some(ctx.git_ops.repository().branch_oid(main_branch)?);
+        ctx.command.switch_to_main_branch(ctx.git_ops, ctx.config, main_branch).await?;
+        ctx.report.switched_to = Some(main_branch.clone());
+        Ok(())
+    }
+    
+    fn rollback(&mut self, ctx: &mut ShipContext<'a>) -> GtResult<()> {
+        let repo = ctx.git_ops.repository();
+        if repo.rebase_in_progress() {
+            repo.rebase_abort()?;
+        }
+        if ctx.git_ops.current_branch().ok().as_deref() != Some(ctx.branch.as_str()) {
+            ctx.git_ops.checkout_branch(&ctx.branch)?;
+        }
+        if let Some(main_head) = self.main_head.take() {
+            if repo.branch_oid(&ctx.config.main_branch)? != main_head {
+                repo.set_branch_target(&ctx.config.main_branch, &main_head, "gt ship: rollback")?;
+            }
+        }
+        ctx.report.switched_to = None;
+        Ok(())
+    }
+}
+
+/// 删除本地功能分支
+struct DeleteBranch;
+
+#[async_trait(?Send)]
+impl<'a> Step<ShipContext<'a>> for DeleteBranch {
+    fn description(&self, ctx: &ShipContext<'a>) -> String {
+        format!("删除本地分支 '{}'", ctx.branch)
+    }
+    
+    async fn run(&mut self, ctx: &mut ShipContext<'a>) -> GtResult<()> {
+        ctx.command.cleanup_feature_branch(ctx.git_ops, &ctx.branch)?;
+        ctx.report.branch_deleted = true;
+        Ok(())
+    }
+}
+
 // 为 MergeStrategy 实现转换到 GitHub API 格式
 impl From<MergeStrategy> for crate::github::pr::MergeStrategy {
     fn from(strategy: MergeStrategy) -> Self {
@@ -357,18 +668,18 @@ impl From<MergeStrategy> for crate::github::pr::MergeStrategy {
 
 /// 便捷函数：简单推送
 pub async fn ship_simple() -> GtResult<()> {
-    let cmd = ShipCommand::new(false, false, None, false);
+    let cmd = ShipCommand::new(false, false, false, None, false);
     cmd.execute().await
 }
 
 /// 便捷函数：创建 PR
 pub async fn ship_with_pr() -> GtResult<()> {
-    let cmd = ShipCommand::new(false, true, None, false);
+    let cmd = ShipCommand::new(false, true, false, None, false);
     cmd.execute().await
 }
 
 /// 便捷函数：自动合并
 pub async fn ship_auto_merge() -> GtResult<()> {
-    let cmd = ShipCommand::new(false, true, Some(MergeStrategy::Rebase), true);
+    let cmd = ShipCommand::new(false, true, true, None, true);
     cmd.execute().await
 } 
\ No newline at end of file
@@ -0,0 +1,281 @@
+//! Stack 命令实现
+//!
+//! 显示分支栈，restack 分支栈，以及修改分支的父分支。
+
+use crate::cli::args::StackAction;
+use crate::config::ConfigManager;
+use crate::error::{GtResult, GtError};
+use crate::git::{GitOps, Repository, Restacker, Stack};
+use crate::plan::Plan;
+use crate::ui::{emit_json, is_json, print_info, print_step, print_success, green, red, yellow};
+use serde_json::{json, Value};
+
+/// Stack 命令
+pub struct StackCommand {
+    action: Option<StackAction>,
+}
+
+impl StackCommand {
+    /// 创建新的 Stack 命令
+    pub fn new(action: Option<StackAction>) -> Self {
+        Self { action }
+    }
+
+    /// 执行命令
+    pub async fn execute(self) -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+        let config_manager = ConfigManager::new(git_ops.repository())?;
+        let main_branch = config_manager.repo_config().main_branch.clone();
+
+        // 不带子命令时等同于 show
+        match self.action.unwrap_or(StackAction::Show) {
+            StackAction::Show => Self::show(&git_ops, &main_branch),
+            StackAction::Restack { continue_restack, abort } => {
+                let restacker = Restacker::new(git_ops.repository(), &main_branch);
+                let result = if continue_restack {
+                    restacker.resume()
+                } else if abort {
+                    restacker.abort().map(|_| print_success("已回滚到 restack 之前的状态"))
+                } else {
+                    Self::restack(&git_ops, &restacker, &main_branch)
+                };
+                if result.is_err() && restacker.in_progress() {
+                    print_info("请解决冲突并 'git add' 后运行 'gt stack restack --continue'");
+                    print_info("或者运行 'gt stack restack --abort' 回滚");
+                }
+                result
+            }
+            StackAction::SetParent { parent, branch } => {
+                Self::set_parent(&git_ops, &main_branch, &parent, branch)
+            }
+        }
+    }
+
+    /// 生成执行计划，不修改仓库；只读的 show 返回 None
+    pub fn plan(&self) -> GtResult<Option<Plan>> {
+        let git_ops = GitOps::new()?;
+        let config_manager = ConfigManager::new(git_ops.repository())?;
+        let main_branch = &config_manager.repo_config().main_branch;
+        let mut plan = Plan::new("stack");
+
+        match self.action {
+            None | Some(StackAction::Show) => return Ok(None),
+            Some(StackAction::Restack { continue_restack: true, .. }) => {
+                plan.step("继续被中断的 restack");
+            }
+            Some(StackAction::Restack { abort: true, .. }) => {
+                plan.step("中止 restack，把所有分支恢复到 restack 之前");
+            }
+            Some(StackAction::Restack { .. }) => {
+                if !git_ops.is_clean()? {
+                    return Err(GtError::DirtyWorkingDirectory);
+                }
+                let stack = Stack::load(git_ops.repository())?;
+                let current = git_ops.current_branch()?;
+                for branch in Self::restack_branches(&stack, &current, main_branch) {
+                    let parent = stack.parent_of(&branch).unwrap_or(main_branch);
+                    plan.step(format!("rebase {} 到 {}", branch, parent));
+                }
+            }
+            Some(StackAction::SetParent { ref parent, ref branch }) => {
+                let branch = match branch {
+                    Some(branch) => branch.clone(),
+                    None => git_ops.current_branch()?,
+                };
+                plan.step(format!("在分支栈中把 '{}' 的父分支设置为 '{}'", branch, parent));
+            }
+        }
+
+        Ok(Some(plan))
+    }
+
+    /// 以树形显示分支栈
+    fn show(git_ops: &GitOps, main_branch: &str) -> GtResult<()> {
+        let repo = git_ops.repository();
+        let stack = Stack::load(repo)?;
+        if stack.is_empty() {
+            emit_json(&Vec::<Value>::new());
+            print_info("还没有分支栈，使用 'gt start <分支> -b <父分支>' 基于功能分支创建");
+            return Ok(());
+        }
+
+        let current = git_ops.current_branch().unwrap_or_default();
+        let roots: Vec<String> = stack.roots()
+            .into_iter()
+            .filter(|root| root != main_branch)
+            .collect();
+        let mut children = roots.clone();
+        children.extend(stack.children_of(main_branch));
+        children.sort();
+        children.dedup();
+
+        if is_json() {
+            let mut entries = Vec::new();
+            for branch in &children {
+                Self::collect_entries(repo, &stack, &current, branch, main_branch, &mut entries)?;
+            }
+            emit_json(&entries);
+            return Ok(());
+        }
+
+        println!("{}", Self::label(main_branch, &current));
+        let count = children.len();
+        for (index, branch) in children.iter().enumerate() {
+            Self::print_tree(repo, &stack, &current, branch, main_branch, "", index + 1 == count)?;
+        }
+
+        Ok(())
+    }
+
+    /// 递归打印一个分支及其子分支
+    fn print_tree(
+        repo: &Repository,
+        stack: &Stack,
+        current: &str,
+        branch: &str,
+        parent: &str,
+        prefix: &str,
+        last: bool,
+    ) -> GtResult<()> {
+        let connector = if last { "└─ " } else { "├─ " };
+        let counts = if !repo.branch_exists(branch)? {
+            red("(已删除)").to_string()
+        } else if !repo.branch_exists(parent)? {
+            yellow(format!("(父分支 '{}' 不存在)", parent)).to_string()
+        } else {
+            let (ahead, behind) = repo.ahead_behind(branch, parent)?;
+            let counts = format!("+{} -{}", ahead, behind);
+            if behind > 0 { yellow(counts).to_string() } else { counts }
+        };
+        println!("{}{}{}  {}", prefix, connector, Self::label(branch, current), counts);
+
+        let child_prefix = format!("{}{}", prefix, if last { "   " } else { "│  " });
+        let children = stack.children_of(branch);
+        let count = children.len();
+        for (index, child) in children.iter().enumerate() {
+            Self::print_tree(repo, stack, current, child, branch, &child_prefix, index + 1 == count)?;
+        }
+
+        Ok(())
+    }
+
+    /// 按树的顺序收集分支及其父分支，用于 JSON 输出
+    fn collect_entries(
+        repo: &Repository,
+        stack: &Stack,
+        current: &str,
+        branch: &str,
+        parent: &str,
+        entries: &mut Vec<Value>,
+    ) -> GtResult<()> {
+        let exists = repo.branch_exists(branch)?;
+        let counts = if exists && repo.branch_exists(parent)? {
+            Some(repo.ahead_behind(branch, parent)?)
+        } else {
+            None
+        };
+        entries.push(json!({
+            "branch": branch,
+            "parent": parent,
+            "current": branch == current,
+            "exists": exists,
+            "ahead": counts.map(|(ahead, _)| ahead),
+            "behind": counts.map(|(_, behind)| behind),
+        }));
+
+        for child in stack.children_of(branch) {
+            Self::collect_entries(repo, stack, current, &child, branch, entries)?;
+        }
+        Ok(())
+    }
+
+    /// 分支名，当前分支加上标记
+    fn label(branch: &str, current: &str) -> String {
+        if branch == current {
+            green(format!("* {}", branch)).to_string()
+        } else {
+            branch.to_string()
+        }
+    }
+
+    /// restack 当前分支所在的栈（在主分支上时 restack 所有栈）
+    fn restack(git_ops: &GitOps, restacker: &Restacker, main_branch: &str) -> GtResult<()> {
+        if !git_ops.is_clean()? {
+            return Err(GtError::DirtyWorkingDirectory);
+        }
+
+        let stack = Stack::load(git_ops.repository())?;
+        let current = git_ops.current_branch()?;
+        let branches = Self::restack_branches(&stack, &current, main_branch);
+        if branches.is_empty() {
+            print_info(&format!("分支 '{}' 不在任何分支栈中", current));
+            return Ok(());
+        }
+
+        print_step(&format!("restack {} 个分支...", branches.len()));
+        restacker.restack(branches)?;
+        print_success("分支栈已 restack 到最新状态");
+        Ok(())
+    }
+
+    /// 需要 restack 的分支：当前分支所在的栈，在主分支上时为所有栈；不在栈中时为空
+    fn restack_branches(stack: &Stack, current: &str, main_branch: &str) -> Vec<String> {
+        let roots = if current == main_branch {
+            stack.roots()
+        } else {
+            vec![stack.root_of(current)]
+        };
+
+        let mut branches = Vec::new();
+        for root in roots {
+            if root != main_branch {
+                branches.push(root.clone());
+            }
+            branches.extend(stack.descendants_of(&root));
+        }
+
+        if branches.iter().all(|branch| !stack.contains(branch) && stack.children_of(branch).is_empty()) {
+            return Vec::new();
+        }
+        branches
+    }
+
+    /// 修改分支的父分支，下次 restack 时把分支移到新的父分支上
+    fn set_parent(git_ops: &GitOps, main_branch: &str, parent: &str, branch: Option<String>) -> GtResult<()> {
+        let repo = git_ops.repository();
+        let branch = match branch {
+            Some(branch) => branch,
+            None => git_ops.current_branch()?,
+        };
+
+        if branch == parent || branch == main_branch {
+            return Err(GtError::InvalidInput {
+                input: format!("不能把 '{}' 的父分支设置为 '{}'", branch, parent)
+            });
+        }
+        for name in [&branch, &parent.to_string()] {
+            if !repo.branch_exists(name)? {
+                return Err(GtError::BranchNotFound { branch: name.clone() });
+            }
+        }
+
+        let mut stack = Stack::load(repo)?;
+        if stack.ancestors_of(parent).contains(&branch) {
+            return Err(GtError::InvalidInput {
+                input: format!("'{}' 是 '{}' 的后代分支，不能作为父分支", parent, branch)
+            });
+        }
+
+        // 沿用原来的分叉点，restack 时只移动分支自己的提交
+        let upstream = match stack.get(&branch) {
+            Some(entry) => entry.parent_head.clone(),
+            None => repo.merge_base(&branch, main_branch)?,
+        };
+        stack.set_parent(&branch, parent, &upstream);
+        stack.save(repo)?;
+
+        print_success(&format!("分支 '{}' 的父分支已设置为 '{}'", branch, parent));
+        print_info("运行 'gt stack restack' 把分支移到新的父分支上");
+        Ok(())
+    }
+}
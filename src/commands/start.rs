@@ -2,18 +2,24 @@
 //! 
 //! 对应 gw start，用于开始新的功能分支
 //! 增强功能：智能分支命名、自动配置、状态检查
+//! `gt start #123` / `gt start --issue 123` 根据议题标题生成分支名称，并把议题关联到分支
 
 use crate::error::{GtResult, GtError};
-use crate::git::{Repository, GitOps};
+use crate::git::{BranchPolicy, Repository, GitOps, Stack};
 use crate::config::{ConfigManager, RepoConfig};
-use crate::ui::{print_step, print_success, print_warning, confirm_action};
-use crate::git::network::pull_rebase_with_retry;
+use crate::forge::{self, Issue};
+use crate::ui::{emit_json, is_json, print_step, print_success, print_warning, print_info, confirm_action};
+use crate::git::NetworkOps;
+use crate::plan::Plan;
+use crate::hooks::{Hook, HookContext};
 
 /// Start 命令选项
 #[derive(Debug, Clone)]
 pub struct StartOptions {
-    /// 分支名称
+    /// 分支名称，写作 `#123` 时根据议题生成
     pub branch: String,
+    /// 关联的议题编号，没有指定分支名称时根据议题生成
+    pub issue: Option<u32>,
     /// 基础分支（默认为主分支）
     pub base: Option<String>,
     /// 是否只在本地创建（不推送）
@@ -32,6 +38,7 @@ impl Default for StartOptions {
     fn default() -> Self {
         Self {
             branch: String::new(),
+            issue: None,
             base: None,
             local: false,
             force: false,
@@ -64,20 +71,28 @@ impl StartCommand {
     
     /// 执行命令
     pub async fn execute(mut self) -> GtResult<()> {
-        print_step(&format!("开始创建功能分支 '{}'", self.options.branch));
+        if self.options.dry_run {
+            self.plan().await?.print();
+            return Ok(());
+        }
         
         let git_ops = GitOps::new()?;
         let config_manager = ConfigManager::new(git_ops.repository())?;
         let config = config_manager.repo_config();
         
-        // 1. 验证输入
-        self.validate_input()?;
+        // 1. 从议题生成分支名称，按命名规范验证
+        let issue = self.resolve_issue(&git_ops, config).await?;
+        self.validate_input(config)?;
+        print_step(&format!("开始创建功能分支 '{}'", self.options.branch));
         
         // 2. 检查工作区状态
         self.check_working_directory(&git_ops)?;
         
-        // 3. 确定基础分支并更新
+        // 3. 确定基础分支，运行 pre-start 钩子后更新
         let base_branch = self.determine_base_branch(config)?;
+        HookContext::collect(git_ops.repository(), Some(&base_branch))
+            .branch(self.options.branch.clone())
+            .run(config, Hook::PreStart)?;
         if !self.options.skip_update {
             self.update_base_branch(&git_ops, config, &base_branch).await?;
         }
@@ -87,56 +102,119 @@ impl StartCommand {
         
         // 5. 创建并切换到新分支
         self.create_and_checkout_branch(&git_ops, &base_branch)?;
+        if let Some(ref issue) = issue {
+            self.link_issue(&git_ops, issue)?;
+        }
+        
+        // 6. 基于功能分支创建时记录父分支，组成分支栈
+        if base_branch != config.main_branch {
+            self.record_parent(&git_ops, &base_branch)?;
+        }
         
-        // 6. 推送到远程（如果不是本地模式）
+        // 7. 推送到远程（如果不是本地模式）
         if !self.options.local {
             self.push_branch(&git_ops, config).await?;
         }
         
-        // 7. 显示成功信息和后续建议
+        HookContext::collect(git_ops.repository(), Some(&base_branch))
+            .run(config, Hook::PostStart)?;
+        
+        // 8. 显示成功信息和后续建议
+        emit_json(&serde_json::json!({
+            "branch": self.options.branch,
+            "base": base_branch,
+            "pushed": !self.options.local,
+            "issue": issue,
+        }));
         self.show_success_info();
         
         Ok(())
     }
     
-    /// 验证输入参数
-    fn validate_input(&mut self) -> GtResult<()> {
-        // 验证分支名称
-        if self.options.branch.is_empty() {
-            return Err(GtError::InvalidInput {
-                input: "分支名称不能为空".to_string()
-            });
+    /// 生成执行计划，不修改仓库
+    pub async fn plan(mut self) -> GtResult<Plan> {
+        let git_ops = GitOps::new()?;
+        let config_manager = ConfigManager::new(git_ops.repository())?;
+        let config = config_manager.repo_config();
+        let repo = git_ops.repository();
+        
+        let issue = self.resolve_issue(&git_ops, config).await?;
+        self.validate_input(config)?;
+        let branch = &self.options.branch;
+        let base_branch = self.determine_base_branch(config)?;
+        let remote = &config.remote_name;
+        let mut plan = Plan::new("start");
+        Hook::PreStart.plan(&mut plan, config);
+        
+        if !self.options.skip_update {
+            let remote_exists = repo.remote_branch_exists(remote, &base_branch)?;
+            if !repo.branch_exists(&base_branch)? && remote_exists {
+                plan.step(format!("从 '{}/{}' 创建本地分支 '{}'", remote, base_branch, base_branch));
+            }
+            if git_ops.current_branch()? != base_branch {
+                plan.step(format!("checkout {}", base_branch));
+            }
+            if base_branch == config.main_branch || remote_exists {
+                plan.step(format!("pull --rebase {} {}", remote, base_branch));
+            }
         }
         
-        // 规范化分支名称
-        self.options.branch = self.normalize_branch_name(&self.options.branch);
+        if repo.branch_exists(branch)? {
+            if !self.options.force {
+                return Err(GtError::BranchAlreadyExists { branch: branch.clone() });
+            }
+            plan.step(format!("强制删除已存在的分支 '{}'", branch));
+        }
         
-        // 验证分支名称格式
-        if !self.is_valid_branch_name(&self.options.branch) {
-            return Err(GtError::InvalidBranchName {
-                name: self.options.branch.clone()
-            });
+        plan.step(format!("基于 '{}' 创建并切换到新分支 '{}'", base_branch, branch));
+        if let Some(ref issue) = issue {
+            plan.step(format!("关联议题 #{}: {}", issue.number, issue.title));
+        }
+        if base_branch != config.main_branch {
+            plan.step(format!("在分支栈中记录父分支 '{}'", base_branch));
+        }
+        if !self.options.local {
+            plan.step(format!("push {} {}", remote, branch));
         }
+        Hook::PostStart.plan(&mut plan, config);
         
-        Ok(())
+        Ok(plan)
     }
     
-    /// 规范化分支名称
-    fn normalize_branch_name(&self, name: &str) -> String {
-        name.trim()
-            .replace(' ', "-")
-            .replace("_", "-")
-            .to_lowercase()
+    /// 获取要关联的议题，没有指定分支名称时根据议题生成
+    async fn resolve_issue(&mut self, git_ops: &GitOps, config: &RepoConfig) -> GtResult<Option<Issue>> {
+        let from_branch = self.options.branch.strip_prefix('#').and_then(|number| number.parse().ok());
+        let Some(number) = self.options.issue.or(from_branch) else {
+            return Ok(None);
+        };
+        
+        let forge = forge::from_config(config, git_ops.repository(), false)?;
+        print_step(&format!("从 {} 获取议题 #{}", forge.kind(), number));
+        let issue = forge.get_issue(number).await?;
+        
+        if from_branch.is_some() || self.options.branch.trim().is_empty() {
+            self.options.branch = BranchPolicy::from_config(config)?.issue_branch(issue.number, &issue.title)?;
+            print_info(&format!("根据议题 #{} '{}' 生成分支名称 '{}'", issue.number, issue.title, self.options.branch));
+        }
+        
+        Ok(Some(issue))
     }
     
-    /// 验证分支名称是否有效
-    fn is_valid_branch_name(&self, name: &str) -> bool {
-        !name.is_empty() 
-            && !name.starts_with('-')
-            && !name.ends_with('-')
-            && !name.contains("..")
-            && !name.contains("//")
-            && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '/' || c == '_')
+    /// 验证输入参数，按命名规范规范化分支名称
+    fn validate_input(&mut self, config: &RepoConfig) -> GtResult<()> {
+        if self.options.branch.trim().is_empty() {
+            return Err(GtError::InvalidInput {
+                input: "分支名称不能为空".to_string()
+            });
+        }
+        
+        let branch = BranchPolicy::from_config(config)?.enforce(&self.options.branch)?;
+        if branch != self.options.branch {
+            print_info(&format!("分支名称已规范化为 '{}'", branch));
+        }
+        self.options.branch = branch;
+        
+        Ok(())
     }
     
     /// 检查工作区状态
@@ -174,26 +252,34 @@ impl StartCommand {
     
     /// 更新基础分支
     async fn update_base_branch(&self, git_ops: &GitOps, config: &RepoConfig, base_branch: &str) -> GtResult<()> {
-        if self.options.dry_run {
-            print_step(&format!("🔍 [预演] 更新基础分支 '{}' 到最新状态", base_branch));
-            print_success(&format!("🔍 [预演] 基础分支 '{}' 已更新到最新状态", base_branch));
-            return Ok(());
-        }
-        
         let current_branch = git_ops.current_branch()?;
         
+        // 本地还没有基础分支时（如远程默认分支不是克隆时的分支），从远程跟踪分支创建
+        let repo = git_ops.repository();
+        if !repo.branch_exists(base_branch)? && repo.remote_branch_exists(&config.remote_name, base_branch)? {
+            print_step(&format!("从 '{}/{}' 创建本地分支 '{}'", config.remote_name, base_branch, base_branch));
+            repo.create_branch(base_branch, Some(&format!("{}/{}", config.remote_name, base_branch)))?;
+        }
+        
         // 如果当前不在基础分支上，需要切换
         if current_branch != base_branch {
             print_step(&format!("切换到基础分支 '{}'", base_branch));
             git_ops.checkout_branch(base_branch)?;
         }
         
+        // 只在本地的功能分支（如分支栈中的父分支）没有可拉取的内容
+        if base_branch != config.main_branch && !repo.remote_branch_exists(&config.remote_name, base_branch)? {
+            print_info(&format!("基础分支 '{}' 尚未推送到远程，跳过更新", base_branch));
+            return Ok(());
+        }
+        
         // 拉取最新更新
         print_step(&format!("更新基础分支 '{}' 到最新状态", base_branch));
-        pull_rebase_with_retry(
+        NetworkOps::new(config.network_config()).pull_with_retry(
             git_ops.repository(), 
             &config.remote_name, 
-            Some(base_branch)
+            Some(base_branch),
+            true
         )?;
         
         print_success(&format!("基础分支 '{}' 已更新到最新状态", base_branch));
@@ -233,18 +319,31 @@ impl StartCommand {
         Ok(())
     }
     
+    /// 在分支栈中记录新分支的父分支
+    fn record_parent(&self, git_ops: &GitOps, base_branch: &str) -> GtResult<()> {
+        let repo = git_ops.repository();
+        let mut stack = Stack::load(repo)?;
+        stack.set_parent(&self.options.branch, base_branch, &repo.branch_oid(base_branch)?);
+        stack.save(repo)?;
+        
+        print_info(&format!("已记录父分支 '{}'，可使用 'gt stack' 查看分支栈", base_branch));
+        Ok(())
+    }
+    
+    /// 把议题关联到新分支，`gt ship --pr` 时在 PR 中引用
+    fn link_issue(&self, git_ops: &GitOps, issue: &Issue) -> GtResult<()> {
+        issue.link(git_ops.repository(), &self.options.branch)?;
+        
+        print_info(&format!("已关联议题 #{}: {}", issue.number, issue.url));
+        Ok(())
+    }
+    
     /// 推送分支到远程
     async fn push_branch(&self, git_ops: &GitOps, config: &RepoConfig) -> GtResult<()> {
-        if self.options.dry_run {
-            print_step(&format!("🔍 [预演] 推送分支 '{}' 到远程", self.options.branch));
-            print_success(&format!("🔍 [预演] 分支 '{}' 已推送到远程", self.options.branch));
-            return Ok(());
-        }
-        
         print_step(&format!("推送分支 '{}' 到远程", self.options.branch));
         
         // 使用网络重试机制推送
-        crate::git::network::push_with_retry(
+        NetworkOps::new(config.network_config()).push_with_retry(
             git_ops.repository(),
             &config.remote_name,
             Some(&self.options.branch)
@@ -257,6 +356,9 @@ impl StartCommand {
     /// 显示成功信息和建议
     fn show_success_info(&self) {
         print_success(&format!("🎉 功能分支 '{}' 创建成功！", self.options.branch));
+        if is_json() {
+            return;
+        }
         
         if let Some(ref desc) = self.options.description {
             println!("  📝 描述: {}", desc);
@@ -0,0 +1,221 @@
+//! Stash 命令实现
+//!
+//! 增强版 git stash：保存、恢复、查看和管理暂存的变更。
+
+use crate::cli::args::StashAction;
+use crate::error::{GtResult, GtError};
+use crate::git::{GitOps, StashPushOptions};
+use crate::git::stash::parse_stash_index;
+use crate::plan::Plan;
+use crate::utils::format_timestamp;
+use crate::ui::{emit_json, is_json, print_step, print_success, print_warning, print_info, confirm_action};
+
+/// Stash 命令
+pub struct StashCommand {
+    action: Option<StashAction>,
+}
+
+impl StashCommand {
+    /// 创建新的 Stash 命令
+    pub fn new(action: Option<StashAction>) -> Self {
+        Self { action }
+    }
+
+    /// 执行命令
+    pub async fn execute(self) -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+
+        // 与 git 一致：不带子命令时等同于 push
+        let action = self.action.unwrap_or(StashAction::Push {
+            message: None,
+            include_untracked: false,
+            keep_index: false,
+            paths: Vec::new(),
+        });
+
+        match action {
+            StashAction::Push { message, include_untracked, keep_index, paths } => {
+                let mut options = StashPushOptions::new().with_paths(paths);
+                if let Some(message) = message {
+                    options = options.with_message(message);
+                }
+                if include_untracked {
+                    options = options.include_untracked();
+                }
+                if keep_index {
+                    options = options.keep_index();
+                }
+                Self::push(&git_ops, &options)
+            }
+            StashAction::Pop { index } => {
+                let index = Self::resolve_index(index.as_deref())?;
+                print_step(&format!("恢复并删除 stash@{{{}}}...", index));
+                git_ops.pop_stash(index)?;
+                print_success(&format!("stash@{{{}}} 已恢复", index));
+                Ok(())
+            }
+            StashAction::Apply { index } => {
+                let index = Self::resolve_index(index.as_deref())?;
+                print_step(&format!("应用 stash@{{{}}}...", index));
+                git_ops.apply_stash(index)?;
+                print_success(&format!("stash@{{{}}} 已应用（仍保留在列表中）", index));
+                Ok(())
+            }
+            StashAction::List => Self::list(&git_ops),
+            StashAction::Show { index } => {
+                let index = Self::resolve_index(index.as_deref())?;
+                let patch = git_ops.stash_diff(index)?;
+                if is_json() {
+                    emit_json(&serde_json::json!({ "index": index, "patch": patch }));
+                } else if patch.is_empty() {
+                    print_info(&format!("stash@{{{}}} 没有内容变更", index));
+                } else {
+                    print!("{}", patch);
+                }
+                Ok(())
+            }
+            StashAction::Drop { index } => {
+                let index = Self::resolve_index(index.as_deref())?;
+                git_ops.drop_stash(index)?;
+                print_success(&format!("stash@{{{}}} 已删除", index));
+                Ok(())
+            }
+            StashAction::Clear => Self::clear(&git_ops),
+            StashAction::Branch { name, index } => {
+                let index = Self::resolve_index(index.as_deref())?;
+                print_step(&format!("基于 stash@{{{}}} 创建分支 '{}'...", index, name));
+                git_ops.stash_branch(&name, index)?;
+                print_success(&format!("已切换到分支 '{}' 并恢复 stash@{{{}}}", name, index));
+                Ok(())
+            }
+        }
+    }
+
+    /// 生成执行计划，不修改仓库；只读的 list / show 返回 None
+    pub fn plan(&self) -> GtResult<Option<Plan>> {
+        let git_ops = GitOps::new()?;
+        let mut plan = Plan::new("stash");
+
+        match self.action {
+            None => Self::plan_push(&git_ops, &mut plan, false, false, &[])?,
+            Some(StashAction::Push { include_untracked, keep_index, ref paths, .. }) => {
+                Self::plan_push(&git_ops, &mut plan, include_untracked, keep_index, paths)?;
+            }
+            Some(StashAction::Pop { ref index }) => {
+                let index = Self::resolve_index(index.as_deref())?;
+                plan.step(format!("stash pop stash@{{{}}}", index));
+            }
+            Some(StashAction::Apply { ref index }) => {
+                let index = Self::resolve_index(index.as_deref())?;
+                plan.step(format!("stash apply stash@{{{}}}", index));
+            }
+            Some(StashAction::Drop { ref index }) => {
+                let index = Self::resolve_index(index.as_deref())?;
+                plan.step(format!("stash drop stash@{{{}}}", index));
+            }
+            Some(StashAction::Clear) => {
+                let count = git_ops.list_stashes()?.len();
+                if count > 0 {
+                    plan.step(format!("删除全部 {} 个 stash", count));
+                }
+            }
+            Some(StashAction::Branch { ref name, ref index }) => {
+                let index = Self::resolve_index(index.as_deref())?;
+                plan.step(format!("基于 stash@{{{}}} 创建并切换到分支 '{}'", index, name));
+                plan.step(format!("恢复并删除 stash@{{{}}}", index));
+            }
+            Some(StashAction::List) | Some(StashAction::Show { .. }) => return Ok(None),
+        }
+
+        Ok(Some(plan))
+    }
+
+    /// push 的执行计划
+    fn plan_push(git_ops: &GitOps, plan: &mut Plan, include_untracked: bool, keep_index: bool, paths: &[String]) -> GtResult<()> {
+        if git_ops.is_clean()? {
+            return Ok(());
+        }
+
+        let mut description = if paths.is_empty() {
+            let untracked = if include_untracked { 0 } else { git_ops.check_status()?.untracked_files };
+            format!("stash {} 个文件", git_ops.changed_paths()?.len().saturating_sub(untracked))
+        } else {
+            format!("stash {}", paths.join(" "))
+        };
+        if include_untracked {
+            description.push_str("（含未追踪文件）");
+        }
+        if keep_index {
+            description.push_str("，保留暂存区");
+        }
+        plan.step(description);
+        Ok(())
+    }
+
+    /// 保存当前变更
+    fn push(git_ops: &GitOps, options: &StashPushOptions) -> GtResult<()> {
+        if git_ops.is_clean()? {
+            print_warning("没有需要暂存的变更");
+            return Ok(());
+        }
+
+        if options.paths.is_empty() {
+            print_step("暂存当前变更...");
+        } else {
+            print_step(&format!("暂存指定文件: {}", options.paths.join(", ")));
+        }
+
+        git_ops.stash_push(options)?;
+
+        print_success("变更已暂存到 stash@{0}");
+        if !options.include_untracked && git_ops.has_untracked_files()? {
+            print_info("未追踪的文件未被暂存，可使用 'gt stash push -u' 一并暂存");
+        }
+        Ok(())
+    }
+
+    /// 列出所有 stash
+    fn list(git_ops: &GitOps) -> GtResult<()> {
+        let stashes = git_ops.list_stashes()?;
+
+        if is_json() {
+            emit_json(&stashes);
+            return Ok(());
+        }
+
+        if stashes.is_empty() {
+            print_info("没有任何 stash");
+            return Ok(());
+        }
+
+        for stash in &stashes {
+            println!("{}  {}  {}", stash.reference(), format_timestamp(stash.time), stash.message);
+        }
+
+        Ok(())
+    }
+
+    /// 清空所有 stash
+    fn clear(git_ops: &GitOps) -> GtResult<()> {
+        let stashes = git_ops.list_stashes()?;
+        if stashes.is_empty() {
+            print_info("没有任何 stash");
+            return Ok(());
+        }
+
+        if !confirm_action(&format!("确定要删除全部 {} 个 stash 吗？此操作无法撤销", stashes.len()), false) {
+            return Err(GtError::UserCancelled);
+        }
+
+        let count = git_ops.clear_stashes()?;
+        print_success(&format!("已删除 {} 个 stash", count));
+        Ok(())
+    }
+
+    /// 解析用户输入的 stash 索引
+    fn resolve_index(input: Option<&str>) -> GtResult<usize> {
+        parse_stash_index(input).ok_or_else(|| GtError::InvalidInput {
+            input: format!("无效的 stash 索引: {}", input.unwrap_or_default())
+        })
+    }
+}
@@ -0,0 +1,212 @@
+//! Status 命令实现
+//!
+//! 按暂存、未暂存、未追踪和冲突分组列出文件，显示当前分支与上游的同步状态、
+//! stash 数量，以及进行中的 rebase、merge 等操作和下一步要运行的命令。
+
+use crate::commands::update::UPDATE_STATE_NAME;
+use crate::config::ConfigManager;
+use crate::error::GtResult;
+use crate::git::{Commit, FileStatus, FileStatuses, GitOps, Operation, Remote, Restacker, StateStore, SyncStatus, WorkingTreeStatus};
+use crate::utils::format_timestamp;
+use crate::ui::{bold, cyan, emit_json, green, is_json, red, yellow, ColoredString};
+use serde::Serialize;
+
+/// `-l` 时显示的提交数
+const LOG_LIMIT: usize = 5;
+
+/// 进行中的操作及下一步命令
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationStatus {
+    /// 操作类型
+    pub operation: Operation,
+    /// 完成操作的命令
+    pub continue_command: String,
+    /// 放弃操作的命令
+    pub abort_command: String,
+}
+
+/// Status 的结果，`--format json` 时作为输出
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusReport {
+    /// 当前分支，HEAD 分离时为 None
+    pub branch: Option<String>,
+    /// HEAD 指向的提交
+    pub head: Option<String>,
+    /// 上游分支
+    pub upstream: Option<String>,
+    /// 与上游的同步状态
+    pub sync: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+    /// stash 数量
+    pub stashes: usize,
+    /// 进行中的操作
+    pub in_progress: Option<OperationStatus>,
+    /// 工作区是否干净
+    pub clean: bool,
+    #[serde(flatten)]
+    pub counts: WorkingTreeStatus,
+    pub files: FileStatuses,
+    /// `-r` 时的远程仓库
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remotes: Option<Vec<Remote>>,
+    /// `-l` 时最近的提交
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log: Option<Vec<Commit>>,
+}
+
+/// Status 命令
+pub struct StatusCommand {
+    /// 显示远程信息
+    remote: bool,
+    /// 显示最近的提交
+    log: bool,
+}
+
+impl StatusCommand {
+    /// 创建新的 Status 命令
+    pub fn new(remote: bool, log: bool) -> Self {
+        Self { remote, log }
+    }
+
+    /// 执行命令
+    pub async fn execute(self) -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+        let report = self.collect(&git_ops)?;
+
+        emit_json(&report);
+        if !is_json() {
+            Self::print(&report);
+        }
+        Ok(())
+    }
+
+    /// 收集状态
+    fn collect(&self, git_ops: &GitOps) -> GtResult<StatusReport> {
+        let repo = git_ops.repository();
+        let config_manager = ConfigManager::load(repo)?;
+        let config = config_manager.repo_config();
+
+        // HEAD 分离时 shorthand 为 "HEAD"
+        let branch = git_ops.current_branch().ok().filter(|_| repo.inner().head_detached().ok() != Some(true));
+        let head = repo.inner().head().ok()
+            .and_then(|head| head.target())
+            .map(|oid| oid.to_string()[..8].to_string());
+        let sync = branch.as_ref().and_then(|_| repo.sync_status(&config.remote_name).ok());
+        let (ahead, behind) = sync.as_ref().map(SyncStatus::ahead_behind).unwrap_or((0, 0));
+        let counts = git_ops.check_status()?;
+
+        // gt update 和 gt stack restack 中断时用对应的 gt 命令继续
+        let in_progress = repo.operation_in_progress().map(|operation| {
+            let gt_command = if StateStore::new(repo).exists(UPDATE_STATE_NAME) {
+                Some("gt update")
+            } else if Restacker::new(repo, &config.main_branch).in_progress() {
+                Some("gt stack restack")
+            } else {
+                None
+            };
+            match gt_command {
+                Some(command) => OperationStatus {
+                    operation,
+                    continue_command: format!("{} --continue", command),
+                    abort_command: format!("{} --abort", command),
+                },
+                None => OperationStatus {
+                    operation,
+                    continue_command: operation.continue_command().to_string(),
+                    abort_command: operation.abort_command().to_string(),
+                },
+            }
+        });
+
+        Ok(StatusReport {
+            upstream: branch.as_deref().and_then(|branch| repo.upstream_of(branch)),
+            branch,
+            head,
+            sync: sync.map(|sync| sync.description()),
+            ahead,
+            behind,
+            stashes: git_ops.list_stashes().map(|stashes| stashes.len()).unwrap_or(0),
+            in_progress,
+            clean: counts.is_clean(),
+            counts,
+            files: repo.file_statuses()?,
+            remotes: self.remote.then(|| git_ops.list_remotes()).transpose()?,
+            log: self.log.then(|| git_ops.get_commit_history(LOG_LIMIT, 0)).transpose()?,
+        })
+    }
+
+    /// 以文本形式显示
+    fn print(report: &StatusReport) {
+        match (&report.branch, &report.head) {
+            (Some(branch), _) => println!("位于分支 {}", bold(branch)),
+            (None, Some(head)) => println!("HEAD 处于分离状态 ({})", yellow(head)),
+            (None, None) => println!("还没有任何提交"),
+        }
+        match (&report.upstream, &report.sync) {
+            (Some(upstream), Some(sync)) => println!("上游 {}: {}", cyan(upstream), sync),
+            (None, Some(sync)) => println!("远程: {}", sync),
+            _ => {}
+        }
+        if report.stashes > 0 {
+            println!("有 {} 个 stash", report.stashes);
+        }
+
+        if let Some(ref status) = report.in_progress {
+            println!();
+            println!("{}", yellow(format!("⚠️ 正在进行 {}", status.operation.name())));
+            println!("  解决冲突并 git add 后继续: {}", cyan(&status.continue_command));
+            println!("  放弃: {}", cyan(&status.abort_command));
+        }
+
+        let files = &report.files;
+        print_group("冲突", &files.conflicted, |text| red(text));
+        print_group("已暂存", &files.staged, |text| green(text));
+        print_group("未暂存", &files.unstaged, |text| yellow(text));
+        print_group("未追踪", &files.untracked, |text| red(text));
+
+        println!();
+        if report.clean {
+            println!("✅ 工作区干净");
+        } else {
+            println!("⚠️ 工作区有未处理的变更");
+        }
+
+        if let Some(ref remotes) = report.remotes {
+            println!();
+            println!("{}", bold("远程仓库:"));
+            for remote in remotes {
+                println!("  {:<10} {}", remote.name, remote.url);
+            }
+        }
+
+        if let Some(ref commits) = report.log {
+            println!();
+            println!("{}", bold("最近的提交:"));
+            for commit in commits {
+                println!(
+                    "  {}  {}  {}",
+                    yellow(&commit.id[..8]),
+                    format_timestamp(commit.time),
+                    commit.message.lines().next().unwrap_or("")
+                );
+            }
+        }
+    }
+}
+
+/// 显示一组文件
+fn print_group(title: &str, files: &[FileStatus], color: fn(String) -> ColoredString) {
+    if files.is_empty() {
+        return;
+    }
+    println!();
+    println!("{} ({}):", bold(title), files.len());
+    for file in files {
+        let path = match file.old_path {
+            Some(ref old_path) => format!("{} -> {}", old_path, file.path),
+            None => file.path.clone(),
+        };
+        println!("  {:<6} {}", file.change.label(), color(path));
+    }
+}
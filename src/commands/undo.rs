@@ -0,0 +1,181 @@
+//! Undo 命令实现
+//!
+//! 根据操作日志撤销最近一次 gt 操作：恢复本地分支和 HEAD，
+//! 并取回该操作创建的 stash。默认与 `git reset` 相同，只重置暂存区，
+//! 撤销的提交中的变更保留在工作区；只有 `--hard` 会丢弃工作区的变更。
+
+use crate::commands::update::UPDATE_STATE_NAME;
+use crate::error::{GtResult, GtError};
+use crate::git::{GitOps, Journal, JournalEntry, StateStore};
+use crate::git::journal::{current_head, RefChange};
+use crate::git::stack::RESTACK_STATE_NAME;
+use crate::plan::Plan;
+use crate::utils::format_timestamp;
+use crate::ui::{print_step, print_success, print_warning, print_info, confirm_action};
+
+/// Undo 命令
+pub struct UndoCommand {
+    /// 只移动引用，保留暂存区和工作区
+    soft: bool,
+    /// 丢弃工作区的变更，包括撤销的提交中的变更
+    hard: bool,
+}
+
+impl UndoCommand {
+    /// 创建新的 Undo 命令
+    pub fn new(soft: bool, hard: bool) -> Self {
+        Self { soft, hard }
+    }
+
+    /// 执行命令
+    pub async fn execute(self) -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+        let repo = git_ops.repository();
+
+        Self::ensure_no_pending(&StateStore::new(repo))?;
+
+        let journal = Journal::new(repo);
+        let Some(entry) = journal.last_undoable()? else {
+            print_info("没有可以撤销的 gt 操作");
+            return Ok(());
+        };
+
+        print_step(&format!(
+            "撤销操作 #{}: gt {} ({})",
+            entry.id, entry.command, format_timestamp(entry.time)
+        ));
+
+        self.check_undoable(&git_ops, &entry)?;
+
+        if self.hard && git_ops.has_uncommitted_changes()?
+            && !confirm_action("工作区的未提交变更将被丢弃，确定继续吗？", false)
+        {
+            return Err(GtError::UserCancelled);
+        }
+
+        // 先切换 HEAD，避免删除当前所在的分支
+        if entry.head_changed() && !entry.head_before.is_empty() {
+            repo.set_head(&entry.head_before)?;
+        }
+
+        let reason = format!("gt undo #{}", entry.id);
+        for change in &entry.changes {
+            repo.restore_reference(&change.name, change.before.as_deref(), &reason)?;
+            print_info(&Self::describe(change));
+        }
+
+        // 默认与 git reset 相同：重置暂存区，撤销的提交中的变更保留在工作区
+        if self.hard {
+            repo.reset_hard("HEAD")?;
+        } else if !self.soft {
+            repo.reset_mixed("HEAD")?;
+        }
+
+        if let Some(ref stash_id) = entry.stash {
+            match repo.find_stash_index(stash_id)? {
+                Some(index) => {
+                    print_step("恢复操作时暂存的变更...");
+                    git_ops.pop_stash(index)?;
+                }
+                None => print_warning("操作时创建的 stash 已不存在，跳过恢复"),
+            }
+        }
+
+        journal.mark_undone(entry.id)?;
+
+        if let Ok(branch) = git_ops.current_branch() {
+            print_success(&format!("已撤销 'gt {}'，当前分支: {}", entry.command, branch));
+        } else {
+            print_success(&format!("已撤销 'gt {}'", entry.command));
+        }
+        print_info("已推送到远程的变更不会被撤销");
+
+        Ok(())
+    }
+
+    /// 生成执行计划，不修改仓库
+    pub fn plan(&self) -> GtResult<Plan> {
+        let git_ops = GitOps::new()?;
+        let repo = git_ops.repository();
+        Self::ensure_no_pending(&StateStore::new(repo))?;
+
+        let mut plan = Plan::new("undo");
+        let Some(entry) = Journal::new(repo).last_undoable()? else {
+            return Ok(plan);
+        };
+        self.check_undoable(&git_ops, &entry)?;
+
+        if entry.head_changed() && !entry.head_before.is_empty() {
+            let head = entry.head_before.strip_prefix("refs/heads/").unwrap_or(&entry.head_before);
+            plan.step(format!("把 HEAD 切换回 {}", head));
+        }
+        for change in &entry.changes {
+            plan.step(Self::describe(change));
+        }
+        if self.hard {
+            plan.step("reset --hard HEAD");
+        } else if !self.soft {
+            plan.step("reset --mixed HEAD（重置暂存区，保留工作区文件）");
+        }
+        if entry.stash.is_some() {
+            plan.step("stash pop 恢复操作时暂存的变更");
+        }
+        plan.step(format!("把操作 #{} (gt {}) 标记为已撤销", entry.id, entry.command));
+
+        Ok(plan)
+    }
+
+    /// 有未完成的 update 或 restack 时返回错误
+    fn ensure_no_pending(store: &StateStore) -> GtResult<()> {
+        if store.exists(UPDATE_STATE_NAME) {
+            return Err(GtError::PreconditionFailed {
+                condition: "有未完成的 gt update，请先运行 'gt update --continue' 或 'gt update --abort'".to_string()
+            });
+        }
+        if store.exists(RESTACK_STATE_NAME) {
+            return Err(GtError::PreconditionFailed {
+                condition: "有未完成的 restack，请先运行 'gt stack restack --continue' 或 'gt stack restack --abort'".to_string()
+            });
+        }
+        Ok(())
+    }
+
+    /// 确认仓库仍处于该操作完成后的状态
+    fn check_undoable(&self, git_ops: &GitOps, entry: &JournalEntry) -> GtResult<()> {
+        let repo = git_ops.repository();
+
+        for change in &entry.changes {
+            if repo.reference_target(&change.name)? != change.after {
+                return Err(GtError::PreconditionFailed {
+                    condition: format!(
+                        "分支 '{}' 在 'gt {}' 之后又有变化，无法安全撤销",
+                        change.short_name(), entry.command
+                    )
+                });
+            }
+        }
+
+        if current_head(repo) != entry.head_after {
+            return Err(GtError::PreconditionFailed {
+                condition: format!("'gt {}' 之后已切换过分支，请先切换回原分支", entry.command)
+            });
+        }
+
+        if !self.soft && !self.hard && git_ops.has_uncommitted_changes()? {
+            return Err(GtError::PreconditionFailed {
+                condition: "工作区有未提交的变更，请先提交或暂存，也可以使用 --soft 保留或 --hard 丢弃".to_string()
+            });
+        }
+
+        Ok(())
+    }
+
+    /// 描述一个引用的恢复动作
+    fn describe(change: &RefChange) -> String {
+        match (&change.before, &change.after) {
+            (None, _) => format!("删除分支 '{}'", change.short_name()),
+            (Some(before), None) => format!("重建分支 '{}' -> {}", change.short_name(), &before[..8]),
+            (Some(before), Some(_)) => format!("恢复分支 '{}' -> {}", change.short_name(), &before[..8]),
+        }
+    }
+}
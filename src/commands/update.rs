@@ -2,12 +2,17 @@
 //! 
 //! 对应 gw update，用于同步当前分支到最新状态
 //! 增强功能：智能 stash 管理、自动冲突检测、回滚机制
+//! 进行中的状态保存在 `.git/gt/update.toml`，支持 --continue / --abort
 
 use crate::error::{GtResult, GtError};
-use crate::git::GitOps;
-use crate::config::ConfigManager;
+use crate::git::{GitOps, NetworkOps, Restacker, Stack, StashPushOptions, StateStore};
+use crate::config::{ConfigManager, RepoConfig};
+use crate::hooks::{Hook, HookContext};
+use crate::plan::Plan;
+use crate::workflow::{Step, WorkflowEngine};
+use async_trait::async_trait;
 use crate::ui::{print_step, print_success, print_warning, print_info, confirm_action};
-use crate::git::network::pull_rebase_with_retry;
+use serde::{Deserialize, Serialize};
 
 /// Update 命令选项
 #[derive(Debug, Clone)]
@@ -30,6 +35,58 @@ impl Default for UpdateOptions {
     }
 }
 
+/// 持久化状态文件名（位于 .git/gt/ 下）
+pub const UPDATE_STATE_NAME: &str = "update";
+
+/// Update 流程所处的步骤
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateStep {
+    /// 已处理未提交的变更，准备更新主分支
+    Prepared,
+    /// 主分支已更新，准备 rebase 功能分支
+    MainUpdated,
+    /// 正在将功能分支 rebase 到主分支
+    Rebasing,
+    /// 同步完成，准备恢复暂存的变更
+    Restoring,
+}
+
+impl std::fmt::Display for UpdateStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Prepared => "准备更新主分支",
+            Self::MainUpdated => "主分支已更新",
+            Self::Rebasing => "rebase 功能分支",
+            Self::Restoring => "恢复暂存的变更",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// 进行中的 update 状态，中断后用于 --continue / --abort
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateState {
+    /// 发起同步时所在的分支
+    pub original_branch: String,
+    /// 发起同步时原分支指向的提交
+    pub original_head: String,
+    /// 主分支名
+    pub main_branch: String,
+    /// 更新前主分支指向的提交
+    pub main_head: String,
+    /// 远程仓库名
+    pub remote: String,
+    /// gt 创建的 stash 提交 ID
+    pub stash_id: Option<String>,
+    /// 是否只更新主分支
+    pub main_only: bool,
+    /// 已到达的步骤
+    pub step: UpdateStep,
+    /// 开始时间
+    pub started_at: String,
+}
+
 /// Update 命令
 pub struct UpdateCommand {
     options: UpdateOptions,
@@ -57,52 +114,317 @@ impl UpdateCommand {
         let git_ops = GitOps::new()?;
         let config_manager = ConfigManager::new(git_ops.repository())?;
         let config = config_manager.repo_config();
+        let store = StateStore::new(git_ops.repository());
         
         // 1. 检查是否在git仓库中
         if !git_ops.is_git_repo() {
             return Err(GtError::NotGitRepository);
         }
         
-        // 2. 获取当前分支
+        // 2. 检查是否有未完成的同步
+        Self::ensure_no_pending(&store)?;
+        
+        // 3. 记录同步前的状态
+        let state = self.initial_state(&git_ops, config)?;
+        
+        // 4. 运行 pre-update 钩子
+        HookContext::collect(git_ops.repository(), Some(&state.main_branch))
+            .run(config, Hook::PreUpdate)?;
+        
+        // 5. 按步骤执行同步，失败时回滚已完成的步骤
+        let mut workflow = Self::workflow(&state);
+        let mut ctx = UpdateContext {
+            git_ops: &git_ops,
+            store: &store,
+            options: &self.options,
+            state,
+        };
+        let result = workflow.run(&mut ctx).await;
+        Self::finish(&ctx, &workflow, result)
+    }
+    
+    /// 生成执行计划，不修改仓库
+    pub fn plan(&self) -> GtResult<Plan> {
+        let git_ops = GitOps::new()?;
+        let config_manager = ConfigManager::new(git_ops.repository())?;
+        let store = StateStore::new(git_ops.repository());
+        
+        Self::ensure_no_pending(&store)?;
+        let config = config_manager.repo_config();
+        let state = self.initial_state(&git_ops, config)?;
+        let mut plan = Plan::new("update");
+        Hook::PreUpdate.plan(&mut plan, config);
+        
+        // 与 handle_uncommitted_changes 的判断一致
+        let mut stashed = false;
+        if !git_ops.is_clean()? && !self.options.force {
+            if self.options.no_stash {
+                return Err(GtError::DirtyWorkingDirectory);
+            }
+            plan.step(format!("stash {} 个文件（含未追踪文件）", git_ops.changed_paths()?.len()));
+            stashed = true;
+        }
+        
+        self.plan_remaining(&mut plan, &git_ops, &state, stashed)?;
+        Hook::PostUpdate.plan(&mut plan, config);
+        Ok(plan)
+    }
+    
+    /// 生成继续同步的执行计划
+    pub fn plan_resume(&self) -> GtResult<Plan> {
+        let git_ops = GitOps::new()?;
+        let config_manager = ConfigManager::load(git_ops.repository())?;
+        let store = StateStore::new(git_ops.repository());
+        
+        let state = Self::load_state(&store)?;
+        let mut plan = Plan::new("update --continue");
+        self.plan_remaining(&mut plan, &git_ops, &state, state.stash_id.is_some())?;
+        Hook::PostUpdate.plan(&mut plan, config_manager.repo_config());
+        Ok(plan)
+    }
+    
+    /// 生成回滚同步的执行计划
+    pub fn plan_abort(&self) -> GtResult<Plan> {
+        let git_ops = GitOps::new()?;
+        let repo = git_ops.repository();
+        let store = StateStore::new(repo);
+        
+        let state = Self::load_state(&store)?;
+        let mut plan = Plan::new("update --abort");
+        
+        if Restacker::new(repo, &state.main_branch).in_progress() {
+            plan.step("中止进行中的 restack");
+        }
+        if repo.rebase_in_progress() {
+            plan.step("rebase --abort");
+        }
+        if git_ops.current_branch().ok().as_deref() != Some(state.original_branch.as_str()) {
+            plan.step(format!("checkout {}", state.original_branch));
+        }
+        if repo.branch_oid(&state.original_branch)? != state.original_head {
+            plan.step(format!("reset --hard {}", &state.original_head[..8]));
+        }
+        if state.main_branch != state.original_branch
+            && repo.branch_oid(&state.main_branch)? != state.main_head
+        {
+            plan.step(format!("恢复主分支 '{}' 到 {}", state.main_branch, &state.main_head[..8]));
+        }
+        if state.stash_id.is_some() {
+            plan.step("stash pop 恢复同步前暂存的变更");
+        }
+        
+        Ok(plan)
+    }
+    
+    /// 继续被中断的同步（`gt update --continue`）
+    pub async fn resume(self) -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+        let store = StateStore::new(git_ops.repository());
+        
+        let state = Self::load_state(&store)?;
+        print_step(&format!(
+            "继续同步分支 '{}' (从步骤: {})...",
+            state.original_branch, state.step
+        ));
+        
+        let mut workflow = Self::workflow(&state);
+        let start = Self::step_index(&state, &workflow);
+        let mut ctx = UpdateContext {
+            git_ops: &git_ops,
+            store: &store,
+            options: &self.options,
+            state,
+        };
+        let result = workflow.run_from(&mut ctx, start).await;
+        Self::finish(&ctx, &workflow, result)
+    }
+    
+    /// 回滚被中断的同步（`gt update --abort`）
+    pub async fn abort(self) -> GtResult<()> {
+        let git_ops = GitOps::new()?;
+        let store = StateStore::new(git_ops.repository());
+        
+        let state = Self::load_state(&store)?;
+        print_step(&format!("回滚分支 '{}' 的同步操作...", state.original_branch));
+        
+        // 中断所在的步骤可能只执行了一部分，一并回滚
+        let mut workflow = Self::workflow(&state);
+        let count = Self::step_index(&state, &workflow) + 1;
+        let original_branch = state.original_branch.clone();
+        let mut ctx = UpdateContext {
+            git_ops: &git_ops,
+            store: &store,
+            options: &self.options,
+            state,
+        };
+        workflow.rollback(&mut ctx, count)?;
+        
+        store.remove(UPDATE_STATE_NAME)?;
+        print_success(&format!("分支 '{}' 已回滚到同步前的状态", original_branch));
+        Ok(())
+    }
+    
+    /// 有未完成的同步时返回错误
+    fn ensure_no_pending(store: &StateStore) -> GtResult<()> {
+        if let Some(state) = store.load::<UpdateState>(UPDATE_STATE_NAME)? {
+            print_warning(&format!(
+                "分支 '{}' 上有一次未完成的同步 (停在: {})",
+                state.original_branch, state.step
+            ));
+            return Err(GtError::PreconditionFailed {
+                condition: "请先运行 'gt update --continue' 继续，或 'gt update --abort' 回滚".to_string()
+            });
+        }
+        Ok(())
+    }
+    
+    /// 同步开始前的状态
+    fn initial_state(&self, git_ops: &GitOps, config: &RepoConfig) -> GtResult<UpdateState> {
         let original_branch = git_ops.current_branch()?;
-        let main_branch = &config.main_branch;
+        let main_branch = config.main_branch.clone();
+        let repo = git_ops.repository();
         
-        // 3. 处理未提交的变更
-        let stash_created = self.handle_uncommitted_changes(&git_ops)?;
+        Ok(UpdateState {
+            original_head: repo.branch_oid(&original_branch)?,
+            main_head: repo.branch_oid(&main_branch)?,
+            original_branch,
+            main_branch,
+            remote: config.remote_name.clone(),
+            stash_id: None,
+            main_only: self.options.main_only,
+            step: UpdateStep::Prepared,
+            started_at: crate::utils::current_timestamp(),
+        })
+    }
+    
+    /// 从 state 所处的步骤开始，把剩余步骤加入计划（与 run_steps 对应）
+    fn plan_remaining(&self, plan: &mut Plan, git_ops: &GitOps, state: &UpdateState, stashed: bool) -> GtResult<()> {
+        let repo = git_ops.repository();
+        let main_branch = &state.main_branch;
+        let rebases_branch = state.original_branch != *main_branch && !state.main_only;
         
-        // 4. 更新主分支
-        if original_branch == *main_branch {
-            // 如果在主分支上，直接更新
-            self.update_main_branch(&git_ops, config, main_branch).await?;
-        } else {
-            // 如果在功能分支上，先切换到主分支更新，再rebase
-            self.update_feature_branch(&git_ops, config, &original_branch, main_branch).await?;
+        if state.step == UpdateStep::Prepared {
+            if repo.rebase_in_progress() {
+                plan.step(format!("继续拉取 '{}' 时中断的 rebase", main_branch));
+            } else {
+                if git_ops.current_branch()? != *main_branch {
+                    plan.step(format!("checkout {}", main_branch));
+                }
+                plan.step(format!("pull --rebase {} {}", state.remote, main_branch));
+            }
         }
         
-        // 5. 恢复 stash（如果之前创建了）
-        if stash_created {
-            self.restore_stash(&git_ops)?;
+        match state.step {
+            UpdateStep::Prepared | UpdateStep::MainUpdated if rebases_branch => {
+                plan.step(format!("checkout {}", state.original_branch));
+                self.plan_rebase(plan, git_ops, state)?;
+            }
+            UpdateStep::Rebasing => {
+                if Restacker::new(repo, main_branch).in_progress() {
+                    plan.step("继续被中断的 restack");
+                } else if repo.rebase_in_progress() {
+                    plan.step(format!("继续 rebase '{}' 到 '{}'", state.original_branch, main_branch));
+                } else {
+                    if git_ops.current_branch()? != state.original_branch {
+                        plan.step(format!("checkout {}", state.original_branch));
+                    }
+                    self.plan_rebase(plan, git_ops, state)?;
+                }
+            }
+            _ => {}
         }
         
-        print_success(&format!("分支 '{}' 已成功同步到最新状态！", original_branch));
+        if stashed {
+            plan.step("stash pop 恢复暂存的变更");
+        }
         Ok(())
     }
     
-    /// 处理未提交的变更
-    fn handle_uncommitted_changes(&self, git_ops: &GitOps) -> GtResult<bool> {
+    /// rebase_branch 的执行计划
+    fn plan_rebase(&self, plan: &mut Plan, git_ops: &GitOps, state: &UpdateState) -> GtResult<()> {
+        let stack = Stack::load(git_ops.repository())?;
+        let branch = &state.original_branch;
+        
+        if stack.contains(branch) || !stack.children_of(branch).is_empty() {
+            let root = stack.root_of(branch);
+            let mut branches = vec![root.clone()];
+            branches.extend(stack.descendants_of(&root));
+            plan.step(format!("按分支栈 restack 到 '{}': {}", state.main_branch, branches.join(" → ")));
+        } else {
+            plan.step(format!("rebase {} 到 {}", branch, state.main_branch));
+        }
+        Ok(())
+    }
+    
+    /// 读取进行中的同步状态
+    fn load_state(store: &StateStore) -> GtResult<UpdateState> {
+        store.load::<UpdateState>(UPDATE_STATE_NAME)?
+            .ok_or_else(|| GtError::PreconditionFailed {
+                condition: "没有进行中的同步操作".to_string()
+            })
+    }
+    
+    /// 同步的步骤，只更新主分支时没有 rebase 这一步
+    fn workflow<'a>(state: &UpdateState) -> WorkflowEngine<'a, UpdateContext<'a>> {
+        let workflow = WorkflowEngine::new("update")
+            .step(StashChanges)
+            .step(UpdateMain);
+        let workflow = if state.original_branch != state.main_branch && !state.main_only {
+            workflow.step(RebaseBranch)
+        } else {
+            workflow
+        };
+        workflow.step(RestoreStash)
+    }
+    
+    /// 保存的步骤在工作流中的位置
+    fn step_index(state: &UpdateState, workflow: &WorkflowEngine<UpdateContext>) -> usize {
+        match state.step {
+            UpdateStep::Prepared => 1,
+            UpdateStep::MainUpdated | UpdateStep::Rebasing => 2,
+            UpdateStep::Restoring => workflow.len() - 1,
+        }
+    }
+    
+    /// 根据工作流的结果清理状态文件或提示如何继续，同步完成时运行 post-update 钩子
+    fn finish(ctx: &UpdateContext, workflow: &WorkflowEngine<UpdateContext>, result: GtResult<()>) -> GtResult<()> {
+        match result {
+            Ok(()) => {
+                ctx.store.remove(UPDATE_STATE_NAME)?;
+                print_success(&format!("分支 '{}' 已成功同步到最新状态！", ctx.state.original_branch));
+                
+                let repo = ctx.git_ops.repository();
+                let config_manager = ConfigManager::load(repo)?;
+                HookContext::collect(repo, Some(&ctx.state.main_branch))
+                    .run(config_manager.repo_config(), Hook::PostUpdate)
+            }
+            Err(e) if workflow.interrupted() => {
+                Self::print_resume_hint(ctx.git_ops, &ctx.state);
+                Err(e)
+            }
+            Err(e) => {
+                // 已回滚到同步前的状态
+                ctx.store.remove(UPDATE_STATE_NAME)?;
+                Err(e)
+            }
+        }
+    }
+    
+    /// 处理未提交的变更，返回 gt 创建的 stash ID
+    fn handle_uncommitted_changes(git_ops: &GitOps, options: &UpdateOptions) -> GtResult<Option<String>> {
         if git_ops.is_clean()? {
-            return Ok(false);
+            return Ok(None);
         }
         
-        if self.options.force {
+        if options.force {
             print_warning("强制模式：忽略未提交的变更");
-            return Ok(false);
+            return Ok(None);
         }
         
         print_warning("检测到未提交的变更或未追踪的文件");
         print_info("同步前需要处理这些变更:");
         
-        if self.options.no_stash {
+        if options.no_stash {
             return Err(GtError::DirtyWorkingDirectory);
         }
         
@@ -125,110 +447,137 @@ impl UpdateCommand {
         // 创建 stash
         print_step("暂存当前变更...");
         let current_branch = git_ops.current_branch()?;
-        let stash_message = format!("WIP on {} before gt update", current_branch);
-        git_ops.create_stash(Some(&stash_message))?;
+        let stash_options = StashPushOptions::new()
+            .with_message(format!("WIP on {} before gt update", current_branch))
+            .include_untracked();
+        let stash_id = git_ops.stash_push(&stash_options)?;
         
         print_success("变更已暂存");
-        Ok(true)
+        Ok(Some(stash_id))
     }
     
-    /// 更新主分支（当前就在主分支上）
-    async fn update_main_branch(&self, git_ops: &GitOps, config: &crate::config::RepoConfig, main_branch: &str) -> GtResult<()> {
-        print_info(&format!("您已在主分支 ({})，正在拉取最新代码...", main_branch));
+    /// 切换到主分支并拉取最新代码
+    async fn update_main_branch(git_ops: &GitOps, state: &UpdateState) -> GtResult<()> {
+        let main_branch = &state.main_branch;
+        
+        // 上次拉取时 rebase 发生冲突，冲突解决后继续即可
+        if git_ops.repository().rebase_in_progress() {
+            print_step("继续拉取时中断的 rebase...");
+            git_ops.repository().rebase_continue()?;
+            print_success(&format!("主分支 '{}' 已更新到最新状态", main_branch));
+            return Ok(());
+        }
+        
+        if state.original_branch == *main_branch {
+            print_info(&format!("您已在主分支 ({})，正在拉取最新代码...", main_branch));
+        } else {
+            print_info(&format!("同步功能分支 '{}' 到最新状态", state.original_branch));
+            print_step(&format!("切换到主分支 '{}'...", main_branch));
+            if git_ops.current_branch()? != *main_branch {
+                git_ops.checkout_branch(main_branch)?;
+            }
+            print_success(&format!("已切换到主分支 '{}'", main_branch));
+        }
+        
+        // 继续执行时同样使用当前配置的重试策略
+        let network = ConfigManager::load(git_ops.repository())?.repo_config().network_config();
         
         print_step("从远程拉取最新更新 (使用 rebase)...");
-        pull_rebase_with_retry(
-            git_ops.repository(),
-            &config.remote_name,
-            Some(main_branch)
-        )?;
+        NetworkOps::new(network).pull_with_retry(git_ops.repository(), &state.remote, Some(main_branch), true)?;
         
         print_success(&format!("主分支 '{}' 已更新到最新状态", main_branch));
         Ok(())
     }
     
-    /// 更新功能分支（需要切换到主分支更新，然后rebase）
-    async fn update_feature_branch(
-        &self, 
-        git_ops: &GitOps, 
-        config: &crate::config::RepoConfig, 
-        original_branch: &str, 
-        main_branch: &str
-    ) -> GtResult<()> {
-        print_info(&format!("同步功能分支 '{}' 到最新状态", original_branch));
-        
-        // 1. 切换到主分支
-        print_step(&format!("1/3: 切换到主分支 '{}'...", main_branch));
-        git_ops.checkout_branch(main_branch)?;
-        print_success(&format!("已切换到主分支 '{}'", main_branch));
-        
-        // 2. 更新主分支
-        print_step("2/3: 从远程拉取最新更新 (使用 rebase)...");
-        pull_rebase_with_retry(
-            git_ops.repository(),
-            &config.remote_name,
-            Some(main_branch)
-        )?;
-        print_success("主分支已更新到最新状态");
-        
-        // 3. 切换回原分支
-        print_step(&format!("3/3: 切换回功能分支 '{}'...", original_branch));
-        git_ops.checkout_branch(original_branch)?;
-        
-        // 4. Rebase 到最新的主分支
-        if !self.options.main_only {
-            print_step(&format!("将功能分支 '{}' rebase 到最新的 '{}'...", original_branch, main_branch));
+    /// 切换回功能分支并开始 rebase
+    fn start_rebase(git_ops: &GitOps, store: &StateStore, state: &mut UpdateState) -> GtResult<()> {
+        print_step(&format!("切换回功能分支 '{}'...", state.original_branch));
+        git_ops.checkout_branch(&state.original_branch)?;
+        
+        // rebase 可能因冲突停下，先记录步骤
+        state.step = UpdateStep::Rebasing;
+        store.save(UPDATE_STATE_NAME, state)?;
+        
+        Self::rebase_branch(git_ops, state)
+    }
+    
+    /// 将功能分支 rebase 到主分支；分支在栈中时按栈 restack 整个栈
+    fn rebase_branch(git_ops: &GitOps, state: &UpdateState) -> GtResult<()> {
+        let repo = git_ops.repository();
+        let stack = Stack::load(repo)?;
+        let branch = &state.original_branch;
+        
+        if stack.contains(branch) || !stack.children_of(branch).is_empty() {
+            let root = stack.root_of(branch);
+            print_step(&format!("从 '{}' 开始按分支栈 restack...", root));
             
-            // 使用 GitOps 的 rebase 方法（如果存在）或者直接调用 git rebase
-            if let Err(e) = self.rebase_branch(git_ops, main_branch) {
-                print_warning("Rebase 操作遇到冲突或失败");
-                print_info("请解决 rebase 冲突，然后运行:");
-                print_info("  git add <冲突文件>");
-                print_info("  git rebase --continue");
-                print_info("或者运行 'git rebase --abort' 取消 rebase");
-                return Err(e);
-            }
+            let mut branches = vec![root.clone()];
+            branches.extend(stack.descendants_of(&root));
+            Restacker::new(repo, &state.main_branch).restack(branches)?;
             
-            print_success(&format!("功能分支 '{}' 已成功 rebase 到最新的 '{}'", original_branch, main_branch));
+            print_success(&format!("分支栈已 restack 到最新的 '{}'", state.main_branch));
+            return Ok(());
         }
         
+        print_step(&format!(
+            "将功能分支 '{}' rebase 到最新的 '{}'...",
+            branch, state.main_branch
+        ));
+        repo.rebase(&state.main_branch)?;
+        
+        print_success(&format!(
+            "功能分支 '{}' 已成功 rebase 到最新的 '{}'",
+            branch, state.main_branch
+        ));
         Ok(())
     }
     
-    /// 执行 rebase 操作
-    fn rebase_branch(&self, git_ops: &GitOps, target_branch: &str) -> GtResult<()> {
-        // 简单实现：使用系统 git 命令
-        // 在实际项目中，可以考虑使用 libgit2 的 rebase 功能
-        use std::process::Command;
-        
-        let output = Command::new("git")
-            .args(&["rebase", target_branch])
-            .output()
-            .map_err(|e| GtError::CommandError {
-                command: "git rebase".to_string(),
-                error: e.to_string(),
-            })?;
-        
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            return Err(GtError::GitOperation {
-                message: format!("Rebase 失败: {}", error_msg),
-            });
+    /// 继续被冲突打断的 rebase
+    fn continue_rebase(git_ops: &GitOps, state: &UpdateState) -> GtResult<()> {
+        let repo = git_ops.repository();
+        
+        let restacker = Restacker::new(repo, &state.main_branch);
+        if restacker.in_progress() {
+            restacker.resume()?;
+            print_success(&format!("分支栈已 restack 到最新的 '{}'", state.main_branch));
+            return Ok(());
         }
         
-        Ok(())
+        if repo.rebase_in_progress() {
+            print_step("继续 rebase...");
+            repo.rebase_continue()?;
+            print_success(&format!(
+                "功能分支 '{}' 已成功 rebase 到最新的 '{}'",
+                state.original_branch, state.main_branch
+            ));
+            return Ok(());
+        }
+        
+        // rebase 已由用户手动完成或尚未开始，确保在正确的分支上再执行一次
+        if git_ops.current_branch()? != state.original_branch {
+            git_ops.checkout_branch(&state.original_branch)?;
+        }
+        Self::rebase_branch(git_ops, state)
     }
     
-    /// 恢复 stash
-    fn restore_stash(&self, git_ops: &GitOps) -> GtResult<()> {
+    /// 恢复 gt 创建的 stash
+    fn restore_stash(git_ops: &GitOps, state: &UpdateState) -> GtResult<()> {
+        let Some(ref stash_id) = state.stash_id else {
+            return Ok(());
+        };
+        
         print_step("尝试恢复之前暂存的变更...");
         
-        // 弹出最新的 stash
-        if let Err(e) = git_ops.pop_stash(0) {
+        let Some(index) = git_ops.repository().find_stash_index(stash_id)? else {
+            print_warning("未找到同步前创建的 stash，可能已被手动恢复或删除");
+            return Ok(());
+        };
+        
+        if let Err(e) = git_ops.pop_stash(index) {
             print_warning("自动恢复暂存失败，可能存在冲突");
             print_info("请手动检查并恢复:");
             print_info("  gt stash list    # 查看暂存列表");
-            print_info("  gt stash pop     # 手动恢复暂存");
+            print_info(&format!("  gt stash pop {}   # 手动恢复暂存", index));
             print_info("  git status       # 查看冲突状态");
             return Err(e);
         }
@@ -236,6 +585,163 @@ impl UpdateCommand {
         print_success("暂存的变更已成功恢复");
         Ok(())
     }
+    
+    /// 提示如何继续或回滚
+    fn print_resume_hint(git_ops: &GitOps, state: &UpdateState) {
+        print_warning(&format!("同步在步骤 '{}' 中断，进度已保存", state.step));
+        
+        if git_ops.repository().rebase_in_progress() {
+            print_info("请解决 rebase 冲突，然后运行:");
+            print_info("  git add <冲突文件>");
+            print_info("  gt update --continue");
+        } else {
+            print_info("问题解决后运行 'gt update --continue' 继续");
+        }
+        print_info("或者运行 'gt update --abort' 回滚到同步前的状态");
+    }
+}
+
+/// 同步步骤之间共享的上下文
+struct UpdateContext<'a> {
+    git_ops: &'a GitOps,
+    store: &'a StateStore,
+    options: &'a UpdateOptions,
+    state: UpdateState,
+}
+
+impl UpdateContext<'_> {
+    /// 记录到达的步骤，中断后从这里继续
+    fn advance(&mut self, step: UpdateStep) -> GtResult<()> {
+        self.state.step = step;
+        self.store.save(UPDATE_STATE_NAME, &self.state)
+    }
+}
+
+/// 暂存未提交的变更，并在任何修改之前保存状态
+struct StashChanges;
+
+#[async_trait(?Send)]
+impl<'a> Step<UpdateContext<'a>> for StashChanges {
+    fn description(&self, _ctx: &UpdateContext<'a>) -> String {
+        "处理未提交的变更".to_string()
+    }
+    
+    async fn run(&mut self, ctx: &mut UpdateContext<'a>) -> GtResult<()> {
+        ctx.state.stash_id = UpdateCommand::handle_uncommitted_changes(ctx.git_ops, ctx.options)?;
+        ctx.store.save(UPDATE_STATE_NAME, &ctx.state)
+    }
+    
+    fn rollback(&mut self, ctx: &mut UpdateContext<'a>) -> GtResult<()> {
+        UpdateCommand::restore_stash(ctx.git_ops, &ctx.state)?;
+        ctx.state.stash_id = None;
+        Ok(())
+    }
+}
+
+/// 切换到主分支并拉取
+struct UpdateMain;
+
+#[async_trait(?Send)]
+impl<'a> Step<UpdateContext<'a>> for UpdateMain {
+    fn description(&self, ctx: &UpdateContext<'a>) -> String {
+        format!("更新主分支 '{}'", ctx.state.main_branch)
+    }
+    
+    async fn run(&mut self, ctx: &mut UpdateContext<'a>) -> GtResult<()> {
+        UpdateCommand::update_main_branch(ctx.git_ops, &ctx.state).await?;
+        ctx.advance(UpdateStep::MainUpdated)
+    }
+    
+    fn rollback(&mut self, ctx: &mut UpdateContext<'a>) -> GtResult<()> {
+        let git_ops = ctx.git_ops;
+        let repo = git_ops.repository();
+        let state = &ctx.state;
+        
+        if repo.rebase_in_progress() {
+            repo.rebase_abort()?;
+        }
+        if git_ops.current_branch().ok().as_deref() != Some(state.original_branch.as_str()) {
+            git_ops.checkout_branch(&state.original_branch)?;
+        }
+        if repo.branch_oid(&state.main_branch)? != state.main_head {
+            if state.main_branch == state.original_branch {
+                git_ops.reset_hard(&state.main_head)?;
+            } else {
+                repo.set_branch_target(&state.main_branch, &state.main_head, "gt update: rollback")?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// 拉取时 rebase 冲突，解决后可以继续
+    fn paused(&self, ctx: &UpdateContext<'a>) -> bool {
+        ctx.git_ops.repository().rebase_in_progress()
+    }
+}
+
+/// 把功能分支（或它所在的分支栈）rebase 到主分支
+struct RebaseBranch;
+
+#[async_trait(?Send)]
+impl<'a> Step<UpdateContext<'a>> for RebaseBranch {
+    fn description(&self, ctx: &UpdateContext<'a>) -> String {
+        format!("rebase '{}' 到 '{}'", ctx.state.original_branch, ctx.state.main_branch)
+    }
+    
+    async fn run(&mut self, ctx: &mut UpdateContext<'a>) -> GtResult<()> {
+        if ctx.state.step == UpdateStep::Rebasing {
+            UpdateCommand::continue_rebase(ctx.git_ops, &ctx.state)?;
+        } else {
+            UpdateCommand::start_rebase(ctx.git_ops, ctx.store, &mut ctx.state)?;
+        }
+        ctx.advance(UpdateStep::Restoring)
+    }
+    
+    fn rollback(&mut self, ctx: &mut UpdateContext<'a>) -> GtResult<()> {
+        let git_ops = ctx.git_ops;
+        let repo = git_ops.repository();
+        let state = &ctx.state;
+        
+        let restacker = Restacker::new(repo, &state.main_branch);
+        if restacker.in_progress() {
+            restacker.abort()?;
+        }
+        if repo.rebase_in_progress() {
+            repo.rebase_abort()?;
+        }
+        if git_ops.current_branch().ok().as_deref() != Some(state.original_branch.as_str()) {
+            git_ops.checkout_branch(&state.original_branch)?;
+        }
+        if repo.branch_oid(&state.original_branch)? != state.original_head {
+            git_ops.reset_hard(&state.original_head)?;
+        }
+        Ok(())
+    }
+    
+    /// rebase 或 restack 冲突，解决后可以继续
+    fn paused(&self, ctx: &UpdateContext<'a>) -> bool {
+        let repo = ctx.git_ops.repository();
+        repo.rebase_in_progress() || Restacker::new(repo, &ctx.state.main_branch).in_progress()
+    }
+}
+
+/// 恢复同步前暂存的变更
+struct RestoreStash;
+
+#[async_trait(?Send)]
+impl<'a> Step<UpdateContext<'a>> for RestoreStash {
+    fn description(&self, _ctx: &UpdateContext<'a>) -> String {
+        "恢复暂存的变更".to_string()
+    }
+    
+    async fn run(&mut self, ctx: &mut UpdateContext<'a>) -> GtResult<()> {
+        UpdateCommand::restore_stash(ctx.git_ops, &ctx.state)
+    }
+    
+    /// 同步已经完成，恢复失败时保留同步结果，由用户手动处理 stash
+    fn paused(&self, _ctx: &UpdateContext<'a>) -> bool {
+        true
+    }
 }
 
 /// 便捷函数：标准更新
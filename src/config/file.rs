@@ -0,0 +1,514 @@
+//! 配置文件模块
+//!
+//! 读取和写入 gt 的 TOML 配置文件：
+//! - 全局配置: `~/.config/gt/config.toml`（遵循 `XDG_CONFIG_HOME`）
+//! - 仓库配置: 仓库根目录下的 `.gt.toml`
+//!
+//! 所有字段都是可选的，未设置的字段由下一层配置或默认值提供。
+
+use crate::error::{GtResult, GtError};
+use crate::forge::ForgeKind;
+use crate::github::MergeStrategy;
+use crate::hooks::Hook;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 仓库配置文件名
+pub const REPO_CONFIG_FILE: &str = ".gt.toml";
+
+/// 全局配置文件名
+pub const GLOBAL_CONFIG_FILE: &str = "config.toml";
+
+/// 配置文件内容
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfigFile {
+    /// 默认远程仓库名
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+    /// 主分支名
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub main_branch: Option<String>,
+    /// 默认合并策略 (rebase / squash / merge)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merge_strategy: Option<String>,
+    /// 默认编辑器命令
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor: Option<String>,
+    /// 代码托管平台 (github / gitlab / gitea)，未设置时根据远程 URL 检测
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forge: Option<String>,
+    /// 网络重试策略
+    #[serde(skip_serializing_if = "RetrySection::is_empty")]
+    pub retry: RetrySection,
+    /// 合并前等待 PR 检查的策略
+    #[serde(skip_serializing_if = "ChecksSection::is_empty")]
+    pub checks: ChecksSection,
+    /// 提交信息规范
+    #[serde(skip_serializing_if = "CommitSection::is_empty")]
+    pub commit: CommitSection,
+    /// 发布版本的设置
+    #[serde(skip_serializing_if = "ReleaseSection::is_empty")]
+    pub release: ReleaseSection,
+    /// 分支管理的设置
+    #[serde(skip_serializing_if = "BranchSection::is_empty")]
+    pub branch: BranchSection,
+    /// GitHub API 设置
+    #[serde(skip_serializing_if = "ForgeSection::is_empty")]
+    pub github: ForgeSection,
+    /// GitLab API 设置
+    #[serde(skip_serializing_if = "ForgeSection::is_empty")]
+    pub gitlab: ForgeSection,
+    /// Gitea API 设置
+    #[serde(skip_serializing_if = "ForgeSection::is_empty")]
+    pub gitea: ForgeSection,
+    /// 命令别名 `[aliases]`，如 `pr = "ship --pr"`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+    /// 自定义工作流 `[workflows.<名称>]`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub workflows: BTreeMap<String, WorkflowSection>,
+    /// 生命周期钩子 `[hooks]`，如 `pre-ship = ["cargo test"]`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub hooks: BTreeMap<Hook, Vec<String>>,
+}
+
+/// 网络重试配置段 `[retry]`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetrySection {
+    /// 最大重试次数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<usize>,
+    /// 重试间隔（秒）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_seconds: Option<u64>,
+}
+
+impl RetrySection {
+    /// 是否没有设置任何字段
+    pub fn is_empty(&self) -> bool {
+        self.max_attempts.is_none() && self.delay_seconds.is_none()
+    }
+}
+
+/// PR 检查配置段 `[checks]`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChecksSection {
+    /// 等待检查的最长时间（秒）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_seconds: Option<u64>,
+    /// 查询检查状态的间隔（秒）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval_seconds: Option<u64>,
+}
+
+impl ChecksSection {
+    /// 是否没有设置任何字段
+    pub fn is_empty(&self) -> bool {
+        self.timeout_seconds.is_none() && self.interval_seconds.is_none()
+    }
+}
+
+/// 提交信息配置段 `[commit]`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CommitSection {
+    /// `gt save` 是否使用约定式提交 (Conventional Commits)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conventional: Option<bool>,
+}
+
+impl CommitSection {
+    /// 是否没有设置任何字段
+    pub fn is_empty(&self) -> bool {
+        self.conventional.is_none()
+    }
+}
+
+/// 发布配置段 `[release]`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReleaseSection {
+    /// 版本标签的前缀，默认为 `v`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag_prefix: Option<String>,
+    /// 发布时更新版本号的文件，如 `["Cargo.toml", "package.json"]`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub version_files: Vec<String>,
+}
+
+impl ReleaseSection {
+    /// 是否没有设置任何字段
+    pub fn is_empty(&self) -> bool {
+        self.tag_prefix.is_none() && self.version_files.is_empty()
+    }
+}
+
+/// 分支配置段 `[branch]`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BranchSection {
+    /// 除主分支外不允许 `gt rm` 删除的分支，支持 `release/*` 形式的前缀匹配
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub protected: Vec<String>,
+    /// 分支名称允许的前缀，如 `["feature/", "fix/"]`，为空时不限制
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub prefixes: Vec<String>,
+    /// 分支名称中必须包含的任务编号（正则表达式），如 `[A-Z]+-[0-9]+`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ticket_pattern: Option<String>,
+    /// 分支名称的最大长度
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    /// 分支名称只使用 ASCII 字符
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ascii_only: Option<bool>,
+}
+
+impl BranchSection {
+    /// 是否没有设置任何字段
+    pub fn is_empty(&self) -> bool {
+        self.protected.is_empty()
+            && self.prefixes.is_empty()
+            && self.ticket_pattern.is_none()
+            && self.max_length.is_none()
+            && self.ascii_only.is_none()
+    }
+}
+
+/// 代码托管平台配置段 `[github]` / `[gitlab]` / `[gitea]`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ForgeSection {
+    /// 访问令牌（GitHub 设置后通过 REST API 操作 PR，不再依赖 gh）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// API 地址（自托管实例或测试用的模拟服务）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+}
+
+impl ForgeSection {
+    /// 是否没有设置任何字段
+    pub fn is_empty(&self) -> bool {
+        self.token.is_none() && self.api_url.is_none()
+    }
+}
+
+/// 自定义工作流配置段 `[workflows.<名称>]`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkflowSection {
+    /// 在 `gt --help` 中显示的说明
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 按顺序执行的步骤，`gt ` 开头的是 gt 命令，其余交给 shell 执行
+    pub steps: Vec<String>,
+}
+
+impl ConfigFile {
+    /// 支持的配置项（带 `.` 的对应同名配置段，如 `retry.*` 对应 `[retry]`）
+    pub const KEYS: [&'static str; 20] = [
+        "remote",
+        "main_branch",
+        "merge_strategy",
+        "editor",
+        "forge",
+        "retry.max_attempts",
+        "retry.delay_seconds",
+        "checks.timeout_seconds",
+        "checks.interval_seconds",
+        "commit.conventional",
+        "release.tag_prefix",
+        "branch.ticket_pattern",
+        "branch.max_length",
+        "branch.ascii_only",
+        "github.token",
+        "github.api_url",
+        "gitlab.token",
+        "gitlab.api_url",
+        "gitea.token",
+        "gitea.api_url",
+    ];
+
+    /// 显示时需要隐藏值的配置项
+    pub fn is_secret(key: &str) -> bool {
+        key.ends_with(".token")
+    }
+
+    /// 只能在全局配置、环境变量或 `-c` 中设置，仓库配置文件中会被忽略的配置项
+    ///
+    /// 仓库配置文件随仓库提交：其中的 `*.api_url` 会让用户的令牌发往仓库指定的主机，
+    /// `*.token` 则不应该提交到仓库中。
+    pub fn is_user_only(key: &str) -> bool {
+        split_forge_key(key).is_some()
+    }
+
+    /// 去掉只能由用户设置的配置项，返回被去掉的配置项
+    pub fn strip_user_only(&mut self) -> Vec<&'static str> {
+        let keys: Vec<&'static str> = Self::KEYS.iter()
+            .copied()
+            .filter(|key| Self::is_user_only(key) && self.get(key).is_some())
+            .collect();
+        for key in &keys {
+            // 平台配置项总是可以清除
+            let _ = self.unset(key);
+        }
+        keys
+    }
+
+    /// 从 `key=value` 形式的参数构建配置（用于命令行 `-c` 参数）
+    pub fn from_pairs(pairs: &[String]) -> GtResult<Self> {
+        let mut file = Self::default();
+        for pair in pairs {
+            let (key, value) = pair.split_once('=').ok_or_else(|| GtError::InvalidInput {
+                input: format!("配置参数应为 key=value 形式: {}", pair)
+            })?;
+            file.set(key.trim(), value.trim())?;
+        }
+        Ok(file)
+    }
+
+    /// 读取配置项的值
+    pub fn get(&self, key: &str) -> Option<String> {
+        match key {
+            "remote" => self.remote.clone(),
+            "main_branch" => self.main_branch.clone(),
+            "merge_strategy" => self.merge_strategy.clone(),
+            "editor" => self.editor.clone(),
+            "forge" => self.forge.clone(),
+            "retry.max_attempts" => self.retry.max_attempts.map(|v| v.to_string()),
+            "retry.delay_seconds" => self.retry.delay_seconds.map(|v| v.to_string()),
+            "checks.timeout_seconds" => self.checks.timeout_seconds.map(|v| v.to_string()),
+            "checks.interval_seconds" => self.checks.interval_seconds.map(|v| v.to_string()),
+            "commit.conventional" => self.commit.conventional.map(|v| v.to_string()),
+            "release.tag_prefix" => self.release.tag_prefix.clone(),
+            "branch.ticket_pattern" => self.branch.ticket_pattern.clone(),
+            "branch.max_length" => self.branch.max_length.map(|v| v.to_string()),
+            "branch.ascii_only" => self.branch.ascii_only.map(|v| v.to_string()),
+            _ => {
+                let (section, field) = self.forge_field(key)?;
+                match field {
+                    "token" => section.token.clone(),
+                    _ => section.api_url.clone(),
+                }
+            }
+        }
+    }
+
+    /// 设置配置项的值
+    pub fn set(&mut self, key: &str, value: &str) -> GtResult<()> {
+        match key {
+            "remote" => self.remote = Some(value.to_string()),
+            "main_branch" => self.main_branch = Some(value.to_string()),
+            "merge_strategy" => {
+                MergeStrategy::from_str(value)
+                    .map_err(|e| GtError::InvalidInput { input: e })?;
+                self.merge_strategy = Some(value.to_string());
+            }
+            "editor" => self.editor = Some(value.to_string()),
+            "forge" => {
+                ForgeKind::from_str(value)
+                    .map_err(|e| GtError::InvalidInput { input: e })?;
+                self.forge = Some(value.to_string());
+            }
+            "retry.max_attempts" => self.retry.max_attempts = Some(parse_number(key, value)?),
+            "retry.delay_seconds" => self.retry.delay_seconds = Some(parse_number(key, value)?),
+            "checks.timeout_seconds" => self.checks.timeout_seconds = Some(parse_number(key, value)?),
+            "checks.interval_seconds" => self.checks.interval_seconds = Some(parse_number(key, value)?),
+            "commit.conventional" => self.commit.conventional = Some(parse_bool(key, value)?),
+            "release.tag_prefix" => self.release.tag_prefix = Some(value.to_string()),
+            "branch.ticket_pattern" => {
+                Regex::new(value).map_err(|e| GtError::InvalidInput {
+                    input: format!("{} 不是有效的正则表达式: {}", key, e)
+                })?;
+                self.branch.ticket_pattern = Some(value.to_string());
+            }
+            "branch.max_length" => self.branch.max_length = Some(parse_number(key, value)?),
+            "branch.ascii_only" => self.branch.ascii_only = Some(parse_bool(key, value)?),
+            _ => {
+                let (section, field) = self.forge_field_mut(key).ok_or_else(|| unknown_key(key))?;
+                match field {
+                    "token" => section.token = Some(value.to_string()),
+                    _ => section.api_url = Some(value.to_string()),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 删除配置项
+    pub fn unset(&mut self, key: &str) -> GtResult<()> {
+        match key {
+            "remote" => self.remote = None,
+            "main_branch" => self.main_branch = None,
+            "merge_strategy" => self.merge_strategy = None,
+            "editor" => self.editor = None,
+            "forge" => self.forge = None,
+            "retry.max_attempts" => self.retry.max_attempts = None,
+            "retry.delay_seconds" => self.retry.delay_seconds = None,
+            "checks.timeout_seconds" => self.checks.timeout_seconds = None,
+            "checks.interval_seconds" => self.checks.interval_seconds = None,
+            "commit.conventional" => self.commit.conventional = None,
+            "release.tag_prefix" => self.release.tag_prefix = None,
+            "branch.ticket_pattern" => self.branch.ticket_pattern = None,
+            "branch.max_length" => self.branch.max_length = None,
+            "branch.ascii_only" => self.branch.ascii_only = None,
+            _ => {
+                let (section, field) = self.forge_field_mut(key).ok_or_else(|| unknown_key(key))?;
+                match field {
+                    "token" => section.token = None,
+                    _ => section.api_url = None,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 平台配置段的配置项（`<平台>.token` / `<平台>.api_url`）
+    fn forge_field(&self, key: &str) -> Option<(&ForgeSection, &'static str)> {
+        let (section, field) = split_forge_key(key)?;
+        let section = match section {
+            "github" => &self.github,
+            "gitlab" => &self.gitlab,
+            _ => &self.gitea,
+        };
+        Some((section, field))
+    }
+
+    /// 平台配置段的配置项（可变）
+    fn forge_field_mut(&mut self, key: &str) -> Option<(&mut ForgeSection, &'static str)> {
+        let (section, field) = split_forge_key(key)?;
+        let section = match section {
+            "github" => &mut self.github,
+            "gitlab" => &mut self.gitlab,
+            _ => &mut self.gitea,
+        };
+        Some((section, field))
+    }
+
+    /// 从文件读取配置，文件不存在时返回 None
+    pub fn load(path: &Path) -> GtResult<Option<Self>> {
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(path)
+            .map_err(|e| GtError::IoError {
+                operation: format!("读取配置文件 {}", path.display()),
+                error: e.to_string(),
+            })?;
+
+        Self::parse(&content)
+            .map(Some)
+            .map_err(|e| GtError::ConfigParseError {
+                reason: format!("{}: {}", path.display(), e)
+            })
+    }
+
+    /// 解析配置内容
+    pub fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    /// 写入配置文件（会创建所在目录）
+    pub fn save(&self, path: &Path) -> GtResult<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| GtError::IoError {
+                    operation: format!("创建配置目录 {}", dir.display()),
+                    error: e.to_string(),
+                })?;
+        }
+
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| GtError::internal_error(format!("序列化配置失败: {}", e)))?;
+
+        fs::write(path, content)
+            .map_err(|e| GtError::IoError {
+                operation: format!("写入配置文件 {}", path.display()),
+                error: e.to_string(),
+            })
+    }
+}
+
+/// 解析数值型配置项
+fn parse_number<T: std::str::FromStr>(key: &str, value: &str) -> GtResult<T> {
+    value.parse().map_err(|_| GtError::InvalidInput {
+        input: format!("配置项 {} 需要一个非负整数，实际为 '{}'", key, value)
+    })
+}
+
+/// 解析布尔型配置项
+fn parse_bool(key: &str, value: &str) -> GtResult<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        _ => Err(GtError::InvalidInput {
+            input: format!("配置项 {} 需要 true 或 false，实际为 '{}'", key, value)
+        }),
+    }
+}
+
+/// 拆分平台配置项，返回平台名和字段名
+fn split_forge_key(key: &str) -> Option<(&str, &'static str)> {
+    let (section, field) = key.split_once('.')?;
+    if !matches!(section, "github" | "gitlab" | "gitea") {
+        return None;
+    }
+    match field {
+        "token" => Some((section, "token")),
+        "api_url" => Some((section, "api_url")),
+        _ => None,
+    }
+}
+
+/// 未知配置项错误
+fn unknown_key(key: &str) -> GtError {
+    GtError::InvalidInput {
+        input: format!("未知的配置项: {}（支持: {}）", key, ConfigFile::KEYS.join(", "))
+    }
+}
+
+/// 全局配置文件路径
+pub fn global_config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".config")))?;
+
+    Some(base.join("gt").join(GLOBAL_CONFIG_FILE))
+}
+
+/// 仓库配置文件路径
+pub fn repo_config_path(workdir: &Path) -> PathBuf {
+    workdir.join(REPO_CONFIG_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_user_only_removes_tokens_and_api_urls() {
+        let mut file = ConfigFile::parse(r#"
+main_branch = "main"
+
+[github]
+token = "secret"
+api_url = "https://evil.example"
+
+[gitea]
+api_url = "https://evil.example/api/v1"
+"#).unwrap();
+
+        assert_eq!(file.strip_user_only(), vec!["github.token", "github.api_url", "gitea.api_url"]);
+        assert!(file.github.is_empty() && file.gitea.is_empty());
+        assert_eq!(file.main_branch.as_deref(), Some("main"));
+    }
+}
@@ -0,0 +1,181 @@
+//! gw 配置迁移
+//!
+//! 查找旧版 gw 脚本的安装位置，解析其 `config_vars.sh` 中的 shell 变量赋值，
+//! 并转换为 gt 的配置文件。
+
+use crate::error::{GtResult, GtError};
+use super::file::ConfigFile;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// gw 配置脚本文件名
+pub const GW_CONFIG_SCRIPT: &str = "config_vars.sh";
+
+/// gw 安装目录中可能存放配置脚本的子目录
+const SCRIPT_SUBDIRS: [&str; 4] = ["", "core_utils", "lib", "scripts"];
+
+/// gw 变量与 gt 配置项的对应关系（同一配置项靠前的变量优先）
+const VARIABLE_MAP: [(&str, &str); 10] = [
+    ("REMOTE_NAME", "remote"),
+    ("MAIN_BRANCH", "main_branch"),
+    ("DEFAULT_MAIN_BRANCH", "main_branch"),
+    ("MAX_ATTEMPTS", "retry.max_attempts"),
+    ("DELAY_SECONDS", "retry.delay_seconds"),
+    ("GW_EDITOR", "editor"),
+    ("DEFAULT_EDITOR", "editor"),
+    ("DEFAULT_IDE", "editor"),
+    ("IDE", "editor"),
+    ("EDITOR", "editor"),
+];
+
+/// 从 gw 配置脚本中解析出的配置
+#[derive(Debug, Clone)]
+pub struct GwConfig {
+    /// 配置脚本路径
+    pub path: PathBuf,
+    /// 脚本中的全部变量赋值
+    pub variables: BTreeMap<String, String>,
+}
+
+impl GwConfig {
+    /// 读取并解析 gw 配置脚本
+    pub fn load(path: &Path) -> GtResult<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| GtError::IoError {
+                operation: format!("读取 gw 配置 {}", path.display()),
+                error: e.to_string(),
+            })?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            variables: parse_shell_assignments(&content),
+        })
+    }
+
+    /// 转换为 gt 配置，同时返回无法识别或取值无效的变量
+    pub fn to_config_file(&self) -> (ConfigFile, Vec<String>) {
+        let mut file = ConfigFile::default();
+        let mut skipped = Vec::new();
+
+        for (var, key) in VARIABLE_MAP {
+            let Some(value) = self.variables.get(var) else { continue };
+            if value.is_empty() || file.get(key).is_some() {
+                continue;
+            }
+            // 运行时才能确定的值（命令替换、变量引用）无法迁移
+            if value.contains('$') || value.contains('`') {
+                skipped.push(format!("{}={}", var, value));
+                continue;
+            }
+            if file.set(key, value).is_err() {
+                skipped.push(format!("{}={}", var, value));
+            }
+        }
+
+        (file, skipped)
+    }
+}
+
+/// 查找 gw 配置脚本
+///
+/// `explicit` 可以是配置脚本本身或 gw 的安装目录；未指定时依次检查
+/// `$GW_HOME`、PATH 中 `gw` 所在目录以及常见的安装位置。
+pub fn find_gw_config(explicit: Option<&Path>) -> Option<PathBuf> {
+    if let Some(path) = explicit {
+        if path.is_file() {
+            return Some(path.to_path_buf());
+        }
+        return find_script_in(path);
+    }
+
+    candidate_dirs().iter().find_map(|dir| find_script_in(dir))
+}
+
+/// 可能的 gw 安装目录
+fn candidate_dirs() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+
+    if let Some(home) = env::var_os("GW_HOME").filter(|dir| !dir.is_empty()) {
+        candidates.push(PathBuf::from(home));
+    }
+
+    // PATH 中的 gw 通常是指向安装目录中脚本的符号链接
+    if let Some(paths) = env::var_os("PATH") {
+        for dir in env::split_paths(&paths) {
+            let gw = dir.join("gw");
+            if gw.is_file() {
+                let resolved = fs::canonicalize(&gw).unwrap_or(gw);
+                candidates.extend(resolved.ancestors().skip(1).take(2).map(Path::to_path_buf));
+            }
+        }
+    }
+
+    if let Some(home) = dirs::home_dir() {
+        for name in [".gw", "git_workflow", ".git_workflow", ".local/share/gw", ".config/gw"] {
+            candidates.push(home.join(name));
+        }
+    }
+
+    candidates
+}
+
+/// 在目录中查找配置脚本
+fn find_script_in(dir: &Path) -> Option<PathBuf> {
+    SCRIPT_SUBDIRS
+        .iter()
+        .map(|sub| dir.join(sub).join(GW_CONFIG_SCRIPT))
+        .find(|path| path.is_file())
+}
+
+/// 解析 shell 脚本中的顶层变量赋值
+///
+/// 支持 `NAME=value`、`export`/`readonly`/`declare` 前缀、引号，
+/// 以及 `${NAME:-default}` 形式的默认值。
+pub fn parse_shell_assignments(content: &str) -> BTreeMap<String, String> {
+    let mut variables = BTreeMap::new();
+
+    for line in content.lines() {
+        let mut line = line.trim();
+        for prefix in ["export ", "readonly ", "declare -r ", "declare -x ", "declare "] {
+            if let Some(rest) = line.strip_prefix(prefix) {
+                line = rest.trim_start();
+            }
+        }
+
+        let Some((name, value)) = line.split_once('=') else { continue };
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            continue;
+        }
+
+        variables.insert(name.to_string(), parse_shell_value(value));
+    }
+
+    variables
+}
+
+/// 解析赋值右侧的值
+fn parse_shell_value(raw: &str) -> String {
+    let raw = raw.trim();
+
+    let value = if let Some(rest) = raw.strip_prefix('"') {
+        rest.split('"').next().unwrap_or_default()
+    } else if let Some(rest) = raw.strip_prefix('\'') {
+        rest.split('\'').next().unwrap_or_default()
+    } else {
+        // 未加引号时，空白或注释之后的内容不属于值
+        raw.split(|c: char| c.is_whitespace() || c == ';')
+            .next()
+            .unwrap_or_default()
+    };
+
+    // ${NAME:-default} / ${NAME:=default} 取默认值
+    if let Some(inner) = value.strip_prefix("${").and_then(|v| v.strip_suffix('}')) {
+        if let Some((_, default)) = inner.split_once(":-").or_else(|| inner.split_once(":=")) {
+            return default.trim_matches(|c| c == '"' || c == '\'').to_string();
+        }
+    }
+
+    value.to_string()
+}
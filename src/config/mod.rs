@@ -1,4 +1,7 @@
+pub mod file;
+pub mod migrate;
 pub mod repository;
 
 // 重新导出常用类型
-pub use repository::{RepoConfig, ConfigManager}; 
\ No newline at end of file
+pub use file::{ConfigFile, WorkflowSection};
+pub use repository::{RepoConfig, ConfigManager, ConfigSource}; 
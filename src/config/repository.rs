@@ -1,10 +1,32 @@
 //! 仓库配置模块
-//! 
+//!
 //! 管理远程仓库、主分支等配置，对应 gw 的 config_vars.sh
+//!
+//! 配置按以下优先级合并（高优先级覆盖低优先级）：
+//! 1. 命令行参数
+//! 2. 环境变量
+//! 3. 仓库配置文件 `.gt.toml`
+//! 4. 全局配置文件 `~/.config/gt/config.toml`
+//! 5. 自动检测 / 内置默认值
+//!
+//! 访问令牌和 API 地址（`*.token` / `*.api_url`）只从全局配置、环境变量和命令行读取，
+//! 仓库配置文件中的会被忽略，避免克隆的仓库把令牌引向其他主机。
 
 use crate::error::{GtResult, GtError};
+use crate::forge::ForgeKind;
 use crate::git::Repository;
+use crate::git::network::NetworkConfig;
+use crate::github::MergeStrategy;
+use crate::hooks::Hook;
+use crate::ui::print_warning;
+use crate::github::pr::{DEFAULT_CHECKS_INTERVAL, DEFAULT_CHECKS_TIMEOUT};
+use super::file::{ConfigFile, WorkflowSection, global_config_path, repo_config_path};
+use std::collections::BTreeMap;
 use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Once, OnceLock};
+use std::time::Duration;
 
 /// 仓库配置
 #[derive(Debug, Clone)]
@@ -17,6 +39,50 @@ pub struct RepoConfig {
     pub max_attempts: usize,
     /// 重试延迟（秒）
     pub delay_seconds: u64,
+    /// 等待 PR 检查的最长时间（秒）
+    pub checks_timeout_seconds: u64,
+    /// 查询 PR 检查状态的间隔（秒）
+    pub checks_interval_seconds: u64,
+    /// 默认合并策略
+    pub merge_strategy: MergeStrategy,
+    /// `gt save` 是否使用约定式提交
+    pub conventional_commits: bool,
+    /// 版本标签的前缀
+    pub tag_prefix: String,
+    /// 发布时更新版本号的文件
+    pub version_files: Vec<String>,
+    /// 除主分支外受保护的分支
+    pub protected_branches: Vec<String>,
+    /// 分支名称允许的前缀
+    pub branch_prefixes: Vec<String>,
+    /// 分支名称中必须包含的任务编号
+    pub ticket_pattern: Option<String>,
+    /// 分支名称的最大长度
+    pub branch_max_length: Option<usize>,
+    /// 分支名称只使用 ASCII 字符
+    pub ascii_branch_names: bool,
+    /// 默认编辑器命令
+    pub editor: Option<String>,
+    /// 代码托管平台，未设置时根据远程 URL 检测
+    pub forge: Option<ForgeKind>,
+    /// GitHub 访问令牌
+    pub github_token: Option<String>,
+    /// GitHub API 地址
+    pub github_api_url: Option<String>,
+    /// GitLab 访问令牌
+    pub gitlab_token: Option<String>,
+    /// GitLab API 地址
+    pub gitlab_api_url: Option<String>,
+    /// Gitea 访问令牌
+    pub gitea_token: Option<String>,
+    /// Gitea API 地址
+    pub gitea_api_url: Option<String>,
+    /// 命令别名
+    pub aliases: BTreeMap<String, String>,
+    /// 自定义工作流
+    pub workflows: BTreeMap<String, WorkflowSection>,
+    /// 生命周期钩子
+    pub hooks: BTreeMap<Hook, Vec<String>>,
 }
 
 impl Default for RepoConfig {
@@ -26,78 +92,125 @@ impl Default for RepoConfig {
             main_branch: "main".to_string(),
             max_attempts: 50,
             delay_seconds: 1,
+            checks_timeout_seconds: DEFAULT_CHECKS_TIMEOUT.as_secs(),
+            checks_interval_seconds: DEFAULT_CHECKS_INTERVAL.as_secs(),
+            merge_strategy: MergeStrategy::default(),
+            conventional_commits: false,
+            tag_prefix: "v".to_string(),
+            version_files: Vec::new(),
+            protected_branches: Vec::new(),
+            branch_prefixes: Vec::new(),
+            ticket_pattern: None,
+            branch_max_length: None,
+            ascii_branch_names: false,
+            editor: None,
+            forge: None,
+            github_token: None,
+            github_api_url: None,
+            gitlab_token: None,
+            gitlab_api_url: None,
+            gitea_token: None,
+            gitea_api_url: None,
+            aliases: BTreeMap::new(),
+            workflows: BTreeMap::new(),
+            hooks: BTreeMap::new(),
         }
     }
 }
 
+/// 配置项的来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// 内置默认值
+    Default,
+    /// 从仓库自动检测（附检测依据）
+    Detected(String),
+    /// 全局配置文件
+    GlobalFile(PathBuf),
+    /// 仓库配置文件
+    RepoFile(PathBuf),
+    /// 环境变量
+    Env(&'static str),
+    /// 命令行参数
+    Cli,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => write!(f, "默认值"),
+            Self::Detected(reason) => write!(f, "自动检测 {}", reason),
+            Self::GlobalFile(path) => write!(f, "全局配置 {}", path.display()),
+            Self::RepoFile(path) => write!(f, "仓库配置 {}", path.display()),
+            Self::Env(name) => write!(f, "环境变量 {}", name),
+            Self::Cli => write!(f, "命令行参数"),
+        }
+    }
+}
+
+/// 没有远程 HEAD 时按顺序尝试的主分支名
+const DEFAULT_BRANCH_CANDIDATES: [&str; 2] = ["main", "master"];
+
+/// 进程级的命令行配置覆盖（`-c key=value`），由 CLI 入口设置一次
+static CLI_OVERRIDES: OnceLock<ConfigFile> = OnceLock::new();
+
 impl RepoConfig {
-    /// 从环境变量和 Git 仓库创建配置
+    /// 从环境变量和 Git 仓库创建配置（不读取配置文件）
     pub fn from_env_and_repo(repo: &Repository) -> GtResult<Self> {
-        let mut config = Self::default();
-        
-        // 从环境变量读取配置
-        if let Ok(remote) = env::var("REMOTE_NAME") {
-            config.remote_name = remote;
-        }
-        
-        if let Ok(attempts) = env::var("MAX_ATTEMPTS") {
-            if let Ok(attempts) = attempts.parse() {
-                config.max_attempts = attempts;
-            }
+        let layers = ConfigLayers {
+            global: None,
+            repo: None,
+            cli: None,
+        };
+        Ok(layers.resolve(repo)?.0)
+    }
+
+    /// 检测主分支名，返回分支名和检测依据
+    ///
+    /// 依次检查：远程 HEAD (`refs/remotes/<remote>/HEAD`)、远程跟踪分支、
+    /// 本地分支，最后回退到 `DEFAULT_MAIN_BRANCH` 或 main。
+    pub fn detect_main_branch(repo: &Repository, remote: &str) -> GtResult<(String, String)> {
+        match Self::detect_remote_main_branch(repo, remote)? {
+            Some(detected) => Ok(detected),
+            None => Self::fallback_main_branch(repo),
+        }
+    }
+
+    /// 从远程引用检测主分支：远程 HEAD，其次是远程跟踪分支 main / master
+    ///
+    /// 两者都没有时返回 None。
+    pub fn detect_remote_main_branch(repo: &Repository, remote: &str) -> GtResult<Option<(String, String)>> {
+        // 远程 HEAD 是远程仓库声明的默认分支
+        if let Some(branch) = repo.remote_head_branch(remote)? {
+            return Ok(Some((branch, format!("{}/HEAD", remote))));
         }
-        
-        if let Ok(delay) = env::var("DELAY_SECONDS") {
-            if let Ok(delay) = delay.parse() {
-                config.delay_seconds = delay;
+
+        // 检查远程跟踪分支
+        for branch in DEFAULT_BRANCH_CANDIDATES {
+            if repo.remote_branch_exists(remote, branch)? {
+                return Ok(Some((branch.to_string(), format!("{}/{}", remote, branch))));
             }
         }
-        
-        // 检测实际的主分支名
-        config.main_branch = Self::detect_main_branch(repo)?;
-        
-        Ok(config)
+
+        Ok(None)
     }
-    
-    /// 检测主分支名（master 或 main）
-    fn detect_main_branch(repo: &Repository) -> GtResult<String> {
+
+    /// 没有远程引用时检测主分支：本地分支，最后回退到 `DEFAULT_MAIN_BRANCH` 或 main
+    fn fallback_main_branch(repo: &Repository) -> GtResult<(String, String)> {
         // 检查本地分支
-        if repo.branch_exists("master")? {
-            return Ok("master".to_string());
-        }
-        
-        if repo.branch_exists("main")? {
-            return Ok("main".to_string());
-        }
-        
-        // 检查远程分支
-        let remotes = repo.list_remotes()?;
-        for remote in &remotes {
-            // 检查 origin/master
-            if Self::remote_branch_exists(repo, &remote.name, "master")? {
-                return Ok("master".to_string());
-            }
-            
-            // 检查 origin/main
-            if Self::remote_branch_exists(repo, &remote.name, "main")? {
-                return Ok("main".to_string());
+        for branch in DEFAULT_BRANCH_CANDIDATES {
+            if repo.branch_exists(branch)? {
+                return Ok((branch.to_string(), format!("本地分支 {}", branch)));
             }
         }
-        
+
         // 默认使用环境变量或 main
-        let default_branch = env::var("DEFAULT_MAIN_BRANCH")
-            .unwrap_or_else(|_| "main".to_string());
-            
-        Ok(default_branch)
-    }
-    
-    /// 检查远程分支是否存在
-    fn remote_branch_exists(repo: &Repository, remote: &str, branch: &str) -> GtResult<bool> {
-        // 这里需要检查远程分支，现在先简单返回 false
-        // TODO: 实现远程分支检查
-        let _ = (repo, remote, branch);
-        Ok(false)
-    }
-    
+        match env::var("DEFAULT_MAIN_BRANCH") {
+            Ok(branch) => Ok((branch, "DEFAULT_MAIN_BRANCH".to_string())),
+            Err(_) => Ok(("main".to_string(), "默认值".to_string())),
+        }
+    }
+
     /// 验证配置是否有效
     pub fn validate(&self, repo: &Repository) -> GtResult<()> {
         // 检查远程仓库是否存在
@@ -106,42 +219,356 @@ impl RepoConfig {
                 message: format!("远程仓库 '{}' 不存在", self.remote_name)
             });
         }
-        
+
         Ok(())
     }
-    
+
     /// 获取主分支的完整远程引用
     pub fn main_branch_remote_ref(&self) -> String {
         format!("{}/{}", self.remote_name, self.main_branch)
     }
+
+    /// 按配置项名称读取生效的值
+    pub fn value_of(&self, key: &str) -> Option<String> {
+        match key {
+            "remote" => Some(self.remote_name.clone()),
+            "main_branch" => Some(self.main_branch.clone()),
+            "merge_strategy" => Some(self.merge_strategy.to_string().to_lowercase()),
+            "editor" => self.editor.clone(),
+            "forge" => self.forge.map(|forge| forge.config_prefix().to_string()),
+            "retry.max_attempts" => Some(self.max_attempts.to_string()),
+            "retry.delay_seconds" => Some(self.delay_seconds.to_string()),
+            "checks.timeout_seconds" => Some(self.checks_timeout_seconds.to_string()),
+            "checks.interval_seconds" => Some(self.checks_interval_seconds.to_string()),
+            "commit.conventional" => Some(self.conventional_commits.to_string()),
+            "release.tag_prefix" => Some(self.tag_prefix.clone()),
+            "branch.ticket_pattern" => self.ticket_pattern.clone(),
+            "branch.max_length" => self.branch_max_length.map(|v| v.to_string()),
+            "branch.ascii_only" => Some(self.ascii_branch_names.to_string()),
+            "github.token" => self.github_token.clone(),
+            "github.api_url" => self.github_api_url.clone(),
+            "gitlab.token" => self.gitlab_token.clone(),
+            "gitlab.api_url" => self.gitlab_api_url.clone(),
+            "gitea.token" => self.gitea_token.clone(),
+            "gitea.api_url" => self.gitea_api_url.clone(),
+            _ => None,
+        }
+    }
+
+    /// 指定平台配置的 API 地址
+    pub fn forge_api_url(&self, kind: ForgeKind) -> Option<&str> {
+        match kind {
+            ForgeKind::GitHub => self.github_api_url.as_deref(),
+            ForgeKind::GitLab => self.gitlab_api_url.as_deref(),
+            ForgeKind::Gitea => self.gitea_api_url.as_deref(),
+        }
+    }
+
+    /// 等待 PR 检查的最长时间
+    pub fn checks_timeout(&self) -> Duration {
+        Duration::from_secs(self.checks_timeout_seconds)
+    }
+
+    /// 查询 PR 检查状态的间隔（至少 1 秒）
+    pub fn checks_interval(&self) -> Duration {
+        Duration::from_secs(self.checks_interval_seconds.max(1))
+    }
+
+    /// 根据重试策略创建网络配置
+    pub fn network_config(&self) -> NetworkConfig {
+        NetworkConfig {
+            max_attempts: self.max_attempts.max(1),
+            delay_seconds: self.delay_seconds,
+            ..NetworkConfig::default()
+        }
+    }
+
+    /// 分支是否受保护：主分支，或与 `branch.protected` 中的名称或 `prefix/*` 匹配
+    pub fn is_protected_branch(&self, branch: &str) -> bool {
+        branch == self.main_branch || self.protected_branches.iter().any(|pattern| {
+            match pattern.strip_suffix('*') {
+                Some(prefix) => branch.starts_with(prefix),
+                None => branch == pattern,
+            }
+        })
+    }
+}
+
+/// 待合并的各层配置
+struct ConfigLayers {
+    global: Option<(PathBuf, ConfigFile)>,
+    repo: Option<(PathBuf, ConfigFile)>,
+    cli: Option<ConfigFile>,
+}
+
+impl ConfigLayers {
+    /// 读取全局和仓库配置文件
+    fn load(repo: &Repository) -> GtResult<Self> {
+        let global = match global_config_path() {
+            Some(path) => ConfigFile::load(&path)?.map(|file| (path, file)),
+            None => None,
+        };
+
+        let repo_path = repo_config_path(repo.path());
+        let repo_file = match ConfigFile::load(&repo_path)? {
+            Some(mut file) => {
+                let ignored = file.strip_user_only();
+                if !ignored.is_empty() {
+                    // 一条命令中配置可能读取多次，只警告一次
+                    static IGNORED_WARNING: Once = Once::new();
+                    IGNORED_WARNING.call_once(|| print_warning(&format!(
+                        "已忽略仓库配置 {} 中的 {}：访问令牌和 API 地址只能在全局配置、环境变量或 -c 中设置",
+                        repo_path.display(), ignored.join("、")
+                    )));
+                }
+                Some((repo_path, file))
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            global,
+            repo: repo_file,
+            cli: CLI_OVERRIDES.get().cloned(),
+        })
+    }
+
+    /// 按优先级合并，返回配置和每个配置项的来源
+    fn resolve(&self, repo: &Repository) -> GtResult<(RepoConfig, BTreeMap<&'static str, ConfigSource>)> {
+        let mut merged = ConfigFile::default();
+        let mut sources = BTreeMap::new();
+
+        // 1. 配置文件：全局在前，仓库在后
+        if let Some((ref path, ref file)) = self.global {
+            merge_layer(&mut merged, &mut sources, file, ConfigSource::GlobalFile(path.clone()));
+        }
+        if let Some((ref path, ref file)) = self.repo {
+            merge_layer(&mut merged, &mut sources, file, ConfigSource::RepoFile(path.clone()));
+        }
+
+        // 别名、工作流和钩子按名称合并，仓库配置覆盖全局配置中的同名项；
+        // 版本文件、受保护分支和分支前缀列表整体覆盖
+        for (_, file) in self.global.iter().chain(self.repo.iter()) {
+            merged.aliases.extend(file.aliases.clone());
+            merged.workflows.extend(file.workflows.clone());
+            merged.hooks.extend(file.hooks.clone());
+            if !file.release.version_files.is_empty() {
+                merged.release.version_files = file.release.version_files.clone();
+            }
+            if !file.branch.protected.is_empty() {
+                merged.branch.protected = file.branch.protected.clone();
+            }
+            if !file.branch.prefixes.is_empty() {
+                merged.branch.prefixes = file.branch.prefixes.clone();
+            }
+        }
+
+        // 2. 环境变量
+        for (var, key) in ENV_VARS {
+            if let Ok(value) = env::var(var) {
+                merged.set(key, &value).map_err(|e| GtError::ConfigError {
+                    message: format!("环境变量 {} 无效: {}", var, e)
+                })?;
+                sources.insert(key, ConfigSource::Env(var));
+            }
+        }
+
+        // 3. 命令行参数
+        if let Some(ref file) = self.cli {
+            merge_layer(&mut merged, &mut sources, file, ConfigSource::Cli);
+        }
+
+        let defaults = RepoConfig::default();
+
+        // 4. 主分支优先使用远程的默认分支，远程引用中没有时才使用配置，都没有时检测本地分支
+        let remote_name = merged.remote.unwrap_or(defaults.remote_name);
+        let main_branch = match (RepoConfig::detect_remote_main_branch(repo, &remote_name)?, merged.main_branch) {
+            (Some((branch, reason)), _) => {
+                sources.insert("main_branch", ConfigSource::Detected(reason));
+                branch
+            }
+            (None, Some(branch)) => branch,
+            (None, None) => {
+                let (branch, reason) = RepoConfig::fallback_main_branch(repo)?;
+                sources.insert("main_branch", ConfigSource::Detected(reason));
+                branch
+            }
+        };
+
+        let merge_strategy = match merged.merge_strategy {
+            Some(ref strategy) => MergeStrategy::from_str(strategy)
+                .map_err(|e| GtError::ConfigError { message: e })?,
+            None => defaults.merge_strategy,
+        };
+
+        let forge = match merged.forge {
+            Some(ref forge) => Some(ForgeKind::from_str(forge)
+                .map_err(|e| GtError::ConfigError { message: e })?),
+            None => None,
+        };
+
+        let config = RepoConfig {
+            remote_name,
+            main_branch,
+            max_attempts: merged.retry.max_attempts.unwrap_or(defaults.max_attempts),
+            delay_seconds: merged.retry.delay_seconds.unwrap_or(defaults.delay_seconds),
+            checks_timeout_seconds: merged.checks.timeout_seconds.unwrap_or(defaults.checks_timeout_seconds),
+            checks_interval_seconds: merged.checks.interval_seconds.unwrap_or(defaults.checks_interval_seconds),
+            merge_strategy,
+            conventional_commits: merged.commit.conventional.unwrap_or(defaults.conventional_commits),
+            tag_prefix: merged.release.tag_prefix.unwrap_or(defaults.tag_prefix),
+            version_files: merged.release.version_files,
+            protected_branches: merged.branch.protected,
+            branch_prefixes: merged.branch.prefixes,
+            ticket_pattern: merged.branch.ticket_pattern,
+            branch_max_length: merged.branch.max_length,
+            ascii_branch_names: merged.branch.ascii_only.unwrap_or(defaults.ascii_branch_names),
+            editor: merged.editor,
+            forge,
+            github_token: merged.github.token,
+            github_api_url: merged.github.api_url,
+            gitlab_token: merged.gitlab.token,
+            gitlab_api_url: merged.gitlab.api_url,
+            gitea_token: merged.gitea.token,
+            gitea_api_url: merged.gitea.api_url,
+            aliases: merged.aliases,
+            workflows: merged.workflows,
+            hooks: merged.hooks,
+        };
+
+        for key in ConfigFile::KEYS {
+            sources.entry(key).or_insert(ConfigSource::Default);
+        }
+
+        Ok((config, sources))
+    }
+}
+
+/// 环境变量与配置项的对应关系
+///
+/// 同一配置项对应多个变量时，靠后的优先（与 gh 一致，`GH_TOKEN` 优先于 `GITHUB_TOKEN`）。
+const ENV_VARS: [(&str, &str); 14] = [
+    ("REMOTE_NAME", "remote"),
+    ("MAIN_BRANCH", "main_branch"),
+    ("GT_MERGE_STRATEGY", "merge_strategy"),
+    ("GT_EDITOR", "editor"),
+    ("GT_FORGE", "forge"),
+    ("MAX_ATTEMPTS", "retry.max_attempts"),
+    ("DELAY_SECONDS", "retry.delay_seconds"),
+    ("GT_CHECKS_TIMEOUT", "checks.timeout_seconds"),
+    ("GT_CONVENTIONAL_COMMITS", "commit.conventional"),
+    ("GITHUB_TOKEN", "github.token"),
+    ("GH_TOKEN", "github.token"),
+    ("GT_GITHUB_API_URL", "github.api_url"),
+    ("GITLAB_TOKEN", "gitlab.token"),
+    ("GITEA_TOKEN", "gitea.token"),
+];
+
+/// 将一层配置合并到结果中，并记录来源
+fn merge_layer(
+    merged: &mut ConfigFile,
+    sources: &mut BTreeMap<&'static str, ConfigSource>,
+    layer: &ConfigFile,
+    source: ConfigSource,
+) {
+    for key in ConfigFile::KEYS {
+        if let Some(value) = layer.get(key) {
+            // 配置文件在读取时已经校验过格式
+            if merged.set(key, &value).is_ok() {
+                sources.insert(key, source.clone());
+            }
+        }
+    }
 }
 
 /// 全局配置管理器
 pub struct ConfigManager {
     repo_config: RepoConfig,
+    sources: BTreeMap<&'static str, ConfigSource>,
+    layers: ConfigLayers,
 }
 
 impl ConfigManager {
     /// 创建配置管理器
     pub fn new(repo: &Repository) -> GtResult<Self> {
-        let repo_config = RepoConfig::from_env_and_repo(repo)?;
-        repo_config.validate(repo)?;
-        
-        Ok(Self { repo_config })
+        let manager = Self::load(repo)?;
+        manager.repo_config.validate(repo)?;
+
+        Ok(manager)
     }
-    
+
+    /// 加载并合并配置，但不校验（用于查看和修改配置）
+    pub fn load(repo: &Repository) -> GtResult<Self> {
+        let layers = ConfigLayers::load(repo)?;
+        let (repo_config, sources) = layers.resolve(repo)?;
+
+        Ok(Self {
+            repo_config,
+            sources,
+            layers,
+        })
+    }
+
+    /// 设置命令行参数覆盖（进程内只生效一次）
+    pub fn set_cli_overrides(overrides: ConfigFile) {
+        let _ = CLI_OVERRIDES.set(overrides);
+    }
+
     /// 获取仓库配置
     pub fn repo_config(&self) -> &RepoConfig {
         &self.repo_config
     }
-    
+
+    /// 获取配置项的来源
+    pub fn source_of(&self, key: &str) -> Option<&ConfigSource> {
+        self.sources.get(key)
+    }
+
+    /// 获取全局配置文件（路径和内容）
+    pub fn global_file(&self) -> Option<(&Path, &ConfigFile)> {
+        self.layers.global.as_ref().map(|(path, file)| (path.as_path(), file))
+    }
+
+    /// 获取仓库配置文件（路径和内容）
+    pub fn repo_file(&self) -> Option<(&Path, &ConfigFile)> {
+        self.layers.repo.as_ref().map(|(path, file)| (path.as_path(), file))
+    }
+
+    /// 获取命令行参数覆盖的配置
+    pub fn cli_overrides(&self) -> Option<&ConfigFile> {
+        self.layers.cli.as_ref()
+    }
+
+    /// 显式配置的主分支及其来源
+    ///
+    /// 远程引用中有默认分支时，生效的主分支是检测结果，配置的值只作为回退。
+    pub fn configured_main_branch(&self) -> Option<(String, ConfigSource)> {
+        if let Some(branch) = self.layers.cli.as_ref().and_then(|file| file.main_branch.clone()) {
+            return Some((branch, ConfigSource::Cli));
+        }
+        for (var, key) in ENV_VARS.iter().rev() {
+            if *key == "main_branch" {
+                if let Ok(branch) = env::var(var) {
+                    return Some((branch, ConfigSource::Env(var)));
+                }
+            }
+        }
+        let files = [
+            self.layers.repo.as_ref().map(|(path, file)| (file, ConfigSource::RepoFile(path.clone()))),
+            self.layers.global.as_ref().map(|(path, file)| (file, ConfigSource::GlobalFile(path.clone()))),
+        ];
+        files.into_iter()
+            .flatten()
+            .find_map(|(file, source)| Some((file.main_branch.clone()?, source)))
+    }
+
     /// 更新主分支配置
     pub fn update_main_branch(&mut self, branch: String) {
         self.repo_config.main_branch = branch;
     }
-    
+
     /// 更新远程仓库配置
     pub fn update_remote_name(&mut self, remote: String) {
         self.repo_config.remote_name = remote;
     }
-} 
\ No newline at end of file
+}
@@ -72,7 +72,7 @@ impl ErrorHandler {
     }
     
     /// 获取错误解决建议
-    fn get_suggestion(&self, error: &GtError) -> Option<String> {
+    pub fn get_suggestion(&self, error: &GtError) -> Option<String> {
         match error {
             GtError::NotInGitRepo => {
                 Some("请在 Git 仓库目录中运行此命令，或使用 'gt init' 初始化仓库".to_string())
@@ -95,6 +95,24 @@ impl ErrorHandler {
             GtError::GitHubAuthError => {
                 Some("使用 'gh auth login' 登录 GitHub，或检查访问令牌".to_string())
             }
+            GtError::ChecksFailed { .. } => {
+                Some("修复失败的检查并推送后，重新运行 'gt ship -a'".to_string())
+            }
+            GtError::ChecksTimeout { .. } => {
+                Some("使用 'gt -c checks.timeout_seconds=<秒> ship -a' 或在配置文件的 [checks] 中延长等待时间".to_string())
+            }
+            GtError::InvalidCommitMessage { .. } => {
+                Some("提交信息格式为 '<类型>(<范围>): <描述>'，如 'feat(save): 支持约定式提交'；不带 -m 运行 'gt save' 可以逐项输入".to_string())
+            }
+            GtError::BranchNamePolicy { .. } => {
+                Some("分支命名规范在配置文件的 [branch] 中定义，可以用 'gt config show' 查看".to_string())
+            }
+            GtError::HookFailed { hook, .. } => {
+                Some(format!("修复 {} 钩子报告的问题后重试，钩子在配置文件的 [hooks] 中定义", hook))
+            }
+            GtError::ForgeError { forge, .. } => {
+                Some(format!("检查 {} 的访问令牌和 API 地址配置（gt config show）", forge))
+            }
             _ => None,
         }
     }
@@ -65,6 +65,16 @@ pub enum GtError {
     #[error("Pull Request 创建失败: {reason}")]
     PullRequestError { reason: String },
     
+    #[error("PR 检查未通过: {checks}")]
+    ChecksFailed { checks: String },
+    
+    #[error("等待 PR 检查超时（{seconds} 秒）")]
+    ChecksTimeout { seconds: u64 },
+    
+    /// GitLab / Gitea 等代码托管平台的 API 错误
+    #[error("{forge} API 错误: {message}")]
+    ForgeError { forge: String, message: String },
+    
     /// 配置错误
     #[error("配置错误: {message}")]
     ConfigError { message: String },
@@ -92,6 +102,9 @@ pub enum GtError {
     #[error("提交信息为空")]
     EmptyCommitMessage,
     
+    #[error("提交信息不符合约定式提交规范: {reason}")]
+    InvalidCommitMessage { reason: String },
+    
     /// 命令执行错误
     #[error("命令执行失败: {command} - {error}")]
     CommandError { command: String, error: String },
@@ -110,6 +123,10 @@ pub enum GtError {
     #[error("前置条件不满足: {condition}")]
     PreconditionFailed { condition: String },
     
+    /// 钩子错误
+    #[error("{hook} 钩子执行失败，已取消操作: {command} ({reason})")]
+    HookFailed { hook: String, command: String, reason: String },
+    
     /// 文件系统错误
     #[error("文件操作失败: {path}")]
     FileSystemError { path: PathBuf },
@@ -143,6 +160,10 @@ pub enum GtError {
     /// 无效的分支名称
     #[error("无效的分支名称: {name}")]
     InvalidBranchName { name: String },
+    
+    /// 分支名称不符合命名规范
+    #[error("分支名称 '{name}' 不符合命名规范: {reason}")]
+    BranchNamePolicy { name: String, reason: String },
 }
 
 /// gt 工具的结果类型
@@ -179,6 +200,7 @@ impl GtError {
                 | Self::NetworkTimeout { .. }
                 | Self::UserCancelled
                 | Self::InvalidInput { .. }
+                | Self::InvalidCommitMessage { .. }
         )
     }
     
@@ -200,6 +222,57 @@ impl GtError {
             _ => ErrorSeverity::Error,
         }
     }
+    
+    /// 错误变体名，用于 JSON 输出等机器可读的场景
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            Self::NotInGitRepo => "NotInGitRepo",
+            Self::NotGitRepository => "NotGitRepository",
+            Self::BranchNotFound { .. } => "BranchNotFound",
+            Self::BranchAlreadyExists { .. } => "BranchAlreadyExists",
+            Self::CurrentBranchNotFound => "CurrentBranchNotFound",
+            Self::UncommittedChanges => "UncommittedChanges",
+            Self::UntrackedFiles => "UntrackedFiles",
+            Self::DirtyWorkingDirectory => "DirtyWorkingDirectory",
+            Self::RemoteNotFound { .. } => "RemoteNotFound",
+            Self::RemoteError { .. } => "RemoteError",
+            Self::PushFailed { .. } => "PushFailed",
+            Self::PullFailed { .. } => "PullFailed",
+            Self::NetworkTimeout { .. } => "NetworkTimeout",
+            Self::NetworkError { .. } => "NetworkError",
+            Self::GitHubError(_) => "GitHubError",
+            Self::GitHubAuthError => "GitHubAuthError",
+            Self::PullRequestError { .. } => "PullRequestError",
+            Self::ChecksFailed { .. } => "ChecksFailed",
+            Self::ChecksTimeout { .. } => "ChecksTimeout",
+            Self::ForgeError { .. } => "ForgeError",
+            Self::ConfigError { .. } => "ConfigError",
+            Self::ConfigFileNotFound { .. } => "ConfigFileNotFound",
+            Self::ConfigParseError { .. } => "ConfigParseError",
+            Self::UserCancelled => "UserCancelled",
+            Self::InvalidInput { .. } => "InvalidInput",
+            Self::EditorError { .. } => "EditorError",
+            Self::EditorNotFound => "EditorNotFound",
+            Self::EmptyCommitMessage => "EmptyCommitMessage",
+            Self::InvalidCommitMessage { .. } => "InvalidCommitMessage",
+            Self::CommandError { .. } => "CommandError",
+            Self::IoError { .. } => "IoError",
+            Self::WorkflowValidationError { .. } => "WorkflowValidationError",
+            Self::WorkflowStepError { .. } => "WorkflowStepError",
+            Self::PreconditionFailed { .. } => "PreconditionFailed",
+            Self::HookFailed { .. } => "HookFailed",
+            Self::FileSystemError { .. } => "FileSystemError",
+            Self::PermissionDenied { .. } => "PermissionDenied",
+            Self::GitOperation { .. } => "GitOperation",
+            Self::GitError(_) => "GitError",
+            Self::StdIoError(_) => "StdIoError",
+            Self::SerializationError(_) => "SerializationError",
+            Self::InternalError { .. } => "InternalError",
+            Self::NotImplemented { .. } => "NotImplemented",
+            Self::InvalidBranchName { .. } => "InvalidBranchName",
+            Self::BranchNamePolicy { .. } => "BranchNamePolicy",
+        }
+    }
 }
 
 /// 错误严重程度
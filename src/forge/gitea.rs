@@ -0,0 +1,563 @@
+//! Gitea 后端
+//!
+//! 通过 Gitea REST API v1 操作 Pull Request（同样适用于 Forgejo）。
+//! API 地址默认为 `<远程主机>/api/v1`，令牌来自 `gitea.token` 或 `GITEA_TOKEN`。
+
+use crate::config::RepoConfig;
+use crate::error::{GtResult, GtError};
+use crate::forge::http::{encode_path_segment, RestClient};
+use crate::forge::{self, AuthStatus, CreateRepoOptions, Forge, ForgeKind, Issue, RemoteUrl};
+use crate::github::checks::{self, Check, CheckState, ChecksStatus, ReviewDecision};
+use crate::github::pr::{parse_pr_number, CreatePrOptions, MergePrOptions, MergeStrategy, PrState, PullRequest};
+use crate::ui::{print_step, print_warning};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+
+/// Gitea 后端
+pub struct GiteaForge {
+    http: RestClient,
+    /// `/repos/{owner}/{repo}`
+    repo_path: String,
+    web_url: String,
+    has_token: bool,
+    verbose: bool,
+}
+
+/// Gitea Pull Request
+#[derive(Debug, Deserialize)]
+struct Pull {
+    number: u64,
+    title: String,
+    html_url: String,
+    head: Branch,
+    base: Branch,
+    state: String,
+    #[serde(default)]
+    merged: bool,
+    #[serde(default)]
+    draft: bool,
+    user: Option<User>,
+    created_at: Option<String>,
+    mergeable: Option<bool>,
+    /// 还没有审阅的审阅请求
+    requested_reviewers: Option<Vec<User>>,
+}
+
+/// 提交的合并状态
+#[derive(Debug, Deserialize)]
+struct CombinedStatus {
+    #[serde(default)]
+    statuses: Vec<CommitStatus>,
+}
+
+/// commit status
+#[derive(Debug, Deserialize)]
+struct CommitStatus {
+    context: String,
+    status: String,
+    target_url: Option<String>,
+}
+
+/// PR 审阅
+#[derive(Debug, Deserialize)]
+struct Review {
+    state: String,
+    user: Option<User>,
+    #[serde(default)]
+    dismissed: bool,
+}
+
+/// Gitea 议题
+#[derive(Debug, Deserialize)]
+struct RepoIssue {
+    number: u64,
+    title: String,
+    html_url: String,
+}
+
+/// PR 的 head / base 分支
+#[derive(Debug, Deserialize)]
+struct Branch {
+    #[serde(rename = "ref")]
+    ref_field: String,
+    sha: Option<String>,
+}
+
+/// Gitea 用户
+#[derive(Debug, Deserialize)]
+struct User {
+    login: String,
+}
+
+/// Gitea 标签
+#[derive(Debug, Deserialize)]
+struct Label {
+    id: u64,
+    name: String,
+}
+
+/// Gitea 仓库
+#[derive(Debug, Deserialize)]
+struct Repo {
+    html_url: String,
+}
+
+impl GiteaForge {
+    /// 创建后端，api_url 形如 `https://gitea.example.com/api/v1`
+    pub fn new(api_url: &str, token: Option<&str>, remote: &RemoteUrl) -> GtResult<Self> {
+        let (owner, repo) = remote.owner_and_repo().ok_or_else(|| GtError::ConfigError {
+            message: format!("无法从远程仓库路径 '{}' 解析 owner/repo", remote.path)
+        })?;
+        let auth = token.map(|token| ("Authorization", format!("token {}", token)));
+
+        Ok(Self {
+            http: RestClient::new(ForgeKind::Gitea, api_url, auth)?,
+            repo_path: format!("/repos/{}/{}", encode_path_segment(owner), encode_path_segment(repo)),
+            web_url: remote.web_url(),
+            has_token: token.is_some(),
+            verbose: false,
+        })
+    }
+
+    /// 根据配置创建，未配置 `gitea.api_url` 时使用远程主机的 `/api/v1`
+    pub fn from_config(config: &RepoConfig, remote: &RemoteUrl) -> GtResult<Self> {
+        let api_url = config.gitea_api_url.clone()
+            .unwrap_or_else(|| format!("{}/api/v1", remote.web_base));
+        Self::new(&api_url, config.gitea_token.as_deref(), remote)
+    }
+
+    /// 设置是否输出详细信息
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Gitea 按 ID 设置标签，把标签名转换为 ID，找不到的标签跳过
+    async fn label_ids(&self, names: &[String]) -> GtResult<Vec<u64>> {
+        let labels: Vec<Label> = self.http
+            .get(&format!("{}/labels", self.repo_path), &[("limit", "100".to_string())])
+            .await?;
+
+        let mut ids = Vec::new();
+        for name in names {
+            match labels.iter().find(|label| &label.name == name) {
+                Some(label) => ids.push(label.id),
+                None => print_warning(&format!("仓库中没有标签 '{}'，跳过", name)),
+            }
+        }
+        Ok(ids)
+    }
+}
+
+#[async_trait]
+impl Forge for GiteaForge {
+    fn kind(&self) -> ForgeKind {
+        ForgeKind::Gitea
+    }
+
+    fn is_available(&self) -> bool {
+        self.has_token
+    }
+
+    fn compare_url(&self, base: &str, head: &str) -> String {
+        format!("{}/compare/{}...{}", self.web_url, base, head)
+    }
+
+    fn change_request_url(&self, number: u32) -> Option<String> {
+        Some(format!("{}/pulls/{}", self.web_url, number))
+    }
+
+    async fn auth_status(&self) -> GtResult<AuthStatus> {
+        if !self.has_token {
+            return Ok(AuthStatus::NotAuthenticated);
+        }
+        let user: User = self.http.get("/user", &[]).await?;
+        Ok(AuthStatus::Authenticated(user.login))
+    }
+
+    async fn create_change_request(&self, options: &CreatePrOptions) -> GtResult<PullRequest> {
+        let (title, body) = forge::title_and_body(options)?;
+        // Gitea 通过标题前缀标记草稿
+        let title = if options.draft { format!("WIP: {}", title) } else { title };
+
+        if self.verbose {
+            print_step(&format!("调用 Gitea API 创建 PR: {} -> {}", options.head_branch, options.base_branch));
+        }
+
+        let mut request = json!({
+            "head": options.head_branch,
+            "base": options.base_branch,
+            "title": title,
+            "body": body.unwrap_or_default(),
+        });
+        if !options.labels.is_empty() {
+            request["labels"] = json!(self.label_ids(&options.labels).await?);
+        }
+        if options.milestone.is_some() {
+            print_warning("Gitea 后端暂不支持按名称设置里程碑，已忽略");
+        }
+
+        let pull: Pull = self.http.post(&format!("{}/pulls", self.repo_path), &request).await?;
+
+        if !options.reviewers.is_empty() {
+            let _: serde_json::Value = self.http
+                .post(
+                    &format!("{}/pulls/{}/requested_reviewers", self.repo_path, pull.number),
+                    &json!({ "reviewers": options.reviewers }),
+                )
+                .await?;
+        }
+
+        Ok(convert_pull(pull))
+    }
+
+    async fn merge_change_request(&self, id: &str, options: &MergePrOptions) -> GtResult<PullRequest> {
+        let number = parse_pr_number(id)?;
+        if options.wait_for_checks {
+            checks::wait_for_checks(|| self.checks_status(id), options.checks_timeout, options.checks_interval).await?;
+        }
+
+        let method = match options.strategy {
+            MergeStrategy::Rebase => "rebase",
+            MergeStrategy::Squash => "squash",
+            MergeStrategy::Merge => "merge",
+        };
+
+        let mut body = json!({
+            "Do": method,
+            "delete_branch_after_merge": options.delete_branch,
+            "merge_when_checks_succeed": options.auto_merge,
+        });
+        if let Some(ref message) = options.commit_message {
+            body["MergeTitleField"] = json!(message);
+        }
+
+        if self.verbose {
+            print_step(&format!("调用 Gitea API 合并 PR #{} (策略: {})", number, options.strategy));
+        }
+
+        // 成功时返回空响应，不可合并时返回 405 / 409
+        let _: serde_json::Value = self.http
+            .post(&format!("{}/pulls/{}/merge", self.repo_path, number), &body)
+            .await?;
+        self.get_change_request(id).await
+    }
+
+    async fn get_change_request(&self, id: &str) -> GtResult<PullRequest> {
+        let number = parse_pr_number(id)?;
+        let pull: Pull = self.http.get(&format!("{}/pulls/{}", self.repo_path, number), &[]).await?;
+        Ok(convert_pull(pull))
+    }
+
+    async fn checks_status(&self, id: &str) -> GtResult<ChecksStatus> {
+        let number = parse_pr_number(id)?;
+        let pull: Pull = self.http.get(&format!("{}/pulls/{}", self.repo_path, number), &[]).await?;
+        let sha = pull.head.sha.unwrap_or_default();
+
+        let combined: CombinedStatus = self.http
+            .get(&format!("{}/commits/{}/status", self.repo_path, sha), &[("limit", "100".to_string())])
+            .await?;
+        let checks = combined.statuses
+            .into_iter()
+            .map(|status| Check {
+                // warning 不影响合并
+                state: match status.status.as_str() {
+                    "warning" => CheckState::Skipped,
+                    state => CheckState::from_commit_status(state),
+                },
+                name: status.context,
+                url: status.target_url.filter(|url| !url.is_empty()),
+            })
+            .collect();
+
+        // 按每个审阅者最后的结论判断，被驳回的审阅不算
+        let reviews: Vec<Review> = self.http
+            .get(&format!("{}/pulls/{}/reviews", self.repo_path, number), &[("limit", "100".to_string())])
+            .await?;
+        let mut latest = std::collections::HashMap::new();
+        for review in reviews {
+            if review.dismissed {
+                continue;
+            }
+            if matches!(review.state.as_str(), "APPROVED" | "REQUEST_CHANGES") {
+                latest.insert(review.user.map(|user| user.login).unwrap_or_default(), review.state);
+            }
+        }
+        let review = if latest.values().any(|state| state == "REQUEST_CHANGES") {
+            ReviewDecision::ChangesRequested
+        } else if latest.values().any(|state| state == "APPROVED") {
+            ReviewDecision::Approved
+        } else if pull.requested_reviewers.is_some_and(|reviewers| !reviewers.is_empty()) {
+            ReviewDecision::ReviewRequired
+        } else {
+            ReviewDecision::NotRequired
+        };
+
+        Ok(ChecksStatus { checks, review })
+    }
+
+    async fn list_change_requests(&self, state: Option<&str>, limit: Option<u32>) -> GtResult<Vec<PullRequest>> {
+        let requested = state.map(|s| s.to_lowercase());
+        let api_state = match requested.as_deref() {
+            None | Some("open") => "open",
+            Some("closed") | Some("merged") => "closed",
+            Some("all") => "all",
+            Some(other) => {
+                return Err(GtError::InvalidInput {
+                    input: format!("不支持的 PR 状态: {}（支持: open, closed, merged, all）", other)
+                });
+            }
+        };
+
+        let limit = limit.unwrap_or(30).clamp(1, 100);
+        let pulls: Vec<Pull> = self.http
+            .get(
+                &format!("{}/pulls", self.repo_path),
+                &[("state", api_state.to_string()), ("limit", limit.to_string())],
+            )
+            .await?;
+
+        let mut prs: Vec<PullRequest> = pulls.into_iter().map(convert_pull).collect();
+        if requested.as_deref() == Some("merged") {
+            prs.retain(|pr| pr.state == PrState::Merged);
+        }
+        Ok(prs)
+    }
+
+    async fn get_issue(&self, number: u32) -> GtResult<Issue> {
+        let issue: RepoIssue = self.http.get(&format!("{}/issues/{}", self.repo_path, number), &[]).await?;
+        Ok(Issue { number: issue.number as u32, title: issue.title, url: issue.html_url })
+    }
+
+    async fn create_repo(&self, options: &CreateRepoOptions) -> GtResult<String> {
+        let body = json!({
+            "name": options.name,
+            "description": options.description.clone().unwrap_or_default(),
+            "private": options.private,
+        });
+        let repo: Repo = self.http.post("/user/repos", &body).await?;
+        Ok(repo.html_url)
+    }
+}
+
+/// 转换为 gt 的 PR 模型
+fn convert_pull(pull: Pull) -> PullRequest {
+    let draft = pull.draft || pull.title.starts_with("WIP:");
+    let state = if pull.merged {
+        PrState::Merged
+    } else if pull.state == "closed" {
+        PrState::Closed
+    } else if draft {
+        PrState::Draft
+    } else {
+        PrState::Open
+    };
+
+    PullRequest {
+        number: pull.number as u32,
+        title: pull.title,
+        url: pull.html_url,
+        head_branch: pull.head.ref_field,
+        head_sha: pull.head.sha,
+        base_branch: pull.base.ref_field,
+        state,
+        author: pull.user.map(|user| user.login),
+        created_at: pull.created_at,
+        mergeable: pull.mergeable,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::mock::MockServer;
+    use serde_json::Value;
+
+    /// 模拟服务返回的 PR
+    fn pull(number: u64, state: &str, merged: bool) -> Value {
+        json!({
+            "number": number,
+            "title": "添加登录",
+            "html_url": format!("https://gitea.test/o/r/pulls/{}", number),
+            "head": { "ref": "feature/login", "sha": "abc123" },
+            "base": { "ref": "main", "sha": "def456" },
+            "state": state,
+            "merged": merged,
+            "user": { "login": "alice" },
+            "mergeable": true,
+        })
+    }
+
+    fn forge(server: &MockServer) -> GiteaForge {
+        let remote = RemoteUrl::parse("https://gitea.test/o/r.git").unwrap();
+        GiteaForge::new(&format!("{}/api/v1", server.url()), Some("secret"), &remote).unwrap()
+    }
+
+    #[tokio::test]
+    async fn create_sends_label_ids_and_reviewers() {
+        let server = MockServer::start();
+        server.route("GET", "/api/v1/repos/o/r/labels", 200, json!([
+            { "id": 1, "name": "bug" },
+            { "id": 2, "name": "enhancement" },
+        ]));
+        server.route("POST", "/api/v1/repos/o/r/pulls", 201, pull(4, "open", false));
+        server.route("POST", "/api/v1/repos/o/r/pulls/4/requested_reviewers", 201, json!([]));
+
+        let options = CreatePrOptions::new("feature/login".to_string(), "main".to_string())
+            .with_title("添加登录".to_string())
+            .as_draft()
+            .add_label("enhancement".to_string())
+            .add_label("missing".to_string())
+            .add_reviewer("bob".to_string());
+        let pr = forge(&server).create_change_request(&options).await.unwrap();
+
+        assert_eq!(pr.number, 4);
+        assert_eq!(pr.head_sha.as_deref(), Some("abc123"));
+        assert_eq!(pr.url, "https://gitea.test/o/r/pulls/4");
+
+        let request = server.request("POST", "/api/v1/repos/o/r/pulls");
+        assert_eq!(request.header("authorization"), Some("token secret"));
+        assert_eq!(request.body, json!({
+            "head": "feature/login",
+            "base": "main",
+            "title": "WIP: 添加登录",
+            "body": "",
+            "labels": [2],
+        }));
+        let reviewers = server.request("POST", "/api/v1/repos/o/r/pulls/4/requested_reviewers");
+        assert_eq!(reviewers.body, json!({ "reviewers": ["bob"] }));
+    }
+
+    #[tokio::test]
+    async fn get_maps_state() {
+        let server = MockServer::start();
+        server.route("GET", "/api/v1/repos/o/r/pulls/1", 200, pull(1, "open", false));
+        server.route("GET", "/api/v1/repos/o/r/pulls/2", 200, pull(2, "closed", true));
+        server.route("GET", "/api/v1/repos/o/r/pulls/3", 200, pull(3, "closed", false));
+        let mut wip = pull(4, "open", false);
+        wip["title"] = json!("WIP: 添加登录");
+        server.route("GET", "/api/v1/repos/o/r/pulls/4", 200, wip);
+
+        let forge = forge(&server);
+        let open = forge.get_change_request("1").await.unwrap();
+        assert_eq!(open.state, PrState::Open);
+        assert_eq!(open.author.as_deref(), Some("alice"));
+        assert_eq!(forge.get_change_request("#2").await.unwrap().state, PrState::Merged);
+        assert_eq!(forge.get_change_request("3").await.unwrap().state, PrState::Closed);
+        assert_eq!(forge.get_change_request("4").await.unwrap().state, PrState::Draft);
+    }
+
+    #[tokio::test]
+    async fn merge_sends_strategy() {
+        let server = MockServer::start();
+        server.route("POST", "/api/v1/repos/o/r/pulls/5/merge", 200, Value::Null);
+        server.route("GET", "/api/v1/repos/o/r/pulls/5", 200, pull(5, "closed", true));
+        server.route("POST", "/api/v1/repos/o/r/pulls/6/merge", 405, json!({ "message": "Please try again later" }));
+
+        let forge = forge(&server);
+        let options = MergePrOptions::new(MergeStrategy::Rebase)
+            .skip_checks()
+            .delete_branch()
+            .with_commit_message("添加登录".to_string());
+        // 合并后重新读取 PR，返回实际的状态
+        let pr = forge.merge_change_request("5", &options).await.unwrap();
+        assert_eq!(pr.state, PrState::Merged);
+
+        let request = server.request("POST", "/api/v1/repos/o/r/pulls/5/merge");
+        assert_eq!(request.body, json!({
+            "Do": "rebase",
+            "delete_branch_after_merge": true,
+            "merge_when_checks_succeed": false,
+            "MergeTitleField": "添加登录",
+        }));
+
+        let err = forge.merge_change_request("6", &options).await.unwrap_err();
+        assert!(err.to_string().contains("HTTP 405: Please try again later"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn list_merged_queries_closed_and_filters() {
+        let server = MockServer::start();
+        server.route("GET", "/api/v1/repos/o/r/pulls", 200, json!([
+            pull(1, "closed", true),
+            pull(2, "closed", false),
+        ]));
+
+        let forge = forge(&server);
+        let merged = forge.list_change_requests(Some("merged"), Some(10)).await.unwrap();
+        assert_eq!(merged.iter().map(|pr| pr.number).collect::<Vec<_>>(), vec![1]);
+        let request = server.request("GET", "/api/v1/repos/o/r/pulls");
+        assert_eq!(request.query("state"), Some("closed"));
+        assert_eq!(request.query("limit"), Some("10"));
+
+        let closed = forge.list_change_requests(Some("closed"), None).await.unwrap();
+        assert_eq!(closed.iter().map(|pr| pr.state.clone()).collect::<Vec<_>>(), vec![PrState::Merged, PrState::Closed]);
+        assert_eq!(server.request("GET", "/api/v1/repos/o/r/pulls").query("limit"), Some("30"));
+
+        for (state, expected) in [(None, "open"), (Some("OPEN"), "open"), (Some("all"), "all")] {
+            forge.list_change_requests(state, None).await.unwrap();
+            assert_eq!(server.request("GET", "/api/v1/repos/o/r/pulls").query("state"), Some(expected));
+        }
+        assert!(forge.list_change_requests(Some("draft"), None).await.is_err());
+    }
+
+    /// PR #5 的 commit status 和审阅
+    fn checks_server(statuses: Value, reviews: Value) -> MockServer {
+        let server = MockServer::start();
+        let mut pr = pull(5, "open", false);
+        pr["requested_reviewers"] = json!([{ "login": "carol" }]);
+        server.route("GET", "/api/v1/repos/o/r/pulls/5", 200, pr);
+        server.route("GET", "/api/v1/repos/o/r/commits/abc123/status", 200, json!({
+            "state": "pending",
+            "statuses": statuses,
+        }));
+        server.route("GET", "/api/v1/repos/o/r/pulls/5/reviews", 200, reviews);
+        server.route("POST", "/api/v1/repos/o/r/pulls/5/merge", 200, Value::Null);
+        server
+    }
+
+    #[tokio::test]
+    async fn checks_status_reads_commit_statuses_and_reviews() {
+        let server = checks_server(json!([
+            { "context": "ci/build", "status": "success", "target_url": "https://ci.test/build" },
+            { "context": "ci/lint", "status": "warning", "target_url": "" },
+            { "context": "ci/test", "status": "pending" },
+        ]), json!([]));
+
+        let status = forge(&server).checks_status("5").await.unwrap();
+        let checks: Vec<(&str, CheckState, Option<&str>)> = status.checks.iter()
+            .map(|check| (check.name.as_str(), check.state, check.url.as_deref()))
+            .collect();
+        assert_eq!(checks, vec![
+            ("ci/build", CheckState::Success, Some("https://ci.test/build")),
+            ("ci/lint", CheckState::Skipped, None),
+            ("ci/test", CheckState::Pending, None),
+        ]);
+        assert_eq!(status.review, ReviewDecision::ReviewRequired);
+
+        let reviews = json!([
+            { "state": "REQUEST_CHANGES", "user": { "login": "alice" }, "dismissed": true },
+            { "state": "REQUEST_CHANGES", "user": { "login": "bob" } },
+            { "state": "APPROVED", "user": { "login": "bob" } },
+        ]);
+        let server = checks_server(json!([]), reviews);
+        assert_eq!(forge(&server).checks_status("5").await.unwrap().review, ReviewDecision::Approved);
+    }
+
+    #[tokio::test]
+    async fn merge_waits_for_checks() {
+        let approved = json!([{ "state": "APPROVED", "user": { "login": "carol" } }]);
+        let server = checks_server(json!([{ "context": "ci/build", "status": "success" }]), approved);
+        // 设置为检查通过后合并时 PR 仍处于打开状态
+        let options = MergePrOptions::new(MergeStrategy::Merge).enable_auto_merge();
+        let pr = forge(&server).merge_change_request("5", &options).await.unwrap();
+        assert_eq!(pr.state, PrState::Open);
+        assert_eq!(server.request("POST", "/api/v1/repos/o/r/pulls/5/merge").body["merge_when_checks_succeed"], true);
+
+        let server = checks_server(json!([{ "context": "ci/test", "status": "failure" }]), json!([]));
+        let err = forge(&server).merge_change_request("5", &MergePrOptions::new(MergeStrategy::Merge)).await.unwrap_err();
+        assert!(matches!(err, GtError::ChecksFailed { ref checks } if checks == "ci/test"), "{}", err);
+        assert!(!server.requests().iter().any(|request| request.method == "POST"));
+    }
+}
@@ -0,0 +1,153 @@
+//! GitHub 后端
+//!
+//! 配置了令牌时通过 REST API 操作，否则使用 GitHub CLI (gh)。
+
+use crate::config::RepoConfig;
+use crate::error::{GtResult, GtError};
+use crate::forge::{AuthStatus, CreateRepoOptions, Forge, ForgeKind, Issue, RemoteUrl};
+use crate::git::Repository;
+#[cfg(feature = "github")]
+use crate::github::GithubApi;
+use crate::github::{GithubAuth, GithubCli, PullRequestManager};
+use crate::github::checks::ChecksStatus;
+use crate::github::pr::{CreatePrOptions, MergePrOptions, PullRequest};
+use async_trait::async_trait;
+
+/// GitHub 后端
+pub struct GithubForge {
+    manager: PullRequestManager,
+    cli: GithubCli,
+    #[cfg(feature = "github")]
+    api: Option<GithubApi>,
+    web_url: Option<String>,
+}
+
+impl GithubForge {
+    /// 根据配置创建，配置了 `github.token` 时使用 REST API
+    pub fn from_config(config: &RepoConfig, repo: &Repository, remote: Option<&RemoteUrl>, verbose: bool) -> GtResult<Self> {
+        let cli = GithubCli::new(verbose);
+
+        #[cfg(feature = "github")]
+        let api = GithubApi::from_config(config, repo)?;
+        #[cfg(feature = "github")]
+        let manager = match api {
+            Some(ref api) => PullRequestManager::with_api(api.clone(), verbose),
+            None => PullRequestManager::new(cli.clone()),
+        };
+
+        #[cfg(not(feature = "github"))]
+        let _ = (config, repo);
+        #[cfg(not(feature = "github"))]
+        let manager = PullRequestManager::new(cli.clone());
+
+        Ok(Self {
+            manager,
+            cli,
+            #[cfg(feature = "github")]
+            api,
+            web_url: remote.map(RemoteUrl::web_url),
+        })
+    }
+
+    /// 使用的 PR 操作管理器
+    pub fn manager(&self) -> &PullRequestManager {
+        &self.manager
+    }
+}
+
+#[async_trait]
+impl Forge for GithubForge {
+    fn kind(&self) -> ForgeKind {
+        ForgeKind::GitHub
+    }
+
+    fn is_available(&self) -> bool {
+        self.manager.is_available()
+    }
+
+    fn compare_url(&self, base: &str, head: &str) -> String {
+        match self.web_url {
+            Some(ref web_url) => format!("{}/compare/{}...{}", web_url, base, head),
+            None => format!("{}...{}", base, head),
+        }
+    }
+
+    fn change_request_url(&self, number: u32) -> Option<String> {
+        self.web_url.as_ref().map(|web_url| format!("{}/pull/{}", web_url, number))
+    }
+
+    async fn auth_status(&self) -> GtResult<AuthStatus> {
+        #[cfg(feature = "github")]
+        let auth = match self.api {
+            Some(ref api) => api.auth_status().await?,
+            None => self.cli.check_auth()?,
+        };
+        #[cfg(not(feature = "github"))]
+        let auth = self.cli.check_auth()?;
+
+        Ok(match auth {
+            GithubAuth::Authenticated(user) => AuthStatus::Authenticated(user),
+            GithubAuth::NotAuthenticated => AuthStatus::NotAuthenticated,
+            GithubAuth::Unknown => AuthStatus::Unknown,
+        })
+    }
+
+    async fn create_change_request(&self, options: &CreatePrOptions) -> GtResult<PullRequest> {
+        self.manager.create_pr(options.clone()).await
+    }
+
+    async fn merge_change_request(&self, id: &str, options: &MergePrOptions) -> GtResult<PullRequest> {
+        self.manager.merge_pr(id, options.clone()).await?;
+        self.manager.get_pr_info(id).await
+    }
+
+    async fn get_change_request(&self, id: &str) -> GtResult<PullRequest> {
+        self.manager.get_pr_info(id).await
+    }
+
+    async fn checks_status(&self, id: &str) -> GtResult<ChecksStatus> {
+        self.manager.checks_status(id).await
+    }
+
+    async fn list_change_requests(&self, state: Option<&str>, limit: Option<u32>) -> GtResult<Vec<PullRequest>> {
+        self.manager.list_prs(state, limit).await
+    }
+
+    async fn get_issue(&self, number: u32) -> GtResult<Issue> {
+        #[cfg(feature = "github")]
+        if let Some(ref api) = self.api {
+            return api.get_issue(number).await;
+        }
+
+        let output = self.cli.execute_command(&["issue", "view", &number.to_string(), "--json", "number,title,url"])?;
+        serde_json::from_str(&output).map_err(|e| GtError::CommandError {
+            command: "gh issue view".to_string(),
+            error: format!("解析 gh 输出失败: {}", e),
+        })
+    }
+
+    async fn create_repo(&self, options: &CreateRepoOptions) -> GtResult<String> {
+        #[cfg(feature = "github")]
+        if let Some(ref api) = self.api {
+            return api.create_repo(&options.name, options.description.as_deref(), options.private).await;
+        }
+
+        let mut args = vec![
+            "repo", "create", options.name.as_str(),
+            if options.private { "--private" } else { "--public" },
+        ];
+        if let Some(ref description) = options.description {
+            args.extend(["--description", description.as_str()]);
+        }
+
+        let output = self.cli.execute_command(&args)?;
+        output.lines()
+            .rev()
+            .find(|line| line.starts_with("https://"))
+            .map(|line| line.trim().to_string())
+            .ok_or_else(|| GtError::CommandError {
+                command: "gh repo create".to_string(),
+                error: format!("无法从输出中获取仓库地址: {}", output),
+            })
+    }
+}
@@ -0,0 +1,590 @@
+//! GitLab 后端
+//!
+//! 通过 GitLab REST API v4 操作 Merge Request。
+//! API 地址默认为 `<远程主机>/api/v4`，令牌来自 `gitlab.token` 或 `GITLAB_TOKEN`。
+
+use crate::config::RepoConfig;
+use crate::error::{GtResult, GtError};
+use crate::forge::http::{encode_path_segment, RestClient};
+use crate::forge::{self, AuthStatus, CreateRepoOptions, Forge, ForgeKind, Issue, RemoteUrl};
+use crate::github::checks::{self, Check, CheckState, ChecksStatus, ReviewDecision};
+use crate::github::pr::{parse_pr_number, CreatePrOptions, MergePrOptions, MergeStrategy, PrState, PullRequest};
+use crate::ui::{print_step, print_warning};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+/// 等待 GitLab 完成 rebase 的最长轮询次数（每秒一次）
+const REBASE_POLL_ATTEMPTS: u32 = 60;
+
+/// GitLab 后端
+pub struct GitlabForge {
+    http: RestClient,
+    /// URL 编码后的项目路径，作为 API 中的项目 ID
+    project: String,
+    web_url: String,
+    has_token: bool,
+    verbose: bool,
+}
+
+/// GitLab Merge Request
+#[derive(Debug, Deserialize)]
+struct MergeRequest {
+    iid: u64,
+    title: String,
+    web_url: String,
+    source_branch: String,
+    target_branch: String,
+    /// 源分支最新提交
+    sha: Option<String>,
+    state: String,
+    #[serde(default)]
+    draft: bool,
+    author: Option<User>,
+    created_at: Option<String>,
+    merge_status: Option<String>,
+    rebase_in_progress: Option<bool>,
+    merge_error: Option<String>,
+    /// 较新的版本中审阅者要求修改时为 `requested_changes`
+    detailed_merge_status: Option<String>,
+    head_pipeline: Option<Pipeline>,
+}
+
+/// GitLab 流水线
+#[derive(Debug, Deserialize)]
+struct Pipeline {
+    id: u64,
+    status: String,
+    web_url: Option<String>,
+}
+
+/// 流水线中的作业
+#[derive(Debug, Deserialize)]
+struct Job {
+    name: String,
+    status: String,
+    web_url: Option<String>,
+    #[serde(default)]
+    allow_failure: bool,
+}
+
+/// MR 的批准状态
+#[derive(Debug, Deserialize)]
+struct Approvals {
+    #[serde(default)]
+    approvals_left: u32,
+    /// 已批准的用户，只关心是否为空
+    #[serde(default)]
+    approved_by: Vec<serde_json::Value>,
+}
+
+/// GitLab 议题
+#[derive(Debug, Deserialize)]
+struct ProjectIssue {
+    iid: u64,
+    title: String,
+    web_url: String,
+}
+
+/// GitLab 用户
+#[derive(Debug, Deserialize)]
+struct User {
+    id: u64,
+    username: String,
+}
+
+/// GitLab 项目
+#[derive(Debug, Deserialize)]
+struct Project {
+    web_url: String,
+}
+
+impl GitlabForge {
+    /// 创建后端，api_url 形如 `https://gitlab.example.com/api/v4`
+    pub fn new(api_url: &str, token: Option<&str>, remote: &RemoteUrl) -> GtResult<Self> {
+        let auth = token.map(|token| ("PRIVATE-TOKEN", token.to_string()));
+        Ok(Self {
+            http: RestClient::new(ForgeKind::GitLab, api_url, auth)?,
+            project: encode_path_segment(&remote.path),
+            web_url: remote.web_url(),
+            has_token: token.is_some(),
+            verbose: false,
+        })
+    }
+
+    /// 根据配置创建，未配置 `gitlab.api_url` 时使用远程主机的 `/api/v4`
+    pub fn from_config(config: &RepoConfig, remote: &RemoteUrl) -> GtResult<Self> {
+        let api_url = config.gitlab_api_url.clone()
+            .unwrap_or_else(|| format!("{}/api/v4", remote.web_base));
+        Self::new(&api_url, config.gitlab_token.as_deref(), remote)
+    }
+
+    /// 设置是否输出详细信息
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    fn mr_path(&self, iid: u64) -> String {
+        format!("/projects/{}/merge_requests/{}", self.project, iid)
+    }
+
+    /// 把用户名转换为 GitLab 用户 ID，找不到的用户跳过
+    async fn user_ids(&self, usernames: &[String]) -> GtResult<Vec<u64>> {
+        let mut ids = Vec::new();
+        for username in usernames {
+            let users: Vec<User> = self.http.get("/users", &[("username", username.clone())]).await?;
+            match users.first() {
+                Some(user) => ids.push(user.id),
+                None => print_warning(&format!("GitLab 上找不到用户 '{}'，跳过", username)),
+            }
+        }
+        Ok(ids)
+    }
+
+    /// 请求 GitLab 在服务端 rebase 并等待完成
+    async fn rebase(&self, iid: u64) -> GtResult<()> {
+        if self.verbose {
+            print_step(&format!("在 GitLab 上 rebase MR !{}", iid));
+        }
+        let _: serde_json::Value = self.http.put(&format!("{}/rebase", self.mr_path(iid)), &json!({})).await?;
+
+        for _ in 0..REBASE_POLL_ATTEMPTS {
+            let mr: MergeRequest = self.http
+                .get(&self.mr_path(iid), &[("include_rebase_in_progress", "true".to_string())])
+                .await?;
+            if let Some(error) = mr.merge_error.filter(|error| !error.is_empty()) {
+                return Err(GtError::PullRequestError {
+                    reason: format!("MR !{} rebase 失败: {}", iid, error)
+                });
+            }
+            if mr.rebase_in_progress != Some(true) {
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+
+        Err(GtError::PullRequestError {
+            reason: format!("等待 MR !{} rebase 超时", iid)
+        })
+    }
+}
+
+#[async_trait]
+impl Forge for GitlabForge {
+    fn kind(&self) -> ForgeKind {
+        ForgeKind::GitLab
+    }
+
+    fn is_available(&self) -> bool {
+        self.has_token
+    }
+
+    fn compare_url(&self, base: &str, head: &str) -> String {
+        format!(
+            "{}/-/merge_requests/new?merge_request%5Bsource_branch%5D={}&merge_request%5Btarget_branch%5D={}",
+            self.web_url,
+            encode_path_segment(head),
+            encode_path_segment(base)
+        )
+    }
+
+    fn change_request_url(&self, number: u32) -> Option<String> {
+        Some(format!("{}/-/merge_requests/{}", self.web_url, number))
+    }
+
+    async fn auth_status(&self) -> GtResult<AuthStatus> {
+        if !self.has_token {
+            return Ok(AuthStatus::NotAuthenticated);
+        }
+        let user: User = self.http.get("/user", &[]).await?;
+        Ok(AuthStatus::Authenticated(user.username))
+    }
+
+    async fn create_change_request(&self, options: &CreatePrOptions) -> GtResult<PullRequest> {
+        let (title, description) = forge::title_and_body(options)?;
+        // GitLab 通过标题前缀标记草稿
+        let title = if options.draft { format!("Draft: {}", title) } else { title };
+
+        if self.verbose {
+            print_step(&format!("调用 GitLab API 创建 MR: {} -> {}", options.head_branch, options.base_branch));
+        }
+
+        let mut body = json!({
+            "source_branch": options.head_branch,
+            "target_branch": options.base_branch,
+            "title": title,
+            "description": description,
+        });
+        if !options.labels.is_empty() {
+            body["labels"] = json!(options.labels.join(","));
+        }
+        if !options.reviewers.is_empty() {
+            body["reviewer_ids"] = json!(self.user_ids(&options.reviewers).await?);
+        }
+        if options.milestone.is_some() {
+            print_warning("GitLab 后端暂不支持按名称设置里程碑，已忽略");
+        }
+
+        let mr: MergeRequest = self.http
+            .post(&format!("/projects/{}/merge_requests", self.project), &body)
+            .await?;
+        Ok(convert_mr(mr))
+    }
+
+    async fn merge_change_request(&self, id: &str, options: &MergePrOptions) -> GtResult<PullRequest> {
+        let iid = parse_pr_number(id)?;
+
+        if options.wait_for_checks {
+            checks::wait_for_checks(|| self.checks_status(id), options.checks_timeout, options.checks_interval).await?;
+        }
+
+        // GitLab 的合并方式由项目设置决定，rebase 策略先在服务端 rebase 再合并
+        if options.strategy == MergeStrategy::Rebase {
+            self.rebase(iid).await?;
+        }
+
+        let squash = options.strategy == MergeStrategy::Squash;
+        let mut body = json!({
+            "squash": squash,
+            "should_remove_source_branch": options.delete_branch,
+            "merge_when_pipeline_succeeds": options.auto_merge,
+        });
+        if let Some(ref message) = options.commit_message {
+            let key = if squash { "squash_commit_message" } else { "merge_commit_message" };
+            body[key] = json!(message);
+        }
+
+        if self.verbose {
+            print_step(&format!("调用 GitLab API 合并 MR !{} (策略: {})", iid, options.strategy));
+        }
+
+        let mr: MergeRequest = self.http.put(&format!("{}/merge", self.mr_path(iid)), &body).await?;
+        if mr.state != "merged" && !options.auto_merge {
+            return Err(GtError::PullRequestError {
+                reason: format!("MR !{} 未能合并（状态: {}）", iid, mr.state)
+            });
+        }
+
+        Ok(convert_mr(mr))
+    }
+
+    async fn get_change_request(&self, id: &str) -> GtResult<PullRequest> {
+        let mr: MergeRequest = self.http.get(&self.mr_path(parse_pr_number(id)?), &[]).await?;
+        Ok(convert_mr(mr))
+    }
+
+    async fn checks_status(&self, id: &str) -> GtResult<ChecksStatus> {
+        let iid = parse_pr_number(id)?;
+        let mr: MergeRequest = self.http.get(&self.mr_path(iid), &[]).await?;
+
+        // 以 MR 最新流水线中的作业作为检查，还没有作业时以流水线本身作为检查
+        let mut checks = Vec::new();
+        if let Some(pipeline) = mr.head_pipeline {
+            let jobs: Vec<Job> = self.http
+                .get(
+                    &format!("/projects/{}/pipelines/{}/jobs", self.project, pipeline.id),
+                    &[("per_page", "100".to_string())],
+                )
+                .await?;
+            checks = jobs.into_iter()
+                .map(|job| Check { state: job_state(&job.status, job.allow_failure), name: job.name, url: job.web_url })
+                .collect();
+            if checks.is_empty() {
+                checks.push(Check {
+                    name: format!("pipeline #{}", pipeline.id),
+                    state: job_state(&pipeline.status, false),
+                    url: pipeline.web_url,
+                });
+            }
+        }
+
+        let approvals: Approvals = self.http.get(&format!("{}/approvals", self.mr_path(iid)), &[]).await?;
+        let review = if mr.detailed_merge_status.as_deref() == Some("requested_changes") {
+            ReviewDecision::ChangesRequested
+        } else if approvals.approvals_left > 0 {
+            ReviewDecision::ReviewRequired
+        } else if !approvals.approved_by.is_empty() {
+            ReviewDecision::Approved
+        } else {
+            ReviewDecision::NotRequired
+        };
+
+        Ok(ChecksStatus { checks, review })
+    }
+
+    async fn list_change_requests(&self, state: Option<&str>, limit: Option<u32>) -> GtResult<Vec<PullRequest>> {
+        let state = match state.map(|s| s.to_lowercase()).as_deref() {
+            None | Some("open") => "opened",
+            Some("closed") => "closed",
+            Some("merged") => "merged",
+            Some("all") => "all",
+            Some(other) => {
+                return Err(GtError::InvalidInput {
+                    input: format!("不支持的 MR 状态: {}（支持: open, closed, merged, all）", other)
+                });
+            }
+        };
+
+        let limit = limit.unwrap_or(30).clamp(1, 100);
+        let mrs: Vec<MergeRequest> = self.http
+            .get(
+                &format!("/projects/{}/merge_requests", self.project),
+                &[("state", state.to_string()), ("per_page", limit.to_string())],
+            )
+            .await?;
+        Ok(mrs.into_iter().map(convert_mr).collect())
+    }
+
+    async fn get_issue(&self, number: u32) -> GtResult<Issue> {
+        let issue: ProjectIssue = self.http
+            .get(&format!("/projects/{}/issues/{}", self.project, number), &[])
+            .await?;
+        Ok(Issue { number: issue.iid as u32, title: issue.title, url: issue.web_url })
+    }
+
+    async fn create_repo(&self, options: &CreateRepoOptions) -> GtResult<String> {
+        let body = json!({
+            "name": options.name,
+            "description": options.description,
+            "visibility": if options.private { "private" } else { "public" },
+        });
+        let project: Project = self.http.post("/projects", &body).await?;
+        Ok(project.web_url)
+    }
+}
+
+/// 作业或流水线的状态，允许失败的作业失败时不影响合并
+fn job_state(status: &str, allow_failure: bool) -> CheckState {
+    match status {
+        "success" => CheckState::Success,
+        "failed" if allow_failure => CheckState::Skipped,
+        "failed" | "canceled" => CheckState::Failure,
+        "skipped" | "manual" => CheckState::Skipped,
+        _ => CheckState::Pending,
+    }
+}
+
+/// 转换为 gt 的 PR 模型
+fn convert_mr(mr: MergeRequest) -> PullRequest {
+    let state = match mr.state.as_str() {
+        "merged" => PrState::Merged,
+        "closed" | "locked" => PrState::Closed,
+        _ if mr.draft => PrState::Draft,
+        _ => PrState::Open,
+    };
+
+    PullRequest {
+        number: mr.iid as u32,
+        title: mr.title,
+        url: mr.web_url,
+        head_branch: mr.source_branch,
+        head_sha: mr.sha,
+        base_branch: mr.target_branch,
+        state,
+        author: mr.author.map(|author| author.username),
+        created_at: mr.created_at,
+        mergeable: match mr.merge_status.as_deref() {
+            Some("can_be_merged") => Some(true),
+            Some("cannot_be_merged") => Some(false),
+            _ => None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::mock::MockServer;
+    use serde_json::Value;
+
+    const MRS: &str = "/projects/group%2Fsub%2Frepo/merge_requests";
+
+    /// 模拟服务返回的 MR
+    fn merge_request(iid: u64, state: &str) -> Value {
+        json!({
+            "iid": iid,
+            "title": "添加登录",
+            "web_url": format!("https://gitlab.test/group/sub/repo/-/merge_requests/{}", iid),
+            "source_branch": "feature/login",
+            "target_branch": "main",
+            "sha": "abc123",
+            "state": state,
+            "draft": false,
+            "author": { "id": 1, "username": "alice" },
+            "merge_status": "can_be_merged",
+        })
+    }
+
+    fn forge(server: &MockServer) -> GitlabForge {
+        let remote = RemoteUrl::parse("https://gitlab.test/group/sub/repo.git").unwrap();
+        GitlabForge::new(&format!("{}/api/v4", server.url()), Some("secret"), &remote).unwrap()
+    }
+
+    #[tokio::test]
+    async fn create_sends_merge_request_with_reviewer_ids() {
+        let server = MockServer::start();
+        server.route("GET", "/api/v4/users", 200, json!([{ "id": 42, "username": "bob" }]));
+        server.route("POST", &format!("/api/v4{}", MRS), 201, merge_request(3, "opened"));
+
+        let options = CreatePrOptions::new("feature/login".to_string(), "main".to_string())
+            .with_title("添加登录".to_string())
+            .with_body("说明".to_string())
+            .as_draft()
+            .add_reviewer("bob".to_string())
+            .add_label("backend".to_string())
+            .add_label("auth".to_string());
+        let mr = forge(&server).create_change_request(&options).await.unwrap();
+
+        assert_eq!(mr.number, 3);
+        assert_eq!(mr.head_sha.as_deref(), Some("abc123"));
+        assert_eq!(mr.author.as_deref(), Some("alice"));
+        assert_eq!(mr.mergeable, Some(true));
+
+        let request = server.request("POST", &format!("/api/v4{}", MRS));
+        assert_eq!(request.header("private-token"), Some("secret"));
+        assert_eq!(request.body, json!({
+            "source_branch": "feature/login",
+            "target_branch": "main",
+            "title": "Draft: 添加登录",
+            "description": "说明",
+            "labels": "backend,auth",
+            "reviewer_ids": [42],
+        }));
+        assert_eq!(server.request("GET", "/api/v4/users").query("username"), Some("bob"));
+    }
+
+    #[tokio::test]
+    async fn get_maps_state() {
+        let server = MockServer::start();
+        server.route("GET", &format!("/api/v4{}/1", MRS), 200, merge_request(1, "opened"));
+        server.route("GET", &format!("/api/v4{}/2", MRS), 200, merge_request(2, "merged"));
+        server.route("GET", &format!("/api/v4{}/3", MRS), 200, merge_request(3, "locked"));
+
+        let forge = forge(&server);
+        let opened = forge.get_change_request("1").await.unwrap();
+        assert_eq!(opened.state, PrState::Open);
+        assert_eq!(opened.head_branch, "feature/login");
+        assert_eq!(opened.base_branch, "main");
+        assert_eq!(forge.get_change_request("!2").await.unwrap().state, PrState::Merged);
+        assert_eq!(forge.get_change_request("3").await.unwrap().state, PrState::Closed);
+    }
+
+    #[tokio::test]
+    async fn merge_sends_squash_options() {
+        let server = MockServer::start();
+        server.route("PUT", &format!("/api/v4{}/5/merge", MRS), 200, merge_request(5, "merged"));
+
+        let options = MergePrOptions::new(MergeStrategy::Squash)
+            .skip_checks()
+            .delete_branch()
+            .with_commit_message("添加登录".to_string());
+        let mr = forge(&server).merge_change_request("5", &options).await.unwrap();
+        assert_eq!(mr.state, PrState::Merged);
+
+        let request = server.request("PUT", &format!("/api/v4{}/5/merge", MRS));
+        assert_eq!(request.body, json!({
+            "squash": true,
+            "should_remove_source_branch": true,
+            "merge_when_pipeline_succeeds": false,
+            "squash_commit_message": "添加登录",
+        }));
+    }
+
+    #[tokio::test]
+    async fn merge_reports_unmerged_state_and_errors() {
+        let server = MockServer::start();
+        server.route("PUT", &format!("/api/v4{}/5/merge", MRS), 200, merge_request(5, "opened"));
+        server.route("PUT", &format!("/api/v4{}/6/merge", MRS), 405, json!({ "message": "Method Not Allowed" }));
+
+        let forge = forge(&server);
+        let options = MergePrOptions::new(MergeStrategy::Merge).skip_checks();
+        let err = forge.merge_change_request("5", &options).await.unwrap_err();
+        assert!(err.to_string().contains("状态: opened"), "{}", err);
+        let err = forge.merge_change_request("6", &options).await.unwrap_err();
+        assert!(err.to_string().contains("HTTP 405: Method Not Allowed"), "{}", err);
+
+        // 自动合并时 MR 仍处于打开状态
+        let mr = forge.merge_change_request("5", &options.enable_auto_merge()).await.unwrap();
+        assert_eq!(mr.state, PrState::Open);
+    }
+
+    #[tokio::test]
+    async fn list_maps_requested_state() {
+        let server = MockServer::start();
+        server.route("GET", &format!("/api/v4{}", MRS), 200, json!([
+            merge_request(1, "opened"),
+            merge_request(2, "merged"),
+        ]));
+
+        let forge = forge(&server);
+        for (state, expected) in [(None, "opened"), (Some("open"), "opened"), (Some("Closed"), "closed"),
+                                  (Some("merged"), "merged"), (Some("all"), "all")] {
+            let mrs = forge.list_change_requests(state, Some(500)).await.unwrap();
+            assert_eq!(mrs.iter().map(|mr| mr.state.clone()).collect::<Vec<_>>(), vec![PrState::Open, PrState::Merged]);
+
+            let request = server.request("GET", &format!("/api/v4{}", MRS));
+            assert_eq!(request.query("state"), Some(expected));
+            assert_eq!(request.query("per_page"), Some("100"));
+        }
+        assert!(forge.list_change_requests(Some("draft"), None).await.is_err());
+    }
+
+    /// MR !5 的流水线作业和批准状态
+    fn checks_server(jobs: Value, approvals_left: u32) -> MockServer {
+        let server = MockServer::start();
+        let mut mr = merge_request(5, "opened");
+        mr["head_pipeline"] = json!({ "id": 90, "status": "running", "web_url": "https://gitlab.test/pipelines/90" });
+        server.route("GET", &format!("/api/v4{}/5", MRS), 200, mr);
+        server.route("GET", "/api/v4/projects/group%2Fsub%2Frepo/pipelines/90/jobs", 200, jobs);
+        server.route("GET", &format!("/api/v4{}/5/approvals", MRS), 200, json!({
+            "approvals_left": approvals_left,
+            "approved_by": [{ "user": { "id": 1, "username": "alice" } }],
+        }));
+        server.route("PUT", &format!("/api/v4{}/5/merge", MRS), 200, merge_request(5, "merged"));
+        server
+    }
+
+    #[tokio::test]
+    async fn checks_status_reads_pipeline_jobs_and_approvals() {
+        let server = checks_server(json!([
+            { "name": "build", "status": "success", "web_url": "https://gitlab.test/jobs/1" },
+            { "name": "lint", "status": "failed", "allow_failure": true },
+            { "name": "deploy", "status": "manual" },
+            { "name": "test", "status": "running" },
+        ]), 1);
+
+        let status = forge(&server).checks_status("5").await.unwrap();
+        let checks: Vec<(&str, CheckState)> = status.checks.iter()
+            .map(|check| (check.name.as_str(), check.state))
+            .collect();
+        assert_eq!(checks, vec![
+            ("build", CheckState::Success),
+            ("lint", CheckState::Skipped),
+            ("deploy", CheckState::Skipped),
+            ("test", CheckState::Pending),
+        ]);
+        assert_eq!(status.checks[0].url.as_deref(), Some("https://gitlab.test/jobs/1"));
+        assert_eq!(status.review, ReviewDecision::ReviewRequired);
+
+        // 流水线还没有作业时以流水线本身作为检查
+        let server = checks_server(json!([]), 0);
+        let status = forge(&server).checks_status("5").await.unwrap();
+        assert_eq!(status.checks[0].name, "pipeline #90");
+        assert_eq!(status.checks[0].state, CheckState::Pending);
+        assert_eq!(status.review, ReviewDecision::Approved);
+    }
+
+    #[tokio::test]
+    async fn merge_waits_for_checks() {
+        let server = checks_server(json!([{ "name": "build", "status": "success" }]), 0);
+        forge(&server).merge_change_request("5", &MergePrOptions::new(MergeStrategy::Merge)).await.unwrap();
+        server.request("PUT", &format!("/api/v4{}/5/merge", MRS));
+
+        let server = checks_server(json!([{ "name": "test", "status": "failed" }]), 0);
+        let err = forge(&server).merge_change_request("5", &MergePrOptions::new(MergeStrategy::Merge)).await.unwrap_err();
+        assert!(matches!(err, GtError::ChecksFailed { ref checks } if checks == "test"), "{}", err);
+        assert!(!server.requests().iter().any(|request| request.method == "PUT"));
+    }
+}
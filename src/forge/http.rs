@@ -0,0 +1,113 @@
+//! GitLab / Gitea 共用的 REST 客户端
+
+use crate::error::{GtResult, GtError};
+use crate::forge::ForgeKind;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, USER_AGENT};
+use reqwest::{Client, Method, RequestBuilder};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// 带认证头的 JSON REST 客户端
+pub(crate) struct RestClient {
+    client: Client,
+    base_url: String,
+    forge: ForgeKind,
+}
+
+impl RestClient {
+    /// 创建客户端，`auth` 为认证头的名称和值
+    pub fn new(forge: ForgeKind, base_url: &str, auth: Option<(&'static str, String)>) -> GtResult<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+        headers.insert(USER_AGENT, HeaderValue::from_static(concat!("gt/", env!("CARGO_PKG_VERSION"))));
+        if let Some((name, value)) = auth {
+            let mut value = HeaderValue::from_str(&value).map_err(|_| GtError::ConfigError {
+                message: format!("{} 访问令牌包含无效字符", forge)
+            })?;
+            value.set_sensitive(true);
+            headers.insert(name, value);
+        }
+
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .map_err(|e| GtError::ForgeError {
+                forge: forge.to_string(),
+                message: format!("创建 HTTP 客户端失败: {}", e),
+            })?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            forge,
+        })
+    }
+
+    /// GET 请求
+    pub async fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, String)]) -> GtResult<T> {
+        self.send(self.request(Method::GET, path).query(query)).await
+    }
+
+    /// POST 请求
+    pub async fn post<B: Serialize + ?Sized, T: DeserializeOwned>(&self, path: &str, body: &B) -> GtResult<T> {
+        self.send(self.request(Method::POST, path).json(body)).await
+    }
+
+    /// PUT 请求
+    pub async fn put<B: Serialize + ?Sized, T: DeserializeOwned>(&self, path: &str, body: &B) -> GtResult<T> {
+        self.send(self.request(Method::PUT, path).json(body)).await
+    }
+
+    /// 构建请求，path 相对于 API 根地址
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.client.request(method, format!("{}{}", self.base_url, path))
+    }
+
+    /// 发送请求并解析 JSON 响应，非 2xx 状态码转换为错误
+    async fn send<T: DeserializeOwned>(&self, request: RequestBuilder) -> GtResult<T> {
+        let response = request.send().await.map_err(|e| self.error(e.to_string()))?;
+        let status = response.status();
+        let text = response.text().await.map_err(|e| self.error(e.to_string()))?;
+
+        if !status.is_success() {
+            return Err(self.error(format!("HTTP {}: {}", status.as_u16(), error_message(&text))));
+        }
+
+        // 204 等空响应按 null 解析，调用方可以用 () 或 Option 接收
+        let text = if text.trim().is_empty() { "null" } else { text.as_str() };
+        serde_json::from_str(text).map_err(|e| self.error(format!("解析响应失败: {}", e)))
+    }
+
+    fn error(&self, message: String) -> GtError {
+        GtError::ForgeError {
+            forge: self.forge.to_string(),
+            message,
+        }
+    }
+}
+
+/// 从错误响应中提取信息（GitLab 用 `message` / `error`，Gitea 用 `message`）
+fn error_message(body: &str) -> String {
+    let value: serde_json::Value = match serde_json::from_str(body) {
+        Ok(value) => value,
+        Err(_) => return body.trim().to_string(),
+    };
+
+    match value.get("message").or_else(|| value.get("error")) {
+        Some(serde_json::Value::String(message)) => message.clone(),
+        Some(other) => other.to_string(),
+        None => value.to_string(),
+    }
+}
+
+/// URL 路径段编码（GitLab 的项目路径需要把 `/` 编码为 `%2F`）
+pub(crate) fn encode_path_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
@@ -0,0 +1,171 @@
+//! 测试用的本地 HTTP 模拟服务
+//!
+//! 按方法和路径返回预设的 JSON 响应，并记录收到的请求。
+//! 各平台后端把 `api_url` 指向它，就可以在不访问网络的情况下测试请求和响应的处理。
+
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// 收到的请求
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    /// 路径（不含查询参数，保持 URL 编码）
+    pub path: String,
+    /// 查询参数（未解码）
+    pub query: Vec<(String, String)>,
+    /// 请求头，名称为小写
+    pub headers: Vec<(String, String)>,
+    /// JSON 请求体，没有请求体时为 Null
+    pub body: Value,
+}
+
+impl Request {
+    /// 查询参数的值
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.query.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    /// 请求头的值
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.headers.iter().find(|(key, _)| *key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// 预设的响应
+struct Route {
+    method: String,
+    path: String,
+    status: u16,
+    body: Value,
+}
+
+/// 本地 HTTP 模拟服务，在后台线程中处理请求，测试结束时随进程退出
+pub struct MockServer {
+    url: String,
+    routes: Arc<Mutex<Vec<Route>>>,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl MockServer {
+    /// 在随机端口上启动
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("绑定本地端口失败");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let routes: Arc<Mutex<Vec<Route>>> = Arc::default();
+        let requests: Arc<Mutex<Vec<Request>>> = Arc::default();
+
+        let (thread_routes, thread_requests) = (routes.clone(), requests.clone());
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                handle(stream, &thread_routes, &thread_requests);
+            }
+        });
+
+        Self { url, routes, requests }
+    }
+
+    /// 服务地址，如 `http://127.0.0.1:41234`
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// 为方法和路径设置响应，同一路径设置多次时使用最后一次的
+    pub fn route(&self, method: &str, path: &str, status: u16, body: Value) -> &Self {
+        self.routes.lock().unwrap().insert(0, Route {
+            method: method.to_string(),
+            path: path.to_string(),
+            status,
+            body,
+        });
+        self
+    }
+
+    /// 收到的所有请求
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// 最后一个与方法和路径匹配的请求，没有时 panic
+    pub fn request(&self, method: &str, path: &str) -> Request {
+        self.requests()
+            .into_iter()
+            .rev()
+            .find(|request| request.method == method && request.path == path)
+            .unwrap_or_else(|| panic!("没有收到请求 {} {}，收到的请求: {:?}", method, path, self.requests()))
+    }
+}
+
+/// 处理一个连接上的一个请求，响应后关闭连接
+fn handle(stream: TcpStream, routes: &Mutex<Vec<Route>>, requests: &Mutex<Vec<Request>>) {
+    let mut reader = BufReader::new(stream);
+    let Some(request) = read_request(&mut reader) else {
+        return;
+    };
+
+    let (status, body) = routes.lock().unwrap().iter()
+        .find(|route| route.method == request.method && route.path == request.path)
+        .map(|route| (route.status, route.body.to_string()))
+        .unwrap_or_else(|| (404, r#"{"message":"Not Found"}"#.to_string()));
+    requests.lock().unwrap().push(request);
+
+    // 204 没有响应体
+    let body = if status == 204 { String::new() } else { body };
+    let response = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, body.len(), body
+    );
+    let mut stream = reader.into_inner();
+    let _ = stream.write_all(response.as_bytes());
+    let _ = stream.flush();
+}
+
+/// 读取请求行、请求头和按 Content-Length 读取请求体
+fn read_request(reader: &mut BufReader<TcpStream>) -> Option<Request> {
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?.to_string();
+
+    let mut headers = Vec::new();
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).ok()?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+    }
+
+    let length = headers.iter()
+        .find(|(name, _)| name == "content-length")
+        .and_then(|(_, value)| value.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let query = query.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (key.to_string(), value.to_string())
+        })
+        .collect();
+
+    Some(Request {
+        method,
+        path: path.to_string(),
+        query,
+        headers,
+        body: serde_json::from_slice(&body).unwrap_or(Value::Null),
+    })
+}
@@ -0,0 +1,354 @@
+//! 代码托管平台（forge）抽象
+//!
+//! 把 PR / MR 的创建、合并、查看，议题查询、认证状态和仓库创建抽象为 `Forge` trait，
+//! 根据远程仓库 URL 自动选择 GitHub、GitLab 或 Gitea 后端。
+//!
+//! 每个后端的 API 地址都可以配置（`github.api_url` / `gitlab.api_url` / `gitea.api_url`），
+//! 未配置时从远程仓库 URL 推导，因此也可以指向本地的模拟服务进行测试。
+
+pub mod github;
+#[cfg(any(feature = "gitlab", feature = "gitea"))]
+mod http;
+#[cfg(feature = "gitlab")]
+pub mod gitlab;
+#[cfg(feature = "gitea")]
+pub mod gitea;
+#[cfg(all(test, any(feature = "github", feature = "gitlab", feature = "gitea")))]
+pub(crate) mod mock;
+
+pub use github::GithubForge;
+#[cfg(feature = "gitlab")]
+pub use gitlab::GitlabForge;
+#[cfg(feature = "gitea")]
+pub use gitea::GiteaForge;
+
+use crate::config::RepoConfig;
+use crate::error::{GtResult, GtError};
+use crate::git::Repository;
+use crate::github::checks::ChecksStatus;
+use crate::github::pr::{CreatePrOptions, MergePrOptions, PullRequest};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 分支关联议题的 git 配置项，保存在 `branch.<分支名>` 下，随分支重命名和删除
+const ISSUE_KEYS: [&str; 3] = ["gt-issue", "gt-issue-title", "gt-issue-url"];
+
+/// 代码托管平台类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForgeKind {
+    GitHub,
+    GitLab,
+    Gitea,
+}
+
+impl ForgeKind {
+    /// 所有平台类型
+    pub fn all() -> &'static [ForgeKind] {
+        &[ForgeKind::GitHub, ForgeKind::GitLab, ForgeKind::Gitea]
+    }
+
+    /// 从字符串解析
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "github" => Ok(ForgeKind::GitHub),
+            "gitlab" => Ok(ForgeKind::GitLab),
+            "gitea" | "forgejo" => Ok(ForgeKind::Gitea),
+            _ => Err(format!("不支持的代码托管平台: {}。支持: github, gitlab, gitea", s)),
+        }
+    }
+
+    /// 配置项前缀（`<prefix>.token` / `<prefix>.api_url`）
+    pub fn config_prefix(self) -> &'static str {
+        match self {
+            ForgeKind::GitHub => "github",
+            ForgeKind::GitLab => "gitlab",
+            ForgeKind::Gitea => "gitea",
+        }
+    }
+
+    /// 变更请求的叫法
+    pub fn request_name(self) -> &'static str {
+        match self {
+            ForgeKind::GitLab => "Merge Request",
+            ForgeKind::GitHub | ForgeKind::Gitea => "Pull Request",
+        }
+    }
+
+    /// 根据主机名猜测平台类型
+    pub fn detect(host: &str) -> Option<Self> {
+        let host = host.to_lowercase();
+        if host.contains("github") {
+            Some(ForgeKind::GitHub)
+        } else if host.contains("gitlab") {
+            Some(ForgeKind::GitLab)
+        } else if host.contains("gitea") || host.contains("forgejo") || host == "codeberg.org" {
+            Some(ForgeKind::Gitea)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for ForgeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ForgeKind::GitHub => "GitHub",
+            ForgeKind::GitLab => "GitLab",
+            ForgeKind::Gitea => "Gitea",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 认证状态
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuthStatus {
+    /// 已认证，包含用户名
+    Authenticated(String),
+    /// 未认证
+    NotAuthenticated,
+    /// 认证状态未知
+    Unknown,
+}
+
+/// 创建远程仓库的选项
+#[derive(Debug, Clone, Default)]
+pub struct CreateRepoOptions {
+    /// 仓库名称
+    pub name: String,
+    /// 仓库描述
+    pub description: Option<String>,
+    /// 是否为私有仓库
+    pub private: bool,
+}
+
+/// 代码托管平台上的议题（issue）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Issue {
+    pub number: u32,
+    pub title: String,
+    pub url: String,
+}
+
+impl Issue {
+    /// 读取分支关联的议题，没有关联时返回 None
+    pub fn linked_to(repo: &Repository, branch: &str) -> GtResult<Option<Self>> {
+        let [number, title, url] = ISSUE_KEYS.map(|key| repo.config_value(&format!("branch.{}.{}", branch, key)));
+        let Some(number) = number?.and_then(|number| number.parse().ok()) else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            number,
+            title: title?.unwrap_or_default(),
+            url: url?.unwrap_or_default(),
+        }))
+    }
+
+    /// 把议题关联到分支
+    pub fn link(&self, repo: &Repository, branch: &str) -> GtResult<()> {
+        let values = [self.number.to_string(), self.title.clone(), self.url.clone()];
+        for (key, value) in ISSUE_KEYS.iter().zip(values) {
+            repo.set_config_value(&format!("branch.{}.{}", branch, key), &value, false)?;
+        }
+        Ok(())
+    }
+
+    /// 变更请求描述中关闭议题的关键字，合并后平台自动关闭议题
+    pub fn closing_reference(&self) -> String {
+        format!("Closes #{}", self.number)
+    }
+}
+
+/// 代码托管平台操作
+///
+/// PR / MR 统称为变更请求，`id` 可以是编号（`12` / `#12`）或 URL。
+#[async_trait]
+pub trait Forge: Send + Sync {
+    /// 平台类型
+    fn kind(&self) -> ForgeKind;
+
+    /// 后端是否可用（如 gh 已安装或已配置令牌）
+    fn is_available(&self) -> bool;
+
+    /// 在网页上手动创建变更请求的地址
+    fn compare_url(&self, base: &str, head: &str) -> String;
+
+    /// 编号为 number 的变更请求的网页地址，无法确定仓库地址时返回 None
+    fn change_request_url(&self, number: u32) -> Option<String>;
+
+    /// 认证状态
+    async fn auth_status(&self) -> GtResult<AuthStatus>;
+
+    /// 创建变更请求
+    async fn create_change_request(&self, options: &CreatePrOptions) -> GtResult<PullRequest>;
+
+    /// 合并变更请求，返回合并后重新读取的变更请求
+    ///
+    /// `options.wait_for_checks` 为 true 时先等待检查和审阅通过（见 [`crate::github::checks::wait_for_checks`]）。
+    /// 平台只是把合并排入队列或设置为自动合并时，返回的状态仍为打开。
+    async fn merge_change_request(&self, id: &str, options: &MergePrOptions) -> GtResult<PullRequest>;
+
+    /// 查看变更请求
+    async fn get_change_request(&self, id: &str) -> GtResult<PullRequest>;
+
+    /// 查询变更请求的检查和审阅状态
+    async fn checks_status(&self, id: &str) -> GtResult<ChecksStatus>;
+
+    /// 列出变更请求，state 可以是 open / closed / merged / all
+    async fn list_change_requests(&self, state: Option<&str>, limit: Option<u32>) -> GtResult<Vec<PullRequest>>;
+
+    /// 查看议题
+    async fn get_issue(&self, number: u32) -> GtResult<Issue>;
+
+    /// 在当前用户下创建仓库，返回仓库网页地址
+    async fn create_repo(&self, options: &CreateRepoOptions) -> GtResult<String>;
+}
+
+/// 根据配置和远程仓库 URL 创建对应平台的后端
+pub fn from_config(config: &RepoConfig, repo: &Repository, verbose: bool) -> GtResult<Box<dyn Forge>> {
+    let remote = repo.get_remote(&config.remote_name)?;
+    // 本地路径等没有主机名的远程交给 gh 处理
+    let remote_url = RemoteUrl::parse(&remote.url);
+    let kind = match remote_url {
+        Some(ref url) => detect_kind(config, url),
+        None => config.forge.unwrap_or(ForgeKind::GitHub),
+    };
+    #[cfg(any(feature = "gitlab", feature = "gitea"))]
+    let require_url = || remote_url.as_ref().ok_or_else(|| GtError::ConfigError {
+        message: format!("无法从远程 '{}' 的 URL 解析 {} 仓库: {}", config.remote_name, kind, remote.url)
+    });
+
+    match kind {
+        ForgeKind::GitHub => Ok(Box::new(GithubForge::from_config(config, repo, remote_url.as_ref(), verbose)?)),
+        #[cfg(feature = "gitlab")]
+        ForgeKind::GitLab => Ok(Box::new(GitlabForge::from_config(config, require_url()?)?.verbose(verbose))),
+        #[cfg(feature = "gitea")]
+        ForgeKind::Gitea => Ok(Box::new(GiteaForge::from_config(config, require_url()?)?.verbose(verbose))),
+        #[allow(unreachable_patterns)]
+        kind => Err(GtError::ConfigError {
+            message: format!("gt 编译时没有启用 {} 支持（feature \"{}\"）", kind, kind.config_prefix())
+        }),
+    }
+}
+
+/// 确定平台类型
+///
+/// 依次检查：配置项 `forge`、与远程主机相同的 `*.api_url`、主机名，最后默认为 GitHub。
+pub fn detect_kind(config: &RepoConfig, remote: &RemoteUrl) -> ForgeKind {
+    if let Some(kind) = config.forge {
+        return kind;
+    }
+
+    for &kind in ForgeKind::all() {
+        let api_host = config.forge_api_url(kind)
+            .and_then(RemoteUrl::parse)
+            .map(|url| url.host);
+        if api_host.as_deref() == Some(remote.host.as_str()) {
+            return kind;
+        }
+    }
+
+    ForgeKind::detect(&remote.host).unwrap_or(ForgeKind::GitHub)
+}
+
+/// 解析后的远程仓库 URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteUrl {
+    /// 主机名（不含用户名和端口）
+    pub host: String,
+    /// 仓库网页的根地址，如 `https://gitlab.example.com:8443`
+    pub web_base: String,
+    /// 仓库路径（不含 `.git`），GitLab 中可以包含多级群组
+    pub path: String,
+}
+
+impl RemoteUrl {
+    /// 解析 `https://host/path(.git)`、`git@host:path(.git)` 和 `ssh://git@host:port/path(.git)`
+    pub fn parse(url: &str) -> Option<Self> {
+        let url = url.trim().trim_end_matches('/');
+        let (scheme, authority, path) = match url.split_once("://") {
+            Some((scheme, rest)) => {
+                let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+                (scheme, authority, path)
+            }
+            None => {
+                // scp 形式：git@host:owner/repo
+                let (authority, path) = url.split_once(':')?;
+                ("ssh", authority, path)
+            }
+        };
+
+        let authority = authority.rsplit('@').next()?;
+        let host = authority.split(':').next()?.to_string();
+        if host.is_empty() {
+            return None;
+        }
+
+        // SSH 端口不是网页端口，网页统一按 https 访问
+        let web_base = match scheme {
+            "http" | "https" => format!("{}://{}", scheme, authority),
+            _ => format!("https://{}", host),
+        };
+
+        let path = path.strip_suffix(".git").unwrap_or(path).trim_matches('/').to_string();
+        if path.is_empty() {
+            return None;
+        }
+
+        Some(Self { host, web_base, path })
+    }
+
+    /// 拆分为 owner 和仓库名
+    pub fn owner_and_repo(&self) -> Option<(&str, &str)> {
+        self.path.rsplit_once('/')
+    }
+
+    /// 仓库网页地址
+    pub fn web_url(&self) -> String {
+        format!("{}/{}", self.web_base, self.path)
+    }
+}
+
+/// 从 head 相对 base 的提交生成变更请求的标题和描述
+///
+/// 只有一个提交时使用它的标题和正文，否则使用分支名并列出所有提交。
+pub fn fill_from_commits(head: &str, base: &str) -> GtResult<(String, Option<String>)> {
+    let repo = Repository::discover()?;
+    let git = repo.inner();
+
+    let mut walk = git.revwalk()?;
+    walk.push(git.revparse_single(head)?.peel_to_commit()?.id())?;
+    if let Ok(base) = git.revparse_single(base).and_then(|obj| obj.peel_to_commit()) {
+        walk.hide(base.id())?;
+    }
+
+    let mut commits = Vec::new();
+    for oid in walk {
+        commits.push(git.find_commit(oid?)?);
+    }
+
+    if let [commit] = commits.as_slice() {
+        let title = commit.summary().unwrap_or(head).to_string();
+        let body = commit.body().map(|body| body.to_string());
+        return Ok((title, body));
+    }
+
+    let body = commits.iter()
+        .rev()
+        .filter_map(|commit| commit.summary().map(|summary| format!("- {}", summary)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok((head.to_string(), (!body.is_empty()).then_some(body)))
+}
+
+/// 没有指定标题时从提交生成标题和描述
+pub fn title_and_body(options: &CreatePrOptions) -> GtResult<(String, Option<String>)> {
+    match options.title {
+        Some(ref title) => Ok((title.clone(), options.body.clone())),
+        None => {
+            let (title, body) = fill_from_commits(&options.head_branch, &options.base_branch)?;
+            Ok((title, options.body.clone().or(body)))
+        }
+    }
+}
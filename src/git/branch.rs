@@ -1,7 +1,8 @@
 use crate::error::{GtResult, GtError};
+use serde::Serialize;
 
 /// 分支信息
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Branch {
     pub name: String,
     pub is_current: bool,
@@ -13,18 +14,8 @@ pub struct Branch {
 }
 
 impl Branch {
-    /// 检查分支名是否有效
+    /// 检查分支名是否有效（git 的引用名规则）
     pub fn is_valid_name(name: &str) -> bool {
-        !name.is_empty() 
-        && !name.contains("..")
-        && !name.starts_with('/')
-        && !name.ends_with('/')
-        && !name.contains(' ')
-        && !name.contains('~')
-        && !name.contains('^')
-        && !name.contains(':')
-        && !name.contains('?')
-        && !name.contains('*')
-        && !name.contains('[')
+        super::naming::check_ref_format(name).is_ok()
     }
 } 
\ No newline at end of file
@@ -2,10 +2,11 @@
 //! 
 //! 提供提交信息的类型定义。
 
+use serde::Serialize;
 use std::fmt;
 
 /// Git 提交信息
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Commit {
     /// 提交 ID
     pub id: String,
@@ -0,0 +1,344 @@
+//! 约定式提交 (Conventional Commits)
+//!
+//! 解析和校验 [Conventional Commits 1.0](https://www.conventionalcommits.org/zh-hans/v1.0.0/)
+//! 格式的提交信息：
+//!
+//! ```text
+//! <类型>[(<范围>)][!]: <描述>
+//!
+//! [正文]
+//!
+//! [脚注]
+//! ```
+
+use crate::error::{GtResult, GtError};
+use std::fmt;
+
+/// 支持的提交类型及说明
+pub const COMMIT_TYPES: [(&str, &str); 11] = [
+    ("feat", "新功能"),
+    ("fix", "修复缺陷"),
+    ("docs", "只修改文档"),
+    ("style", "不影响逻辑的格式调整"),
+    ("refactor", "既不修复缺陷也不增加功能的重构"),
+    ("perf", "性能优化"),
+    ("test", "增加或修改测试"),
+    ("build", "构建系统或外部依赖"),
+    ("ci", "CI 配置和脚本"),
+    ("chore", "其他不修改源码和测试的变更"),
+    ("revert", "回退之前的提交"),
+];
+
+/// 表示不兼容变更的脚注名
+const BREAKING_TOKENS: [&str; 2] = ["BREAKING CHANGE", "BREAKING-CHANGE"];
+
+/// 推断范围时跳过的通用目录
+const CONTAINER_DIRS: [&str; 6] = ["src", "lib", "crates", "packages", "apps", "pkg"];
+
+/// 提交信息的脚注，如 `Closes #123`、`Reviewed-by: Z`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Footer {
+    /// 脚注名
+    pub token: String,
+    /// 脚注内容，`#` 形式的脚注保留开头的 `#`
+    pub value: String,
+}
+
+impl Footer {
+    /// 解析一行脚注，不是脚注时返回 None
+    pub fn parse(line: &str) -> Option<Self> {
+        for token in BREAKING_TOKENS {
+            if let Some(value) = line.strip_prefix(token).and_then(|rest| rest.strip_prefix(": ")) {
+                return Some(Self { token: token.to_string(), value: value.to_string() });
+            }
+        }
+
+        let split = line.find(": ").map(|i| (i, 2)).into_iter()
+            .chain(line.find(" #").map(|i| (i, 1)))
+            .min_by_key(|(i, _)| *i)?;
+        let (token, value) = (&line[..split.0], &line[split.0 + split.1..]);
+        let valid_token = !token.is_empty()
+            && token.chars().all(|c| c.is_alphanumeric() || c == '-');
+        valid_token.then(|| Self { token: token.to_string(), value: value.to_string() })
+    }
+
+    /// 是否为不兼容变更的说明
+    pub fn is_breaking(&self) -> bool {
+        BREAKING_TOKENS.contains(&self.token.as_str())
+    }
+}
+
+impl fmt::Display for Footer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.value.starts_with('#') && !self.is_breaking() {
+            write!(f, "{} {}", self.token, self.value)
+        } else {
+            write!(f, "{}: {}", self.token, self.value)
+        }
+    }
+}
+
+/// 约定式提交信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalCommit {
+    /// 类型，如 `feat`
+    pub kind: String,
+    /// 范围，如 `save`
+    pub scope: Option<String>,
+    /// 标题中是否带 `!`
+    pub breaking: bool,
+    /// 简短描述
+    pub description: String,
+    /// 正文
+    pub body: Option<String>,
+    /// 脚注
+    pub footers: Vec<Footer>,
+}
+
+impl ConventionalCommit {
+    /// 创建只有标题的提交信息
+    pub fn new(kind: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            kind: kind.into(),
+            scope: None,
+            breaking: false,
+            description: description.into(),
+            body: None,
+            footers: Vec::new(),
+        }
+    }
+
+    /// 解析并校验提交信息
+    pub fn parse(message: &str) -> GtResult<Self> {
+        let message = message.trim();
+        let mut lines = message.lines();
+        let header = lines.next().unwrap_or_default();
+        let mut commit = Self::parse_header(header)?;
+
+        let rest: Vec<&str> = lines.collect();
+        if rest.is_empty() {
+            return Ok(commit);
+        }
+        if !rest[0].trim().is_empty() {
+            return Err(invalid("标题和正文之间需要一个空行"));
+        }
+
+        // 最后一段全部以脚注开头（续行除外）时作为脚注
+        let paragraphs: Vec<String> = rest.split(|line| line.trim().is_empty())
+            .filter(|lines| !lines.is_empty())
+            .map(|lines| lines.join("\n"))
+            .collect();
+        let Some((last, body)) = paragraphs.split_last() else {
+            return Ok(commit);
+        };
+
+        match Self::parse_footers(last) {
+            Some(footers) => {
+                commit.footers = footers;
+                if !body.is_empty() {
+                    commit.body = Some(body.join("\n\n"));
+                }
+            }
+            None => commit.body = Some(paragraphs.join("\n\n")),
+        }
+
+        if let Some(footer) = commit.footers.iter().find(|footer| footer.value.trim().is_empty()) {
+            return Err(invalid(format!("脚注 '{}' 缺少内容", footer.token)));
+        }
+        Ok(commit)
+    }
+
+    /// 解析标题 `<类型>[(<范围>)][!]: <描述>`
+    fn parse_header(header: &str) -> GtResult<Self> {
+        let (prefix, description) = header.split_once(':')
+            .ok_or_else(|| invalid(format!("标题 '{}' 缺少 '<类型>: ' 前缀", header)))?;
+        if description.trim().is_empty() {
+            return Err(invalid("标题缺少描述"));
+        }
+        let Some(description) = description.strip_prefix(' ') else {
+            return Err(invalid("标题中的 ':' 后面需要一个空格"));
+        };
+
+        let (prefix, breaking) = match prefix.strip_suffix('!') {
+            Some(prefix) => (prefix, true),
+            None => (prefix, false),
+        };
+        let (kind, scope) = match prefix.split_once('(') {
+            Some((kind, scope)) => {
+                let scope = scope.strip_suffix(')')
+                    .ok_or_else(|| invalid(format!("范围 '({}' 缺少右括号", scope)))?;
+                if scope.trim().is_empty() {
+                    return Err(invalid("范围不能为空，不需要范围时去掉括号"));
+                }
+                (kind, Some(scope.to_string()))
+            }
+            None => (prefix, None),
+        };
+
+        if !COMMIT_TYPES.iter().any(|(name, _)| *name == kind) {
+            let types: Vec<&str> = COMMIT_TYPES.iter().map(|(name, _)| *name).collect();
+            return Err(invalid(format!("未知的类型 '{}'，可用的类型: {}", kind, types.join(", "))));
+        }
+
+        Ok(Self {
+            kind: kind.to_string(),
+            scope,
+            breaking,
+            description: description.to_string(),
+            body: None,
+            footers: Vec::new(),
+        })
+    }
+
+    /// 解析脚注段，第一行不是脚注时返回 None
+    fn parse_footers(paragraph: &str) -> Option<Vec<Footer>> {
+        let mut footers: Vec<Footer> = Vec::new();
+        for line in paragraph.lines() {
+            match Footer::parse(line) {
+                Some(footer) => footers.push(footer),
+                // 不是脚注的行是上一个脚注的续行
+                None => {
+                    let footer = footers.last_mut()?;
+                    footer.value.push('\n');
+                    footer.value.push_str(line);
+                }
+            }
+        }
+        Some(footers)
+    }
+
+    /// 是否包含不兼容变更
+    pub fn is_breaking(&self) -> bool {
+        self.breaking || self.footers.iter().any(Footer::is_breaking)
+    }
+
+    /// 标题行
+    pub fn header(&self) -> String {
+        let scope = self.scope.as_ref().map(|scope| format!("({})", scope)).unwrap_or_default();
+        let breaking = if self.breaking { "!" } else { "" };
+        format!("{}{}{}: {}", self.kind, scope, breaking, self.description)
+    }
+
+    /// 根据变更的文件推断范围
+    ///
+    /// 取每个文件的第一级目录（跳过 `src` 等通用目录，文件去掉扩展名），
+    /// 所有文件指向同一个范围时返回该范围。
+    pub fn suggest_scope<S: AsRef<str>>(paths: &[S]) -> Option<String> {
+        let mut scopes = paths.iter().map(|path| scope_of(path.as_ref()));
+        let first = scopes.next()??;
+        scopes.all(|scope| scope.as_deref() == Some(first.as_str())).then_some(first)
+    }
+}
+
+impl fmt::Display for ConventionalCommit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.header())?;
+        if let Some(ref body) = self.body {
+            write!(f, "\n\n{}", body)?;
+        }
+        if !self.footers.is_empty() {
+            let footers: Vec<String> = self.footers.iter().map(Footer::to_string).collect();
+            write!(f, "\n\n{}", footers.join("\n"))?;
+        }
+        Ok(())
+    }
+}
+
+/// 单个文件对应的范围，根目录下的文件没有范围
+fn scope_of(path: &str) -> Option<String> {
+    let mut parts: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    if parts.len() > 1 && CONTAINER_DIRS.contains(&parts[0]) {
+        parts.remove(0);
+    }
+    if parts.len() == 1 && path.contains('/') {
+        // 通用目录下的文件，如 src/hooks.rs => hooks
+        let name = parts[0];
+        let stem = name.split_once('.').map_or(name, |(stem, _)| stem);
+        return (!stem.is_empty() && !matches!(stem, "lib" | "main" | "mod" | "index")).then(|| stem.to_string());
+    }
+    (parts.len() > 1).then(|| parts[0].to_string())
+}
+
+/// 构造提交信息格式错误
+fn invalid(reason: impl Into<String>) -> GtError {
+    GtError::InvalidCommitMessage { reason: reason.into() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 解析失败时的错误信息
+    fn error(message: &str) -> String {
+        ConventionalCommit::parse(message).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn parse_valid_headers() {
+        let commit = ConventionalCommit::parse("feat(save): 支持按路径暂存").unwrap();
+        assert_eq!(commit.kind, "feat");
+        assert_eq!(commit.scope.as_deref(), Some("save"));
+        assert_eq!(commit.description, "支持按路径暂存");
+        assert!(!commit.is_breaking());
+
+        let commit = ConventionalCommit::parse("fix: 修复崩溃\n\n正文第一段\n\n正文第二段\n").unwrap();
+        assert_eq!(commit.scope, None);
+        assert_eq!(commit.body.as_deref(), Some("正文第一段\n\n正文第二段"));
+        assert!(commit.footers.is_empty());
+        assert_eq!(commit.to_string(), "fix: 修复崩溃\n\n正文第一段\n\n正文第二段");
+    }
+
+    #[test]
+    fn breaking_changes_from_header_and_footer() {
+        let commit = ConventionalCommit::parse("refactor(api)!: 删除旧接口").unwrap();
+        assert!(commit.breaking);
+        assert!(commit.is_breaking());
+        assert_eq!(commit.header(), "refactor(api)!: 删除旧接口");
+
+        let message = "feat: 新的配置格式\n\n说明\n\nBREAKING CHANGE: 不再读取 .gtrc\n  需要迁移到 .gt.toml\nCloses #12";
+        let commit = ConventionalCommit::parse(message).unwrap();
+        assert!(!commit.breaking);
+        assert!(commit.is_breaking());
+        assert_eq!(commit.body.as_deref(), Some("说明"));
+        assert_eq!(commit.footers, vec![
+            Footer { token: "BREAKING CHANGE".to_string(), value: "不再读取 .gtrc\n  需要迁移到 .gt.toml".to_string() },
+            Footer { token: "Closes".to_string(), value: "#12".to_string() },
+        ]);
+        assert_eq!(commit.to_string(), message);
+
+        assert!(Footer::parse("BREAKING-CHANGE: 移除 --old").unwrap().is_breaking());
+        assert_eq!(Footer::parse("不是脚注的一行"), None);
+    }
+
+    #[test]
+    fn missing_type_or_description() {
+        assert!(error("添加登录").contains("缺少 '<类型>: ' 前缀"));
+        assert!(error("feature: 添加登录").contains("未知的类型 'feature'"));
+        assert!(error("feat:").contains("标题缺少描述"));
+        assert!(error("feat:   ").contains("标题缺少描述"));
+        assert!(error("feat:添加登录").contains("':' 后面需要一个空格"));
+        assert!(error("feat: 添加登录\n正文").contains("需要一个空行"));
+        assert!(error("feat: 添加登录\n\nReviewed-by: \nCloses #12").contains("脚注 'Reviewed-by' 缺少内容"));
+    }
+
+    #[test]
+    fn invalid_scope() {
+        assert!(error("feat(): 添加登录").contains("范围不能为空"));
+        assert!(error("feat( ): 添加登录").contains("范围不能为空"));
+        assert!(error("feat(save: 添加登录").contains("缺少右括号"));
+    }
+
+    #[test]
+    fn suggest_scope_from_paths() {
+        assert_eq!(ConventionalCommit::suggest_scope(&["src/git/patch.rs", "src/git/mod.rs"]).as_deref(), Some("git"));
+        assert_eq!(ConventionalCommit::suggest_scope(&["src/hooks.rs"]).as_deref(), Some("hooks"));
+        assert_eq!(ConventionalCommit::suggest_scope(&["docs/guide.md", "docs/api/index.md"]).as_deref(), Some("docs"));
+        assert_eq!(ConventionalCommit::suggest_scope(&["crates/cli/src/main.rs"]).as_deref(), Some("cli"));
+
+        // 不同范围、根目录文件和入口文件都没有范围
+        assert_eq!(ConventionalCommit::suggest_scope(&["src/git/patch.rs", "src/ui/print.rs"]), None);
+        assert_eq!(ConventionalCommit::suggest_scope(&["README.md"]), None);
+        assert_eq!(ConventionalCommit::suggest_scope(&["src/main.rs"]), None);
+        assert_eq!(ConventionalCommit::suggest_scope::<&str>(&[]), None);
+    }
+}
@@ -0,0 +1,249 @@
+//! gt 操作日志
+//!
+//! 每个会修改仓库的 gt 命令执行前后各记录一次本地分支、HEAD 和 stash 的快照，
+//! 差异写入 `.git/gt/journal.toml`，供 `gt undo` 回滚和 `gt history` 查看。
+//!
+//! 只记录本地引用，已经推送到远程的变更不会被撤销。
+
+use crate::error::GtResult;
+use crate::git::{Repository, StateStore};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 日志状态文件名
+pub const JOURNAL_NAME: &str = "journal";
+
+/// 最多保留的日志条数
+const MAX_ENTRIES: usize = 200;
+
+/// 单个引用的变化
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RefChange {
+    /// 完整引用名（如 refs/heads/main）
+    pub name: String,
+    /// 操作前指向的提交，None 表示操作前不存在
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<String>,
+    /// 操作后指向的提交，None 表示操作后被删除
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+}
+
+impl RefChange {
+    /// 引用的短名称（去掉 refs/heads/ 前缀）
+    pub fn short_name(&self) -> &str {
+        self.name.strip_prefix("refs/heads/").unwrap_or(&self.name)
+    }
+}
+
+/// 一次 gt 操作的日志
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// 递增的操作编号
+    pub id: u64,
+    /// 执行的命令（如 "start feature/login"）
+    pub command: String,
+    /// 执行时间（Unix 时间戳）
+    pub time: i64,
+    /// 命令是否执行成功
+    pub success: bool,
+    /// 操作前的 HEAD（分支引用名或分离状态下的提交）
+    pub head_before: String,
+    /// 操作后的 HEAD
+    pub head_after: String,
+    /// 操作创建的 stash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stash: Option<String>,
+    /// 是否已被撤销
+    #[serde(default)]
+    pub undone: bool,
+    /// 变化的本地分支
+    #[serde(default)]
+    pub changes: Vec<RefChange>,
+}
+
+impl JournalEntry {
+    /// HEAD 是否发生变化
+    pub fn head_changed(&self) -> bool {
+        self.head_before != self.head_after
+    }
+}
+
+/// 日志文件内容
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct JournalFile {
+    #[serde(default)]
+    entries: Vec<JournalEntry>,
+}
+
+/// 仓库引用快照
+#[derive(Debug, Clone)]
+pub struct RefSnapshot {
+    head: String,
+    refs: BTreeMap<String, String>,
+    stashes: Vec<String>,
+}
+
+impl RefSnapshot {
+    /// 记录当前的本地分支、HEAD 和 stash
+    pub fn capture(repo: &Repository) -> GtResult<Self> {
+        let git = repo.inner();
+        let head = current_head(repo);
+
+        let mut refs = BTreeMap::new();
+        for reference in git.references_glob("refs/heads/*")? {
+            let reference = reference?;
+            if let (Some(name), Some(oid)) = (reference.name(), reference.target()) {
+                refs.insert(name.to_string(), oid.to_string());
+            }
+        }
+
+        let stashes = repo.list_stashes()?
+            .into_iter()
+            .map(|stash| stash.id)
+            .collect();
+
+        Ok(Self { head, refs, stashes })
+    }
+
+    /// 与之后的快照比较，返回变化的引用
+    fn diff(&self, after: &RefSnapshot) -> Vec<RefChange> {
+        let mut names: Vec<&String> = self.refs.keys().chain(after.refs.keys()).collect();
+        names.sort();
+        names.dedup();
+
+        names.into_iter()
+            .filter_map(|name| {
+                let before = self.refs.get(name);
+                let after = after.refs.get(name);
+                (before != after).then(|| RefChange {
+                    name: name.clone(),
+                    before: before.cloned(),
+                    after: after.cloned(),
+                })
+            })
+            .collect()
+    }
+
+    /// 与之后的快照比较，返回新创建的 stash
+    fn created_stash(&self, after: &RefSnapshot) -> Option<String> {
+        after.stashes.first()
+            .filter(|id| !self.stashes.contains(id))
+            .cloned()
+    }
+}
+
+/// 当前 HEAD：指向分支时为分支引用名，分离状态时为提交
+pub fn current_head(repo: &Repository) -> String {
+    match repo.inner().find_reference("HEAD") {
+        Ok(head) => match head.symbolic_target() {
+            Some(target) => target.to_string(),
+            None => head.target().map(|oid| oid.to_string()).unwrap_or_default(),
+        },
+        Err(_) => String::new(),
+    }
+}
+
+/// 操作日志
+pub struct Journal {
+    store: StateStore,
+}
+
+impl Journal {
+    /// 打开仓库的操作日志
+    pub fn new(repo: &Repository) -> Self {
+        Self {
+            store: StateStore::new(repo),
+        }
+    }
+
+    /// 读取全部日志（按时间顺序）
+    pub fn entries(&self) -> GtResult<Vec<JournalEntry>> {
+        Ok(self.load()?.entries)
+    }
+
+    /// 记录一次操作，没有任何变化时不记录并返回 None
+    pub fn record(
+        &self,
+        command: &str,
+        before: &RefSnapshot,
+        after: &RefSnapshot,
+        success: bool,
+    ) -> GtResult<Option<JournalEntry>> {
+        let changes = before.diff(after);
+        let stash = before.created_stash(after);
+        if changes.is_empty() && stash.is_none() && before.head == after.head {
+            return Ok(None);
+        }
+
+        let mut file = self.load()?;
+        let id = file.entries.last().map(|entry| entry.id + 1).unwrap_or(1);
+        let entry = JournalEntry {
+            id,
+            command: command.to_string(),
+            time: chrono::Local::now().timestamp(),
+            success,
+            head_before: before.head.clone(),
+            head_after: after.head.clone(),
+            stash,
+            undone: false,
+            changes,
+        };
+
+        file.entries.push(entry.clone());
+        if file.entries.len() > MAX_ENTRIES {
+            let excess = file.entries.len() - MAX_ENTRIES;
+            file.entries.drain(..excess);
+        }
+        self.store.save(JOURNAL_NAME, &file)?;
+
+        Ok(Some(entry))
+    }
+
+    /// 最近一次尚未撤销的操作
+    pub fn last_undoable(&self) -> GtResult<Option<JournalEntry>> {
+        Ok(self.load()?.entries.into_iter().rev().find(|entry| !entry.undone))
+    }
+
+    /// 标记操作已撤销
+    pub fn mark_undone(&self, id: u64) -> GtResult<()> {
+        let mut file = self.load()?;
+        if let Some(entry) = file.entries.iter_mut().find(|entry| entry.id == id) {
+            entry.undone = true;
+        }
+        self.store.save(JOURNAL_NAME, &file)
+    }
+
+    fn load(&self) -> GtResult<JournalFile> {
+        Ok(self.store.load(JOURNAL_NAME)?.unwrap_or_default())
+    }
+}
+
+/// 记录一次命令执行的辅助类型
+///
+/// 命令执行前调用 [`OperationRecorder::begin`]，结束后调用 [`OperationRecorder::finish`]。
+pub struct OperationRecorder {
+    repo: Repository,
+    command: String,
+    before: RefSnapshot,
+}
+
+impl OperationRecorder {
+    /// 在当前仓库记录操作前的快照
+    pub fn begin(command: impl Into<String>) -> GtResult<Self> {
+        let repo = Repository::discover()?;
+        let before = RefSnapshot::capture(&repo)?;
+
+        Ok(Self {
+            repo,
+            command: command.into(),
+            before,
+        })
+    }
+
+    /// 记录操作后的快照并写入日志
+    pub fn finish(self, success: bool) -> GtResult<Option<JournalEntry>> {
+        let after = RefSnapshot::capture(&self.repo)?;
+        Journal::new(&self.repo).record(&self.command, &self.before, &after, success)
+    }
+}
@@ -1,6 +1,8 @@
 pub mod repository;
 pub mod branch;
 pub mod commit;
+pub mod conventional;
+pub mod naming;
 pub mod remote;
 pub mod stash;
 pub mod status;
@@ -9,14 +11,24 @@ pub mod fetch;
 pub mod push;
 pub mod reset;
 pub mod network;
+pub mod state;
+pub mod journal;
+pub mod stack;
+pub mod patch;
 
 // 重新导出核心类型
-pub use repository::Repository;
+pub use repository::{MergeKind, Repository, SyncStatus};
 pub use branch::Branch;
 pub use commit::Commit;
+pub use conventional::{ConventionalCommit, Footer, COMMIT_TYPES};
+pub use naming::BranchPolicy;
 pub use remote::Remote;
-pub use stash::Stash;
-pub use status::WorkingTreeStatus;
+pub use stash::{Stash, StashPushOptions};
+pub use status::{FileChange, FileStatus, FileStatuses, Operation, WorkingTreeStatus};
+pub use state::StateStore;
+pub use journal::{Journal, JournalEntry, OperationRecorder};
+pub use stack::{Stack, Restacker};
+pub use patch::{FilePatch, Hunk, HunkLine};
 
 // 重新导出操作结果类型
 pub use add::{AddOptions, AddResult};
@@ -95,6 +107,16 @@ impl GitOps {
         self.repo.check_status()
     }
     
+    /// 有变更（含暂存区和未追踪文件）的路径
+    pub fn changed_paths(&self) -> GtResult<Vec<String>> {
+        self.repo.changed_paths()
+    }
+    
+    /// 暂存区中有变更的路径
+    pub fn staged_paths(&self) -> GtResult<Vec<String>> {
+        self.repo.staged_paths()
+    }
+    
     /// 检查是否有未提交的变更
     pub fn has_uncommitted_changes(&self) -> GtResult<bool> {
         self.repo.has_uncommitted_changes()
@@ -116,6 +138,16 @@ impl GitOps {
         self.repo.add_files(files)
     }
     
+    /// 工作区相对暂存区的变更，按文件拆成 hunk
+    pub fn unstaged_patches(&self, paths: &[&str]) -> GtResult<Vec<FilePatch>> {
+        self.repo.unstaged_patches(paths)
+    }
+    
+    /// 把选中的 hunk 写入暂存区
+    pub fn stage_hunks(&self, patch: &FilePatch, hunks: &[Hunk]) -> GtResult<()> {
+        self.repo.stage_hunks(patch, hunks)
+    }
+    
     /// 添加所有变更到暂存区
     pub fn add_all(&self) -> GtResult<()> {
         self.repo.add_all()
@@ -152,6 +184,31 @@ impl GitOps {
         self.repo.list_stashes()
     }
     
+    /// 按选项创建 stash
+    pub fn stash_push(&self, options: &StashPushOptions) -> GtResult<String> {
+        self.repo.stash_push(options)
+    }
+    
+    /// 删除 stash
+    pub fn drop_stash(&self, index: usize) -> GtResult<()> {
+        self.repo.drop_stash(index)
+    }
+    
+    /// 清空所有 stash
+    pub fn clear_stashes(&self) -> GtResult<usize> {
+        self.repo.clear_stashes()
+    }
+    
+    /// 获取 stash 的差异内容
+    pub fn stash_diff(&self, index: usize) -> GtResult<String> {
+        self.repo.stash_diff(index)
+    }
+    
+    /// 基于 stash 创建新分支
+    pub fn stash_branch(&self, name: &str, index: usize) -> GtResult<()> {
+        self.repo.stash_branch(name, index)
+    }
+    
     // ===== 远程操作 =====
     
     /// 添加远程仓库
@@ -159,6 +216,11 @@ impl GitOps {
         self.repo.add_remote(name, url)
     }
     
+    /// 修改远程仓库 URL
+    pub fn set_remote_url(&self, name: &str, url: &str) -> GtResult<()> {
+        self.repo.set_remote_url(name, url)
+    }
+    
     /// 列出所有远程仓库
     pub fn list_remotes(&self) -> GtResult<Vec<Remote>> {
         self.repo.list_remotes()
@@ -0,0 +1,347 @@
+//! 分支命名规范
+//!
+//! 统一分支名称的规范化和校验。所有分支名称先按 git 的引用名规则
+//! （`git check-ref-format`）检查，再按配置段 `[branch]` 中的规范检查：
+//!
+//! ```toml
+//! [branch]
+//! prefixes = ["feature/", "fix/", "hotfix/", "release/"]
+//! ticket_pattern = "[A-Z]+-[0-9]+"
+//! max_length = 50
+//! ascii_only = true
+//! ```
+//!
+//! `gt start`、`gt checkout -b` 和 `gt branch -m` 都使用同一套规范。
+//! `gt start #123` 根据议题生成的名称同样需要符合规范。
+
+use crate::config::RepoConfig;
+use crate::error::{GtResult, GtError};
+use regex::Regex;
+
+/// 没有配置前缀时，根据议题生成的分支名称使用的前缀
+const DEFAULT_ISSUE_PREFIX: &str = "feature/";
+
+/// 引用名中不允许出现的字符
+const FORBIDDEN_CHARS: [char; 8] = ['~', '^', ':', '?', '*', '[', '\\', ' '];
+
+/// 按 git 的引用名规则检查分支名称，返回不符合的原因
+pub fn check_ref_format(name: &str) -> Result<(), String> {
+    let reason = if name.is_empty() {
+        "不能为空".to_string()
+    } else if name == "@" || name == "HEAD" {
+        format!("不能是 '{}'", name)
+    } else if name.starts_with('-') {
+        "不能以 '-' 开头".to_string()
+    } else if name.starts_with('/') || name.ends_with('/') || name.contains("//") {
+        "不能以 '/' 开头或结尾，也不能包含连续的 '/'".to_string()
+    } else if name.ends_with('.') || name.ends_with(".lock") {
+        "不能以 '.' 或 '.lock' 结尾".to_string()
+    } else if name.contains("..") || name.contains("@{") {
+        "不能包含 '..' 或 '@{'".to_string()
+    } else if name.split('/').any(|part| part.starts_with('.')) {
+        "每一段都不能以 '.' 开头".to_string()
+    } else if let Some(c) = name.chars().find(|c| c.is_control() || FORBIDDEN_CHARS.contains(c)) {
+        format!("不能包含 {:?}", c)
+    } else {
+        return Ok(());
+    };
+    Err(reason)
+}
+
+/// 分支命名规范
+#[derive(Debug, Clone, Default)]
+pub struct BranchPolicy {
+    /// 允许的前缀，都以 `/` 结尾；为空时不限制
+    prefixes: Vec<String>,
+    /// 分支名称中必须包含的任务编号
+    ticket: Option<Regex>,
+    /// 最大长度（字符数）
+    max_length: Option<usize>,
+    /// 只允许 ASCII 字符，带变音符号的拉丁字母转写为 ASCII，其他字符去掉
+    ascii_only: bool,
+}
+
+impl BranchPolicy {
+    /// 从配置创建
+    pub fn from_config(config: &RepoConfig) -> GtResult<Self> {
+        let ticket = match config.ticket_pattern {
+            Some(ref pattern) => Some(Regex::new(pattern).map_err(|e| GtError::ConfigError {
+                message: format!("branch.ticket_pattern 不是有效的正则表达式: {}", e)
+            })?),
+            None => None,
+        };
+        Ok(Self {
+            prefixes: config.branch_prefixes.iter()
+                .map(|prefix| format!("{}/", prefix.trim_end_matches('/')))
+                .collect(),
+            ticket,
+            max_length: config.branch_max_length,
+            ascii_only: config.ascii_branch_names,
+        })
+    }
+
+    /// 规范化并校验分支名称，返回规范化后的名称
+    ///
+    /// 不符合规范时返回的错误中包含所有原因，能给出建议时附上建议的名称。
+    pub fn enforce(&self, input: &str) -> GtResult<String> {
+        let name = self.normalize(input);
+        if name.is_empty() {
+            return Err(GtError::BranchNamePolicy {
+                name: input.to_string(),
+                reason: "规范化后为空，请使用字母或数字".to_string(),
+            });
+        }
+
+        let violations = self.check(&name);
+        if violations.is_empty() {
+            return Ok(name);
+        }
+        let mut reason = violations.join("；");
+        if let Some(suggestion) = self.suggest(&name) {
+            reason.push_str(&format!("；建议使用 '{}'", suggestion));
+        }
+        Err(GtError::BranchNamePolicy { name, reason })
+    }
+
+    /// 根据议题编号和标题生成分支名称，如 `feature/123-add-login-page`
+    ///
+    /// 使用第一个允许的前缀，超过长度上限时截短标题部分。
+    pub fn issue_branch(&self, number: u32, title: &str) -> GtResult<String> {
+        let prefix = self.prefixes.first().map_or(DEFAULT_ISSUE_PREFIX, String::as_str);
+        let mut name = self.normalize(&format!("{}{}-{}", prefix, number, title));
+        if let Some(max_length) = self.max_length {
+            name = truncate(&name, max_length, prefix.len());
+        }
+        self.enforce(&name)
+    }
+
+    /// 规范化分支名称
+    ///
+    /// 转为小写，空格、下划线和其他不能用于分支名称的字符替换为 `-`，去掉重复和首尾的分隔符。
+    /// 与 `ticket_pattern` 匹配的任务编号保持原样。
+    pub fn normalize(&self, input: &str) -> String {
+        let input = input.trim();
+        let mut slug = String::with_capacity(input.len());
+        let mut position = 0;
+        if let Some(ref ticket) = self.ticket {
+            for found in ticket.find_iter(input) {
+                self.slugify(&input[position..found.start()], &mut slug);
+                slug.push_str(found.as_str());
+                position = found.end();
+            }
+        }
+        self.slugify(&input[position..], &mut slug);
+        tidy(&slug)
+    }
+
+    /// 把一段文字转为分支名称可用的字符，追加到 slug
+    fn slugify(&self, text: &str, slug: &mut String) {
+        for c in text.chars().flat_map(char::to_lowercase) {
+            if c.is_ascii_alphanumeric() || matches!(c, '/' | '.' | '-') {
+                slug.push(c);
+            } else if !c.is_ascii() && !self.ascii_only && c.is_alphanumeric() {
+                slug.push(c);
+            } else if let Some(ascii) = transliterate(c).filter(|_| self.ascii_only) {
+                slug.push_str(ascii);
+            } else {
+                slug.push('-');
+            }
+        }
+    }
+
+    /// 检查分支名称，返回所有不符合规范的原因
+    pub fn check(&self, name: &str) -> Vec<String> {
+        let mut violations = Vec::new();
+        if let Err(reason) = check_ref_format(name) {
+            violations.push(reason);
+        }
+        if !self.prefixes.is_empty() && self.matched_prefix(name).is_none() {
+            violations.push(format!("需要以 {} 之一开头", self.prefixes.join("、")));
+        }
+        if let Some(ref ticket) = self.ticket {
+            if !ticket.is_match(name) {
+                violations.push(format!("需要包含匹配 '{}' 的任务编号", ticket.as_str()));
+            }
+        }
+        if let Some(max_length) = self.max_length {
+            let length = name.chars().count();
+            if length > max_length {
+                violations.push(format!("长度 {} 超过上限 {}", length, max_length));
+            }
+        }
+        if self.ascii_only && !name.is_ascii() {
+            violations.push("只能包含 ASCII 字符".to_string());
+        }
+        violations
+    }
+
+    /// 根据前缀和长度的规范给出建议的名称，无法改进时返回 None
+    ///
+    /// 缺少任务编号时无法推断，建议的名称中仍需要补上。
+    pub fn suggest(&self, name: &str) -> Option<String> {
+        let mut suggestion = name.to_string();
+
+        if !self.prefixes.is_empty() && self.matched_prefix(&suggestion).is_none() {
+            // feat/login → feature/login，bugfix/crash → fix/crash，其他直接加上第一个前缀
+            let (head, rest) = suggestion.split_once('/')
+                .filter(|(_, rest)| !rest.is_empty())
+                .unwrap_or(("", &suggestion));
+            let similar = |prefix: &&String| {
+                let prefix = prefix.trim_end_matches('/');
+                !head.is_empty() && (prefix.starts_with(head) || head.starts_with(prefix) || head.ends_with(prefix))
+            };
+            suggestion = match self.prefixes.iter().find(similar) {
+                Some(prefix) => format!("{}{}", prefix, rest),
+                None => format!("{}{}", self.prefixes[0], suggestion),
+            };
+        }
+
+        if let Some(max_length) = self.max_length {
+            suggestion = truncate(&suggestion, max_length, self.matched_prefix(&suggestion).map_or(0, str::len));
+        }
+
+        (suggestion != name && check_ref_format(&suggestion).is_ok()).then_some(suggestion)
+    }
+
+    /// 分支名称使用的前缀
+    fn matched_prefix(&self, name: &str) -> Option<&str> {
+        self.prefixes.iter()
+            .find(|prefix| name.starts_with(prefix.as_str()) && name.len() > prefix.len())
+            .map(String::as_str)
+    }
+}
+
+/// 去掉重复和首尾的分隔符
+fn tidy(slug: &str) -> String {
+    let mut result = String::with_capacity(slug.len());
+    for c in slug.chars() {
+        let last = result.chars().last();
+        match (last, c) {
+            (Some('-'), '-') | (Some('/'), '/') | (Some('.'), '.') | (Some('/'), '-') | (Some('/'), '.') => continue,
+            (Some('-'), '/') => {
+                result.pop();
+                result.push('/');
+            }
+            (None, '-' | '/' | '.') => continue,
+            _ => result.push(c),
+        }
+    }
+    result.trim_end_matches(['-', '/', '.']).to_string()
+}
+
+/// 截短到 max_length 个字符，尽量在 `-` 处断开，不截断前缀
+fn truncate(name: &str, max_length: usize, prefix_length: usize) -> String {
+    if name.chars().count() <= max_length {
+        return name.to_string();
+    }
+    let cut: String = name.chars().take(max_length).collect();
+    let cut = match cut.rfind('-') {
+        Some(index) if index > prefix_length => &cut[..index],
+        _ => cut.as_str(),
+    };
+    cut.trim_end_matches(['-', '/', '.']).to_string()
+}
+
+/// 带变音符号的拉丁字母（小写）的 ASCII 写法
+fn transliterate(c: char) -> Option<&'static str> {
+    let ascii = match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    };
+    Some(ascii)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(prefixes: &[&str], ticket: Option<&str>, max_length: Option<usize>, ascii_only: bool) -> BranchPolicy {
+        BranchPolicy {
+            prefixes: prefixes.iter().map(|prefix| prefix.to_string()).collect(),
+            ticket: ticket.map(|pattern| Regex::new(pattern).unwrap()),
+            max_length,
+            ascii_only,
+        }
+    }
+
+    #[test]
+    fn normalize_keeps_ticket_case() {
+        let plain = BranchPolicy::default();
+        assert_eq!(plain.normalize("  Add Login_Page! "), "add-login-page");
+        assert_eq!(plain.normalize("feature/ABC-12 Add login"), "feature/abc-12-add-login");
+
+        let ticket = policy(&[], Some("[A-Z]+-[0-9]+"), None, false);
+        assert_eq!(ticket.normalize("feature/ABC-12 Add login"), "feature/ABC-12-add-login");
+        assert_eq!(ticket.normalize("Fix ABC-7 and XY-8"), "fix-ABC-7-and-XY-8");
+    }
+
+    #[test]
+    fn ascii_only_transliterates() {
+        let ascii = policy(&[], None, None, true);
+        assert_eq!(ascii.normalize("Café Crème"), "cafe-creme");
+        assert_eq!(ascii.normalize("Straße Œuvre"), "strasse-oeuvre");
+        assert_eq!(ascii.normalize("修复 bug"), "bug");
+
+        let unicode = BranchPolicy::default();
+        assert_eq!(unicode.normalize("修复 Bug"), "修复-bug");
+        assert_eq!(unicode.normalize("Café"), "café");
+        assert_eq!(unicode.check("café"), Vec::<String>::new());
+        assert_eq!(ascii.check("café"), vec!["只能包含 ASCII 字符".to_string()]);
+    }
+
+    #[test]
+    fn tidy_collapses_separators() {
+        assert_eq!(tidy("feature-/login"), "feature/login");
+        assert_eq!(tidy("feature//login"), "feature/login");
+        assert_eq!(tidy("feature/-login--page"), "feature/login-page");
+        assert_eq!(tidy("-/.feature/.login..v2-/"), "feature/login.v2");
+    }
+
+    #[test]
+    fn suggest_similar_prefix() {
+        let policy = policy(&["feature/", "fix/"], None, None, false);
+        assert_eq!(policy.suggest("feat/x").as_deref(), Some("feature/x"));
+        assert_eq!(policy.suggest("bugfix/crash").as_deref(), Some("fix/crash"));
+        assert_eq!(policy.suggest("login").as_deref(), Some("feature/login"));
+        assert_eq!(policy.suggest("feature/x"), None);
+        assert_eq!(policy.check("feature/"), vec!["不能以 '/' 开头或结尾，也不能包含连续的 '/'".to_string(), "需要以 feature/、fix/ 之一开头".to_string()]);
+
+        let err = policy.enforce("feat/x").unwrap_err().to_string();
+        assert!(err.contains("建议使用 'feature/x'"), "{}", err);
+    }
+
+    #[test]
+    fn truncate_keeps_prefix() {
+        assert_eq!(truncate("feature/add-login-page", 19, 8), "feature/add-login");
+        assert_eq!(truncate("feature/abcdefghij", 10, 8), "feature/ab");
+        // 前缀中的 '-' 不作为断开的位置
+        assert_eq!(truncate("hot-fix/abcdef", 9, 8), "hot-fix/a");
+        assert_eq!(truncate("short", 10, 0), "short");
+
+        let policy = policy(&["hot-fix/"], None, Some(12), false);
+        assert_eq!(policy.suggest("hot-fix/add-login").as_deref(), Some("hot-fix/add"));
+        assert_eq!(policy.issue_branch(123, "Add login page").unwrap(), "hot-fix/123");
+    }
+}
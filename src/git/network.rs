@@ -47,12 +47,43 @@ impl NetworkOps {
         let current_branch = repo.current_branch()?;
         let branch_name = branch.unwrap_or(&current_branch);
         
+        self.push_ref_with_retry(repo, remote, branch_name, &format!("分支 '{}'", branch_name))
+    }
+    
+    /// 带重试的标签推送
+    pub fn push_tag_with_retry(
+        &self,
+        repo: &Repository,
+        remote: &str,
+        tag: &str,
+    ) -> GtResult<()> {
+        self.push_ref_with_retry(repo, remote, &format!("refs/tags/{}", tag), &format!("标签 '{}'", tag))
+    }
+    
+    /// 带重试地删除远程分支
+    pub fn delete_remote_branch_with_retry(
+        &self,
+        repo: &Repository,
+        remote: &str,
+        branch: &str,
+    ) -> GtResult<()> {
+        self.push_ref_with_retry(repo, remote, &format!(":refs/heads/{}", branch), &format!("分支 '{}' 的删除", branch))
+    }
+    
+    /// 带重试地推送一个引用，label 用于提示
+    fn push_ref_with_retry(
+        &self,
+        repo: &Repository,
+        remote: &str,
+        refspec: &str,
+        label: &str,
+    ) -> GtResult<()> {
         if self.config.verbose {
-            print_step(&format!("推送分支 '{}' 到远程 '{}'...", branch_name, remote));
+            print_step(&format!("推送{}到远程 '{}'...", label, remote));
         }
         
         for attempt in 1..=self.config.max_attempts {
-            match repo.push(remote, Some(branch_name)) {
+            match repo.push(remote, Some(refspec)) {
                 Ok(_) => {
                     if self.config.verbose {
                         print_success("推送成功");
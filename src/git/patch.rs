@@ -0,0 +1,370 @@
+//! 按 hunk 暂存
+//!
+//! 把工作区相对暂存区的差异拆成 hunk，选中的 hunk 应用到暂存区中的文件内容上，
+//! 得到新的暂存内容。hunk 可以继续拆分，也可以手动编辑后再暂存。
+
+use crate::error::{GtResult, GtError};
+
+/// 文件末尾没有换行时 diff 中的标记行
+const NO_NEWLINE_MARKER: &str = "\\ No newline at end of file";
+
+/// hunk 中的一行
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HunkLine {
+    /// `' '` 上下文、`'+'` 新增、`'-'` 删除
+    pub origin: char,
+    /// 行内容，包含行尾的换行（文件末尾没有换行时不包含）
+    pub content: Vec<u8>,
+}
+
+impl HunkLine {
+    /// 是否为上下文行
+    pub fn is_context(&self) -> bool {
+        self.origin == ' '
+    }
+
+    /// 是否属于暂存区一侧（上下文或删除）
+    fn in_old(&self) -> bool {
+        self.origin != '+'
+    }
+
+    /// 是否属于工作区一侧（上下文或新增）
+    fn in_new(&self) -> bool {
+        self.origin != '-'
+    }
+}
+
+/// 一段连续的变更
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// 在暂存区内容中开始的位置（从 0 开始的行号）
+    old_offset: usize,
+    /// 在工作区内容中开始的位置
+    new_offset: usize,
+    /// hunk 标题中 `@@` 之后的函数名等上下文
+    section: String,
+    pub lines: Vec<HunkLine>,
+}
+
+impl Hunk {
+    /// 从 git2 的 hunk 创建
+    fn from_git2(patch: &git2::Patch, index: usize) -> GtResult<Self> {
+        let (hunk, line_count) = patch.hunk(index)?;
+        let header = String::from_utf8_lossy(hunk.header());
+        let section = header.trim_end()
+            .splitn(3, "@@")
+            .nth(2)
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+
+        let mut lines = Vec::new();
+        for line_index in 0..line_count {
+            let line = patch.line_in_hunk(index, line_index)?;
+            // 文件末尾换行的标记行不算内容，换行与否已经体现在内容中
+            if matches!(line.origin(), ' ' | '+' | '-') {
+                lines.push(HunkLine { origin: line.origin(), content: line.content().to_vec() });
+            }
+        }
+
+        // 只有新增时 start 指向插入位置之前的一行
+        let offset = |start: u32, count: u32| if count == 0 { start as usize } else { start as usize - 1 };
+        Ok(Self {
+            old_offset: offset(hunk.old_start(), hunk.old_lines()),
+            new_offset: offset(hunk.new_start(), hunk.new_lines()),
+            section,
+            lines,
+        })
+    }
+
+    /// 暂存区一侧的行数
+    pub fn old_count(&self) -> usize {
+        self.lines.iter().filter(|line| line.in_old()).count()
+    }
+
+    /// 工作区一侧的行数
+    pub fn new_count(&self) -> usize {
+        self.lines.iter().filter(|line| line.in_new()).count()
+    }
+
+    /// 是否包含变更（编辑后可能只剩上下文）
+    pub fn has_changes(&self) -> bool {
+        self.lines.iter().any(|line| !line.is_context())
+    }
+
+    /// 标题，如 `@@ -1,3 +1,4 @@ fn main()`
+    pub fn header(&self) -> String {
+        let range = |offset: usize, count: usize| {
+            let start = if count == 0 { offset } else { offset + 1 };
+            format!("{},{}", start, count)
+        };
+        let header = format!(
+            "@@ -{} +{} @@",
+            range(self.old_offset, self.old_count()),
+            range(self.new_offset, self.new_count())
+        );
+        if self.section.is_empty() {
+            header
+        } else {
+            format!("{} {}", header, self.section)
+        }
+    }
+
+    /// 按上下文行拆成更小的 hunk，不能再拆时返回 None
+    ///
+    /// 每段变更带上它前面的上下文，最后一段带上末尾的上下文，各段互不重叠。
+    pub fn split(&self) -> Option<Vec<Hunk>> {
+        let mut parts: Vec<Hunk> = Vec::new();
+        let mut current = Hunk { lines: Vec::new(), ..self.clone() };
+        let (mut old_offset, mut new_offset) = (self.old_offset, self.new_offset);
+
+        for line in &self.lines {
+            let after_change = current.lines.last().is_some_and(|last| !last.is_context());
+            if line.is_context() && after_change {
+                let next = Hunk { old_offset, new_offset, lines: Vec::new(), ..self.clone() };
+                parts.push(std::mem::replace(&mut current, next));
+            }
+            old_offset += usize::from(line.in_old());
+            new_offset += usize::from(line.in_new());
+            current.lines.push(line.clone());
+        }
+
+        // 末尾只有上下文时并入上一段
+        match parts.last_mut() {
+            Some(last) if !current.has_changes() => last.lines.append(&mut current.lines),
+            _ => parts.push(current),
+        }
+        (parts.len() > 1).then_some(parts)
+    }
+
+    /// 手动编辑时写入编辑器的内容
+    pub fn to_edit_text(&self) -> String {
+        let mut text = String::from("# 手动编辑 hunk，说明见末尾\n");
+        text.push_str(&self.header());
+        text.push('\n');
+        for line in &self.lines {
+            text.push(line.origin);
+            text.push_str(&String::from_utf8_lossy(&line.content));
+            if !line.content.ends_with(b"\n") {
+                text.push('\n');
+                text.push_str(NO_NEWLINE_MARKER);
+                text.push('\n');
+            }
+        }
+        text.push_str(concat!(
+            "# ---\n",
+            "# 不暂存某个 '-' 行：把开头的 '-' 改为空格 ' '\n",
+            "# 不暂存某个 '+' 行：删除该行\n",
+            "# 以 # 开头的行会被忽略，删除所有行则放弃编辑\n",
+        ));
+        text
+    }
+
+    /// 解析编辑后的内容，删除了所有行时返回 None
+    ///
+    /// 暂存区一侧（上下文和 `-` 行）必须与原来相同，否则无法应用到暂存区。
+    pub fn parse_edited(&self, text: &str) -> GtResult<Option<Hunk>> {
+        let mut lines: Vec<HunkLine> = Vec::new();
+        for raw in text.split_inclusive('\n') {
+            if raw.starts_with('#') || raw.starts_with("@@") {
+                continue;
+            }
+            if raw.starts_with('\\') {
+                if let Some(last) = lines.last_mut() {
+                    if last.content.ends_with(b"\n") {
+                        last.content.pop();
+                    }
+                }
+                continue;
+            }
+
+            // 编辑器可能去掉了空白上下文行开头的空格
+            let (origin, content) = match raw.chars().next() {
+                Some('\n') => (' ', raw),
+                Some(origin @ (' ' | '+' | '-')) => (origin, &raw[1..]),
+                _ => return Err(GtError::InvalidInput {
+                    input: format!("无法识别的行 '{}'，每行需要以 ' '、'+' 或 '-' 开头", raw.trim_end())
+                }),
+            };
+            lines.push(HunkLine { origin, content: content.as_bytes().to_vec() });
+        }
+
+        if lines.is_empty() {
+            return Ok(None);
+        }
+        let old_side = |lines: &[HunkLine]| -> Vec<Vec<u8>> {
+            lines.iter().filter(|line| line.in_old()).map(|line| line.content.clone()).collect()
+        };
+        if old_side(&lines) != old_side(&self.lines) {
+            return Err(GtError::InvalidInput {
+                input: "编辑后的 hunk 与暂存区中的内容不符，只能修改 '+' 行，或把 '-' 改为 ' '".to_string()
+            });
+        }
+        Ok(Some(Hunk { lines, ..self.clone() }))
+    }
+}
+
+/// 一个文件在工作区中的变更
+#[derive(Debug, Clone)]
+pub struct FilePatch {
+    /// 文件路径
+    pub path: String,
+    /// 暂存区中的内容，新文件为空
+    pub old_content: Vec<u8>,
+    /// 暂存时使用的文件模式
+    pub mode: u32,
+    /// 是否为未跟踪的新文件
+    pub is_new: bool,
+    /// 是否已从工作区删除
+    pub is_deleted: bool,
+    /// 是否为二进制文件（没有 hunk，只能整体暂存）
+    pub is_binary: bool,
+    pub hunks: Vec<Hunk>,
+}
+
+impl FilePatch {
+    /// 从 git2 的 patch 创建，old_content 为暂存区中的内容
+    pub fn from_git2(patch: &git2::Patch, old_content: Vec<u8>) -> GtResult<Self> {
+        let delta = patch.delta();
+        let path = delta.new_file().path()
+            .or_else(|| delta.old_file().path())
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mode = match u32::from(delta.new_file().mode()) {
+            0 => u32::from(delta.old_file().mode()),
+            mode => mode,
+        };
+
+        let hunks = (0..patch.num_hunks())
+            .map(|index| Hunk::from_git2(patch, index))
+            .collect::<GtResult<Vec<_>>>()?;
+
+        Ok(Self {
+            path,
+            old_content,
+            mode,
+            is_new: matches!(delta.status(), git2::Delta::Untracked | git2::Delta::Added),
+            is_deleted: delta.status() == git2::Delta::Deleted,
+            is_binary: delta.flags().is_binary() || hunks.is_empty(),
+            hunks,
+        })
+    }
+
+    /// 把选中的 hunk 应用到暂存区内容上，返回新的暂存内容
+    ///
+    /// hunk 之间不能重叠。
+    pub fn apply(&self, hunks: &[Hunk]) -> Vec<u8> {
+        let mut hunks: Vec<&Hunk> = hunks.iter().collect();
+        hunks.sort_by_key(|hunk| hunk.old_offset);
+        let old_lines: Vec<&[u8]> = self.old_content.split_inclusive(|byte| *byte == b'\n').collect();
+        let mut content = Vec::with_capacity(self.old_content.len());
+        let mut position = 0;
+
+        for hunk in hunks {
+            let start = hunk.old_offset.clamp(position, old_lines.len());
+            old_lines[position..start].iter().for_each(|line| content.extend_from_slice(line));
+            for line in hunk.lines.iter().filter(|line| line.in_new()) {
+                content.extend_from_slice(&line.content);
+            }
+            position = (start + hunk.old_count()).min(old_lines.len());
+        }
+        old_lines[position..].iter().for_each(|line| content.extend_from_slice(line));
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 对比两段内容，生成文件变更
+    fn file_patch(old: &str, new: &str, context_lines: u32) -> FilePatch {
+        let mut options = git2::DiffOptions::new();
+        options.context_lines(context_lines);
+        let patch = git2::Patch::from_buffers(
+            old.as_bytes(), Some("file.txt".as_ref()),
+            new.as_bytes(), Some("file.txt".as_ref()),
+            Some(&mut options),
+        ).unwrap();
+        FilePatch::from_git2(&patch, old.as_bytes().to_vec()).unwrap()
+    }
+
+    fn apply(patch: &FilePatch, hunks: &[Hunk]) -> String {
+        String::from_utf8(patch.apply(hunks)).unwrap()
+    }
+
+    #[test]
+    fn split_hunks_can_be_applied_separately() {
+        let patch = file_patch("a\nb\nc\nd\ne\n", "a\nB\nc\nD\ne\n", 3);
+        assert_eq!(patch.hunks.len(), 1);
+
+        let parts = patch.hunks[0].split().unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].header(), "@@ -1,2 +1,2 @@");
+        assert_eq!(parts[1].header(), "@@ -3,3 +3,3 @@");
+        assert!(parts[0].split().is_none());
+
+        assert_eq!(apply(&patch, &parts[..1]), "a\nB\nc\nd\ne\n");
+        assert_eq!(apply(&patch, &parts[1..]), "a\nb\nc\nD\ne\n");
+        assert_eq!(apply(&patch, &[parts[1].clone(), parts[0].clone()]), "a\nB\nc\nD\ne\n");
+        assert_eq!(apply(&patch, &[]), "a\nb\nc\nd\ne\n");
+    }
+
+    #[test]
+    fn add_only_hunks_insert_after_old_start() {
+        // 没有上下文时，只有新增的 hunk 的 old_start 指向插入位置之前的一行
+        let patch = file_patch("a\nb\nc\n", "x\na\nb\ny\nc\n", 0);
+        assert_eq!(patch.hunks.len(), 2);
+        assert_eq!(patch.hunks[0].header(), "@@ -0,0 +1,1 @@");
+        assert!(patch.hunks[1].header().starts_with("@@ -2,0 +4,1 @@"));
+
+        assert_eq!(apply(&patch, &patch.hunks[..1]), "x\na\nb\nc\n");
+        assert_eq!(apply(&patch, &patch.hunks[1..]), "a\nb\ny\nc\n");
+        assert_eq!(apply(&patch, &patch.hunks), "x\na\nb\ny\nc\n");
+
+        // 拆分后只剩新增的一段
+        let patch = file_patch("a\nb\nc\n", "a\nx\nb\ny\nc\n", 3);
+        let parts = patch.hunks[0].split().unwrap();
+        assert_eq!(apply(&patch, &parts[1..]), "a\nb\ny\nc\n");
+    }
+
+    #[test]
+    fn missing_trailing_newline_is_kept() {
+        let patch = file_patch("a\nb", "a\nc", 3);
+        assert_eq!(apply(&patch, &patch.hunks), "a\nc");
+
+        let patch = file_patch("a\nb", "a\nb\n", 3);
+        assert_eq!(apply(&patch, &patch.hunks), "a\nb\n");
+
+        // 编辑时用标记行表示没有换行，解析后不变
+        let patch = file_patch("a\nb\n", "a\nb\nc", 3);
+        let hunk = &patch.hunks[0];
+        let text = hunk.to_edit_text();
+        assert!(text.contains(&format!("+c\n{}\n", NO_NEWLINE_MARKER)));
+        assert_eq!(hunk.parse_edited(&text).unwrap().as_ref(), Some(hunk));
+        assert_eq!(apply(&patch, &patch.hunks), "a\nb\nc");
+    }
+
+    #[test]
+    fn edited_hunk_can_keep_removed_lines() {
+        let patch = file_patch("a\nb\nc\n", "a\nB\nc\n", 3);
+        let hunk = &patch.hunks[0];
+
+        // 把 '-' 改为上下文，只暂存新增的行
+        let text = hunk.to_edit_text().replace("-b\n", " b\n");
+        let edited = hunk.parse_edited(&text).unwrap().unwrap();
+        assert_eq!(edited.header(), "@@ -1,3 +1,4 @@");
+        assert_eq!(apply(&patch, &[edited]), "a\nb\nB\nc\n");
+
+        // 删除 '+' 行后只剩上下文
+        let text = hunk.to_edit_text().replace("-b\n", " b\n").replace("+B\n", "");
+        assert!(!hunk.parse_edited(&text).unwrap().unwrap().has_changes());
+
+        // 修改暂存区一侧的内容无法应用
+        let text = hunk.to_edit_text().replace(" a\n", " z\n");
+        assert!(hunk.parse_edited(&text).is_err());
+
+        // 删除所有行表示放弃
+        assert!(hunk.parse_edited("# 只有注释\n").unwrap().is_none());
+    }
+}
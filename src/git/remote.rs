@@ -2,8 +2,10 @@
 //! 
 //! 提供远程仓库信息的类型定义。
 
+use serde::Serialize;
+
 /// Git 远程仓库信息
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Remote {
     /// 远程名称
     pub name: String,
@@ -6,13 +6,15 @@ use crate::error::{GtError, GtResult};
 use git2::{Repository as Git2Repo, StatusOptions, BranchType, Signature};
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use serde::Serialize;
 
 // 重新导入我们需要的类型
 use super::branch::Branch;
 use super::commit::Commit;
-use super::stash::Stash;
-use super::status::WorkingTreeStatus;
+use super::stash::{Stash, StashPushOptions};
+use super::status::{FileChange, FileStatus, FileStatuses, Operation, WorkingTreeStatus};
 use super::remote::Remote;
+use super::patch::{FilePatch, Hunk};
 
 /// Git 仓库抽象
 pub struct Repository {
@@ -70,6 +72,11 @@ impl Repository {
         &self.path
     }
     
+    /// 获取 .git 目录路径
+    pub fn git_dir(&self) -> &Path {
+        self.inner.path()
+    }
+    
     /// 获取内部的 git2 仓库对象
     pub fn inner(&self) -> &Git2Repo {
         &self.inner
@@ -157,22 +164,28 @@ impl Repository {
     }
     
     /// 获取与远程的同步状态
+    ///
+    /// 优先比较配置的上游分支，没有上游时比较远程的同名分支。
     pub fn sync_status(&self, remote_name: &str) -> GtResult<SyncStatus> {
         let current_branch = self.current_branch()?;
         let local_ref = format!("refs/heads/{}", current_branch);
-        let remote_ref = format!("refs/remotes/{}/{}", remote_name, current_branch);
+        let remote_ref = match self.get_upstream(&current_branch) {
+            Ok(upstream) => format!("refs/remotes/{}", upstream),
+            Err(_) => format!("refs/remotes/{}/{}", remote_name, current_branch),
+        };
         
         let local_oid = self.inner.refname_to_id(&local_ref).ok();
         let remote_oid = self.inner.refname_to_id(&remote_ref).ok();
         
         match (local_oid, remote_oid) {
             (Some(local), Some(remote)) => {
-                if local == remote {
-                    Ok(SyncStatus::UpToDate)
-                } else {
-                    let (ahead, behind) = self.inner.graph_ahead_behind(local, remote)?;
-                    Ok(SyncStatus::Diverged { ahead, behind })
-                }
+                let (ahead, behind) = self.inner.graph_ahead_behind(local, remote)?;
+                Ok(match (ahead, behind) {
+                    (0, 0) => SyncStatus::UpToDate,
+                    (ahead, 0) => SyncStatus::Ahead(ahead),
+                    (0, behind) => SyncStatus::Behind(behind),
+                    (ahead, behind) => SyncStatus::Diverged { ahead, behind },
+                })
             }
             (Some(_), None) => Ok(SyncStatus::LocalOnly),
             (None, Some(_)) => Ok(SyncStatus::RemoteOnly),
@@ -198,6 +211,50 @@ impl Repository {
         }
     }
     
+    /// 检查远程跟踪分支是否存在（refs/remotes/<remote>/<branch>）
+    pub fn remote_branch_exists(&self, remote: &str, branch: &str) -> GtResult<bool> {
+        match self.inner.find_branch(&format!("{}/{}", remote, branch), git2::BranchType::Remote) {
+            Ok(_) => Ok(true),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(false),
+            Err(e) => Err(GtError::GitError(e)),
+        }
+    }
+    
+    /// 读取远程的默认分支（refs/remotes/<remote>/HEAD 指向的分支）
+    pub fn remote_head_branch(&self, remote: &str) -> GtResult<Option<String>> {
+        let head_ref = format!("refs/remotes/{}/HEAD", remote);
+        let reference = match self.inner.find_reference(&head_ref) {
+            Ok(reference) => reference,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(GtError::GitError(e)),
+        };
+        
+        let prefix = format!("refs/remotes/{}/", remote);
+        Ok(reference.symbolic_target()
+            .and_then(|target| target.strip_prefix(&prefix))
+            .map(|branch| branch.to_string()))
+    }
+    
+    /// 从远程查询默认分支并更新 refs/remotes/<remote>/HEAD
+    pub fn refresh_remote_head(&self, remote: &str) -> GtResult<Option<String>> {
+        let output = Command::new("git")
+            .args(["remote", "set-head", remote, "--auto"])
+            .current_dir(&self.path)
+            .output()
+            .map_err(|e| GtError::GitOperation {
+                message: format!("执行 git remote set-head 命令失败: {}", e)
+            })?;
+        
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GtError::GitOperation {
+                message: format!("查询远程 '{}' 的默认分支失败: {}", remote, stderr.trim())
+            });
+        }
+        
+        self.remote_head_branch(remote)
+    }
+    
     // ===== 分支相关操作 =====
     
     /// 创建新分支
@@ -299,13 +356,17 @@ impl Repository {
                 })?;
                 
             if let Some(name) = branch.name()? {
+                let upstream = self.get_upstream(name).ok();
+                let (ahead, behind) = upstream.as_deref()
+                    .and_then(|upstream| self.ahead_behind(name, upstream).ok())
+                    .unwrap_or((0, 0));
                 let branch_info = Branch {
                     name: name.to_string(),
                     is_current: name == current,
                     is_remote: false,
-                    upstream: self.get_upstream(name).ok(),
-                    ahead: 0, // TODO: 计算ahead/behind
-                    behind: 0,
+                    upstream,
+                    ahead,
+                    behind,
                     last_commit: self.get_last_commit(name).ok(),
                 };
                 branches.push(branch_info);
@@ -342,7 +403,191 @@ impl Repository {
         Ok(())
     }
     
+    /// 获取本地分支指向的提交 ID
+    pub fn branch_oid(&self, name: &str) -> GtResult<String> {
+        let branch = self.inner.find_branch(name, BranchType::Local)
+            .map_err(|_| GtError::BranchNotFound { 
+                branch: name.to_string() 
+            })?;
+        
+        branch.get().target()
+            .map(|oid| oid.to_string())
+            .ok_or_else(|| GtError::GitOperation { 
+                message: format!("无法解析分支 '{}' 的提交", name) 
+            })
+    }
+    
+    /// 将本地分支移动到指定提交（不修改工作区）
+    pub fn set_branch_target(&self, name: &str, oid: &str, reason: &str) -> GtResult<()> {
+        let oid = git2::Oid::from_str(oid)?;
+        let mut reference = self.inner.find_reference(&format!("refs/heads/{}", name))
+            .map_err(|_| GtError::BranchNotFound { 
+                branch: name.to_string() 
+            })?;
+        
+        reference.set_target(oid, reason)
+            .map_err(|e| GtError::GitOperation { 
+                message: format!("更新分支 '{}' 失败: {}", name, e) 
+            })?;
+        
+        Ok(())
+    }
+    
+    /// 读取引用当前指向的提交，引用不存在时返回 None
+    pub fn reference_target(&self, name: &str) -> GtResult<Option<String>> {
+        match self.inner.find_reference(name) {
+            Ok(reference) => Ok(reference.target().map(|oid| oid.to_string())),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(GtError::GitError(e)),
+        }
+    }
+    
+    /// 将引用恢复到指定提交，target 为 None 时删除引用（不修改工作区）
+    pub fn restore_reference(&self, name: &str, target: Option<&str>, reason: &str) -> GtResult<()> {
+        match target {
+            Some(oid) => {
+                let oid = git2::Oid::from_str(oid)?;
+                self.inner.reference(name, oid, true, reason)
+                    .map_err(|e| GtError::GitOperation {
+                        message: format!("恢复引用 '{}' 失败: {}", name, e)
+                    })?;
+            }
+            None => {
+                if let Ok(mut reference) = self.inner.find_reference(name) {
+                    reference.delete()
+                        .map_err(|e| GtError::GitOperation {
+                            message: format!("删除引用 '{}' 失败: {}", name, e)
+                        })?;
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// 将 HEAD 指向分支引用或提交（不修改工作区和暂存区）
+    pub fn set_head(&self, head: &str) -> GtResult<()> {
+        let result = if head.starts_with("refs/") {
+            self.inner.set_head(head)
+        } else {
+            self.inner.set_head_detached(git2::Oid::from_str(head)?)
+        };
+        
+        result.map_err(|e| GtError::GitOperation {
+            message: format!("切换 HEAD 到 '{}' 失败: {}", head, e)
+        })
+    }
+    
+    /// 计算 branch 相对 base 领先和落后的提交数
+    pub fn ahead_behind(&self, branch: &str, base: &str) -> GtResult<(usize, usize)> {
+        let local = self.inner.revparse_single(branch)?.peel_to_commit()?.id();
+        let upstream = self.inner.revparse_single(base)?.peel_to_commit()?.id();
+        Ok(self.inner.graph_ahead_behind(local, upstream)?)
+    }
+    
+    /// 获取两个分支/提交的分叉点
+    pub fn merge_base(&self, a: &str, b: &str) -> GtResult<String> {
+        let a = self.inner.revparse_single(a)?.peel_to_commit()?.id();
+        let b = self.inner.revparse_single(b)?.peel_to_commit()?.id();
+        Ok(self.inner.merge_base(a, b)?.to_string())
+    }
+    
+    /// 列出 head 中不在 base 中的提交 ID，从新到旧
+    pub fn commits_between(&self, base: &str, head: &str) -> GtResult<Vec<String>> {
+        let mut walk = self.inner.revwalk()?;
+        walk.push(self.inner.revparse_single(head)?.peel_to_commit()?.id())?;
+        walk.hide(self.inner.revparse_single(base)?.peel_to_commit()?.id())?;
+        walk.map(|oid| Ok(oid?.to_string())).collect()
+    }
+    
+    /// 检查分支是否已合并到 target
+    pub fn is_merged_into(&self, branch: &str, target: &str) -> GtResult<bool> {
+        Ok(self.merge_kind(branch, target)?.is_some())
+    }
+    
+    /// 判断分支以哪种方式合并到了 target，未合并时返回 None
+    ///
+    /// 除了普通合并（分支是 target 的祖先），也能识别 squash 合并和 rebase 合并：
+    /// target 上分叉点之后某个提交的树与分支相同，或者用 `git cherry` 按 patch-id
+    /// 判断 target 中已有分支上每个提交（或把全部改动压缩成的一个临时提交）的等价补丁。
+    pub fn merge_kind(&self, branch: &str, target: &str) -> GtResult<Option<MergeKind>> {
+        let branch_commit = self.inner.revparse_single(branch)?.peel_to_commit()?;
+        let target_commit = self.inner.revparse_single(target)?.peel_to_commit()?;
+        
+        if branch_commit.id() == target_commit.id()
+            || self.inner.graph_descendant_of(target_commit.id(), branch_commit.id())?
+        {
+            return Ok(Some(MergeKind::Ancestor));
+        }
+        
+        let merge_base = self.inner.merge_base(branch_commit.id(), target_commit.id())?;
+        let base_commit = self.inner.find_commit(merge_base)?;
+        if base_commit.tree_id() == branch_commit.tree_id() {
+            // 分支相对分叉点没有任何改动
+            return Ok(None);
+        }
+        
+        // 合并时解决过冲突的 squash 提交补丁不同，但合并后的树与分支相同
+        let mut walk = self.inner.revwalk()?;
+        walk.push(target_commit.id())?;
+        walk.hide(merge_base)?;
+        walk.simplify_first_parent()?;
+        for oid in walk {
+            if self.inner.find_commit(oid?)?.tree_id() == branch_commit.tree_id() {
+                return Ok(Some(MergeKind::SameTree));
+            }
+        }
+        
+        // rebase 合并：分支上的每个提交在 target 中都有等价补丁
+        let cherry = self.cherry(target, branch)?;
+        if !cherry.is_empty() && cherry.iter().all(|line| line.starts_with('-')) {
+            return Ok(Some(MergeKind::Rebased));
+        }
+        
+        // squash 合并：临时提交只写入对象库，不会被任何引用指向
+        let signature = Signature::now("gt", "gt@localhost")?;
+        let squashed = self.inner.commit(
+            None,
+            &signature,
+            &signature,
+            "gt squash check",
+            &branch_commit.tree()?,
+            &[&base_commit],
+        )?;
+        
+        let cherry = self.cherry(target, &squashed.to_string())?;
+        Ok(cherry.first().filter(|line| line.starts_with('-')).map(|_| MergeKind::Squashed))
+    }
+    
+    /// 执行 git cherry，返回每个提交的标记行（'-' 表示 upstream 中已有等价补丁）
+    fn cherry(&self, upstream: &str, head: &str) -> GtResult<Vec<String>> {
+        let output = Command::new("git")
+            .args(["cherry", upstream, head])
+            .current_dir(&self.path)
+            .output()
+            .map_err(|e| GtError::GitOperation {
+                message: format!("执行 git cherry 命令失败: {}", e)
+            })?;
+        
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GtError::GitOperation {
+                message: format!("git cherry 失败: {}", stderr)
+            });
+        }
+        
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.to_string())
+            .collect())
+    }
+    
     // 分支辅助方法
+    /// 分支的上游分支，如 `origin/main`
+    pub fn upstream_of(&self, branch_name: &str) -> Option<String> {
+        self.get_upstream(branch_name).ok()
+    }
+    
     fn get_upstream(&self, branch_name: &str) -> GtResult<String> {
         let branch = self.inner.find_branch(branch_name, BranchType::Local)?;
         if let Ok(upstream) = branch.upstream() {
@@ -369,7 +614,11 @@ impl Repository {
     
     /// 检查工作区状态
     pub fn check_status(&self) -> GtResult<WorkingTreeStatus> {
-        let statuses = self.inner.statuses(None)?;
+        let statuses = self.inner.statuses(Some(
+            StatusOptions::new()
+                .include_untracked(true)
+                .renames_head_to_index(true)
+        ))?;
         
         let mut status = WorkingTreeStatus {
             has_uncommitted_changes: false,
@@ -379,12 +628,20 @@ impl Repository {
             added_files: 0,
             deleted_files: 0,
             untracked_files: 0,
+            renamed_files: 0,
+            conflicted_files: 0,
         };
         
         for entry in statuses.iter() {
             let flags = entry.status();
             
-            if flags.is_wt_modified() || flags.is_wt_deleted() || flags.is_wt_renamed() {
+            if flags.is_conflicted() {
+                status.has_uncommitted_changes = true;
+                status.conflicted_files += 1;
+                continue;
+            }
+            
+            if flags.is_wt_modified() || flags.is_wt_deleted() || flags.is_wt_renamed() || flags.is_wt_typechange() {
                 status.has_uncommitted_changes = true;
                 status.modified_files += 1;
             }
@@ -394,12 +651,15 @@ impl Repository {
                 status.untracked_files += 1;
             }
             
-            if flags.is_index_modified() || flags.is_index_new() || flags.is_index_deleted() {
+            if flags.is_index_modified() || flags.is_index_new() || flags.is_index_deleted()
+                || flags.is_index_renamed() || flags.is_index_typechange() {
                 status.has_staged_changes = true;
                 if flags.is_index_new() {
                     status.added_files += 1;
                 } else if flags.is_index_deleted() {
                     status.deleted_files += 1;
+                } else if flags.is_index_renamed() {
+                    status.renamed_files += 1;
                 }
             }
         }
@@ -407,6 +667,113 @@ impl Repository {
         Ok(status)
     }
     
+    /// 按暂存、未暂存、未追踪和冲突分组的文件状态，检测重命名
+    pub fn file_statuses(&self) -> GtResult<FileStatuses> {
+        let statuses = self.inner.statuses(Some(
+            StatusOptions::new()
+                .include_untracked(true)
+                .recurse_untracked_dirs(true)
+                .include_ignored(false)
+                .renames_head_to_index(true)
+                .renames_index_to_workdir(true)
+        ))?;
+        
+        let mut result = FileStatuses::default();
+        for entry in statuses.iter() {
+            let flags = entry.status();
+            let path = entry.path().unwrap_or_default().to_string();
+            
+            if flags.is_conflicted() {
+                result.conflicted.push(FileStatus { path, old_path: None, change: FileChange::Conflicted });
+                continue;
+            }
+            
+            let staged = if flags.is_index_new() {
+                Some(FileChange::Added)
+            } else if flags.is_index_renamed() {
+                Some(FileChange::Renamed)
+            } else if flags.is_index_deleted() {
+                Some(FileChange::Deleted)
+            } else if flags.is_index_typechange() {
+                Some(FileChange::TypeChange)
+            } else if flags.is_index_modified() {
+                Some(FileChange::Modified)
+            } else {
+                None
+            };
+            if let Some(change) = staged {
+                result.staged.push(Self::file_status(entry.head_to_index(), &path, change));
+            }
+            
+            let unstaged = if flags.is_wt_new() {
+                Some(FileChange::Untracked)
+            } else if flags.is_wt_renamed() {
+                Some(FileChange::Renamed)
+            } else if flags.is_wt_deleted() {
+                Some(FileChange::Deleted)
+            } else if flags.is_wt_typechange() {
+                Some(FileChange::TypeChange)
+            } else if flags.is_wt_modified() {
+                Some(FileChange::Modified)
+            } else {
+                None
+            };
+            match unstaged {
+                Some(FileChange::Untracked) => {
+                    result.untracked.push(FileStatus { path, old_path: None, change: FileChange::Untracked });
+                }
+                Some(change) => result.unstaged.push(Self::file_status(entry.index_to_workdir(), &path, change)),
+                None => {}
+            }
+        }
+        
+        Ok(result)
+    }
+    
+    /// 从 diff 中取出重命名前后的路径
+    fn file_status(delta: Option<git2::DiffDelta>, path: &str, change: FileChange) -> FileStatus {
+        let path_of = |file: git2::DiffFile| file.path().map(|path| path.to_string_lossy().to_string());
+        match (change, delta) {
+            (FileChange::Renamed, Some(delta)) => FileStatus {
+                path: path_of(delta.new_file()).unwrap_or_else(|| path.to_string()),
+                old_path: path_of(delta.old_file()),
+                change,
+            },
+            _ => FileStatus { path: path.to_string(), old_path: None, change },
+        }
+    }
+    
+    /// 进行中的 rebase、merge、cherry-pick 等操作
+    pub fn operation_in_progress(&self) -> Option<Operation> {
+        Operation::from_state(self.inner.state())
+    }
+    
+    /// 有变更（含暂存区和未追踪文件）的路径
+    pub fn changed_paths(&self) -> GtResult<Vec<String>> {
+        let statuses = self.inner.statuses(None)?;
+        
+        Ok(statuses.iter()
+            .filter(|entry| !entry.status().is_ignored())
+            .filter_map(|entry| entry.path().map(|path| path.to_string()))
+            .collect())
+    }
+    
+    /// 暂存区中有变更的路径
+    pub fn staged_paths(&self) -> GtResult<Vec<String>> {
+        let statuses = self.inner.statuses(None)?;
+        
+        Ok(statuses.iter()
+            .filter(|entry| entry.status().intersects(
+                git2::Status::INDEX_NEW
+                    | git2::Status::INDEX_MODIFIED
+                    | git2::Status::INDEX_DELETED
+                    | git2::Status::INDEX_RENAMED
+                    | git2::Status::INDEX_TYPECHANGE
+            ))
+            .filter_map(|entry| entry.path().map(|path| path.to_string()))
+            .collect())
+    }
+    
     // ===== 提交相关操作 =====
     
     /// 添加文件到暂存区
@@ -444,6 +811,76 @@ impl Repository {
         Ok(())
     }
     
+    /// 工作区相对暂存区的变更，按文件拆成 hunk，paths 为空时包含所有文件
+    pub fn unstaged_patches(&self, paths: &[&str]) -> GtResult<Vec<FilePatch>> {
+        let mut options = git2::DiffOptions::new();
+        options.include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .show_untracked_content(true);
+        for path in paths {
+            options.pathspec(*path);
+        }
+        
+        let diff = self.inner.diff_index_to_workdir(None, Some(&mut options))?;
+        let mut patches = Vec::new();
+        for index in 0..diff.deltas().len() {
+            let Some(patch) = git2::Patch::from_diff(&diff, index)? else {
+                continue;
+            };
+            let old_id = patch.delta().old_file().id();
+            let old_content = if old_id.is_zero() {
+                Vec::new()
+            } else {
+                self.inner.find_blob(old_id)?.content().to_vec()
+            };
+            patches.push(FilePatch::from_git2(&patch, old_content)?);
+        }
+        Ok(patches)
+    }
+    
+    /// 把选中的 hunk 写入暂存区
+    ///
+    /// 二进制文件没有 hunk，按工作区中的状态整体暂存。
+    pub fn stage_hunks(&self, patch: &FilePatch, hunks: &[Hunk]) -> GtResult<()> {
+        let mut index = self.inner.index()?;
+        let path = Path::new(&patch.path);
+        let content = patch.apply(hunks);
+        
+        // 删除的文件在所有内容都被删掉时从暂存区移除
+        let result = if patch.is_deleted && (patch.is_binary || content.is_empty()) {
+            index.remove_path(path)
+        } else if patch.is_binary {
+            index.add_path(path)
+        } else {
+            // 不沿用旧条目的时间戳和大小，让 git 重新比较工作区中的内容
+            let entry = git2::IndexEntry {
+                ctime: git2::IndexTime::new(0, 0),
+                mtime: git2::IndexTime::new(0, 0),
+                dev: 0,
+                ino: 0,
+                mode: patch.mode,
+                uid: 0,
+                gid: 0,
+                file_size: 0,
+                id: git2::Oid::zero(),
+                flags: 0,
+                flags_extended: 0,
+                path: patch.path.as_bytes().to_vec(),
+            };
+            index.add_frombuffer(&entry, &content)
+        };
+        result.map_err(|e| GtError::GitOperation {
+            message: format!("无法暂存 '{}' 的变更: {}", patch.path, e)
+        })?;
+        
+        index.write()
+            .map_err(|e| GtError::GitOperation {
+                message: format!("无法写入索引: {}", e)
+            })?;
+        
+        Ok(())
+    }
+    
     /// 创建提交
     pub fn create_commit(&self, message: &str) -> GtResult<()> {
         // 获取当前用户签名
@@ -484,11 +921,11 @@ impl Repository {
         let config = self.inner.config()?;
         let name = config.get_string("user.name")
             .map_err(|_| GtError::ConfigError { 
-                message: "Git 用户名未配置，请运行 'git config user.name \"你的名字\"'".to_string() 
+                message: "Git 用户名未配置，请运行 'gt config user \"你的名字\" \"你的邮箱\"'".to_string() 
             })?;
         let email = config.get_string("user.email")
             .map_err(|_| GtError::ConfigError { 
-                message: "Git 邮箱未配置，请运行 'git config user.email \"你的邮箱\"'".to_string() 
+                message: "Git 邮箱未配置，请运行 'gt config user \"你的名字\" \"你的邮箱\"'".to_string() 
             })?;
             
         Ok(Signature::now(&name, &email)?)
@@ -509,42 +946,320 @@ impl Repository {
     
     // ===== Stash 相关操作 =====
     
+    /// 打开一个可变的 git2 仓库句柄
+    ///
+    /// git2 的 stash 接口需要 `&mut`，这里重新打开同一个仓库，
+    /// 避免把 `Repository` 的其他操作都改成可变借用。
+    fn open_mut(&self) -> GtResult<Git2Repo> {
+        Git2Repo::open(self.inner.path())
+            .map_err(|e| GtError::GitOperation {
+                message: format!("无法打开仓库: {}", e)
+            })
+    }
+    
     /// 创建 stash
     pub fn create_stash(&self, message: Option<&str>) -> GtResult<()> {
+        let mut options = StashPushOptions::new();
+        if let Some(message) = message {
+            options = options.with_message(message);
+        }
+        
+        self.stash_push(&options)?;
+        Ok(())
+    }
+    
+    /// 按选项创建 stash，返回新 stash 的提交 ID
+    pub fn stash_push(&self, options: &StashPushOptions) -> GtResult<String> {
+        if !options.paths.is_empty() {
+            return self.stash_push_paths(options);
+        }
+        
         let signature = self.get_signature()?;
-        let message = message.unwrap_or("WIP");
         
-        // 注意：stash_save 需要可变引用，但我们的设计是不可变的
-        // 这里我们需要重新思考设计，或者使用 RefCell/Mutex
-        // 暂时先返回未实现错误
-        Err(GtError::NotImplemented { 
-            feature: "stash operations require mutable repository access".to_string() 
-        })
+        let mut flags = git2::StashFlags::DEFAULT;
+        if options.include_untracked {
+            flags |= git2::StashFlags::INCLUDE_UNTRACKED;
+        }
+        if options.keep_index {
+            flags |= git2::StashFlags::KEEP_INDEX;
+        }
+        
+        let mut repo = self.open_mut()?;
+        let oid = repo.stash_save2(&signature, options.message.as_deref(), Some(flags))
+            .map_err(|e| match e.code() {
+                git2::ErrorCode::NotFound => GtError::GitOperation {
+                    message: "没有可以暂存的本地变更".to_string()
+                },
+                _ => GtError::GitOperation {
+                    message: format!("创建 stash 失败: {}", e)
+                },
+            })?;
+        
+        Ok(oid.to_string())
+    }
+    
+    /// 只暂存指定路径
+    ///
+    /// git2 的 `StashSaveOptions` 无法设置消息，这里使用系统 git 命令。
+    fn stash_push_paths(&self, options: &StashPushOptions) -> GtResult<String> {
+        let mut args = vec!["stash", "push"];
+        
+        if options.include_untracked {
+            args.push("--include-untracked");
+        }
+        if options.keep_index {
+            args.push("--keep-index");
+        }
+        if let Some(ref message) = options.message {
+            args.extend(&["-m", message]);
+        }
+        
+        args.push("--");
+        args.extend(options.paths.iter().map(|p| p.as_str()));
+        
+        // 指定路径没有变更时 git 只提示 "No local changes to save" 并返回成功，
+        // 通过 stash 数量判断是否真的创建了 stash
+        let stash_count = || self.inner.reflog("refs/stash").map(|reflog| reflog.len()).unwrap_or(0);
+        let count_before = stash_count();
+        
+        let output = Command::new("git")
+            .args(&args)
+            .current_dir(&self.path)
+            .output()
+            .map_err(|e| GtError::GitOperation {
+                message: format!("执行 git stash 命令失败: {}", e)
+            })?;
+        
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GtError::GitOperation {
+                message: format!("创建 stash 失败: {}", stderr.trim())
+            });
+        }
+        
+        let oid = self.inner.refname_to_id("refs/stash")
+            .ok()
+            .filter(|_| stash_count() > count_before)
+            .ok_or_else(|| GtError::GitOperation {
+                message: "没有可以暂存的本地变更".to_string()
+            })?;
+        
+        Ok(oid.to_string())
     }
     
     /// 应用 stash
-    pub fn apply_stash(&self, _index: usize) -> GtResult<()> {
-        Err(GtError::NotImplemented { 
-            feature: "stash operations require mutable repository access".to_string() 
-        })
+    pub fn apply_stash(&self, index: usize) -> GtResult<()> {
+        self.ensure_stash_exists(index)?;
+        
+        let mut repo = self.open_mut()?;
+        let mut options = git2::StashApplyOptions::new();
+        options.reinstantiate_index();
+        
+        repo.stash_apply(index, Some(&mut options))
+            .map_err(|e| Self::stash_apply_error(index, e))
     }
     
     /// 弹出 stash
-    pub fn pop_stash(&self, _index: usize) -> GtResult<()> {
-        Err(GtError::NotImplemented { 
-            feature: "stash operations require mutable repository access".to_string() 
-        })
+    pub fn pop_stash(&self, index: usize) -> GtResult<()> {
+        self.ensure_stash_exists(index)?;
+        
+        let mut repo = self.open_mut()?;
+        let mut options = git2::StashApplyOptions::new();
+        options.reinstantiate_index();
+        
+        repo.stash_pop(index, Some(&mut options))
+            .map_err(|e| Self::stash_apply_error(index, e))
+    }
+    
+    /// 删除 stash
+    pub fn drop_stash(&self, index: usize) -> GtResult<()> {
+        self.ensure_stash_exists(index)?;
+        
+        let mut repo = self.open_mut()?;
+        repo.stash_drop(index)
+            .map_err(|e| GtError::GitOperation {
+                message: format!("删除 stash@{{{}}} 失败: {}", index, e)
+            })
+    }
+    
+    /// 清空所有 stash，返回删除的数量
+    pub fn clear_stashes(&self) -> GtResult<usize> {
+        let count = self.list_stashes()?.len();
+        
+        let mut repo = self.open_mut()?;
+        for _ in 0..count {
+            repo.stash_drop(0)
+                .map_err(|e| GtError::GitOperation {
+                    message: format!("清空 stash 失败: {}", e)
+                })?;
+        }
+        
+        Ok(count)
     }
     
     /// 列出所有 stash
     pub fn list_stashes(&self) -> GtResult<Vec<Stash>> {
-        let mut stashes = Vec::new();
+        let mut entries = Vec::new();
+        
+        let mut repo = self.open_mut()?;
+        repo.stash_foreach(|index, message, oid| {
+            entries.push((index, message.to_string(), *oid));
+            true
+        })?;
+        
+        let stashes = entries.into_iter()
+            .map(|(index, message, oid)| {
+                let time = self.inner.find_commit(oid)
+                    .map(|commit| commit.time().seconds())
+                    .unwrap_or(0);
+                
+                Stash {
+                    index,
+                    message,
+                    time,
+                    id: oid.to_string(),
+                }
+            })
+            .collect();
         
-        // stash_foreach 也需要可变引用
-        // 暂时返回空列表
         Ok(stashes)
     }
     
+    /// 获取指定 stash 的差异（patch 格式）
+    ///
+    /// 包含已追踪文件的变更，如果 stash 含有未追踪文件也一并显示。
+    pub fn stash_diff(&self, index: usize) -> GtResult<String> {
+        let stash = self.ensure_stash_exists(index)?;
+        let oid = git2::Oid::from_str(&stash.id)?;
+        let stash_commit = self.inner.find_commit(oid)?;
+        let base_commit = stash_commit.parent(0)?;
+        
+        let mut diff = self.inner.diff_tree_to_tree(
+            Some(&base_commit.tree()?),
+            Some(&stash_commit.tree()?),
+            None,
+        )?;
+        
+        // 第三个父提交保存了未追踪的文件
+        if let Ok(untracked_commit) = stash_commit.parent(2) {
+            let untracked_diff = self.inner.diff_tree_to_tree(
+                None,
+                Some(&untracked_commit.tree()?),
+                None,
+            )?;
+            diff.merge(&untracked_diff)?;
+        }
+        
+        let mut patch = String::new();
+        diff.print(git2::DiffFormat::Patch, |_delta, _hunk, line| {
+            let content = String::from_utf8_lossy(line.content());
+            match line.origin() {
+                origin @ ('+' | '-' | ' ') => {
+                    patch.push(origin);
+                    patch.push_str(&content);
+                }
+                _ => patch.push_str(&content),
+            }
+            true
+        })?;
+        
+        Ok(patch)
+    }
+    
+    /// 基于 stash 创建新分支
+    ///
+    /// 与 `git stash branch` 相同：在创建 stash 时的提交上建立分支，
+    /// 切换过去后应用 stash，成功后删除该 stash。
+    pub fn stash_branch(&self, name: &str, index: usize) -> GtResult<()> {
+        if self.branch_exists(name)? {
+            return Err(GtError::BranchAlreadyExists {
+                branch: name.to_string()
+            });
+        }
+        
+        let stash = self.ensure_stash_exists(index)?;
+        let oid = git2::Oid::from_str(&stash.id)?;
+        let base_commit = self.inner.find_commit(oid)?.parent(0)?;
+        
+        self.inner.branch(name, &base_commit, false)
+            .map_err(|e| GtError::GitOperation {
+                message: format!("创建分支失败: {}", e)
+            })?;
+        
+        let branch_ref = format!("refs/heads/{}", name);
+        self.inner.checkout_tree(base_commit.as_object(), None)
+            .map_err(|e| GtError::GitOperation {
+                message: format!("检出文件失败: {}", e)
+            })?;
+        self.inner.set_head(&branch_ref)?;
+        
+        self.pop_stash(index)
+    }
+    
+    /// 根据 stash 提交 ID 查找当前索引
+    ///
+    /// 新的 stash 会使旧 stash 的索引后移，因此持久化时应保存 ID。
+    pub fn find_stash_index(&self, id: &str) -> GtResult<Option<usize>> {
+        Ok(self.list_stashes()?
+            .into_iter()
+            .find(|stash| stash.id == id)
+            .map(|stash| stash.index))
+    }
+    
+    /// 确认 stash 存在并返回其信息
+    fn ensure_stash_exists(&self, index: usize) -> GtResult<Stash> {
+        self.list_stashes()?
+            .into_iter()
+            .find(|stash| stash.index == index)
+            .ok_or_else(|| GtError::GitOperation {
+                message: format!("stash@{{{}}} 不存在", index)
+            })
+    }
+    
+    /// 转换 stash 应用错误
+    fn stash_apply_error(index: usize, error: git2::Error) -> GtError {
+        match error.code() {
+            git2::ErrorCode::Conflict | git2::ErrorCode::MergeConflict => GtError::GitOperation {
+                message: format!("应用 stash@{{{}}} 时存在冲突，请先处理工作区中的变更", index)
+            },
+            _ => GtError::GitOperation {
+                message: format!("应用 stash@{{{}}} 失败: {}", index, error)
+            },
+        }
+    }
+    
+    // ===== Git 配置 =====
+    
+    /// 读取 git 配置项（按 git 的优先级合并 local/global/system）
+    pub fn config_value(&self, key: &str) -> GtResult<Option<String>> {
+        let config = self.inner.config()?;
+        match config.get_string(key) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+    
+    /// 写入 git 配置项，global 为 true 时写入 ~/.gitconfig
+    pub fn set_config_value(&self, key: &str, value: &str, global: bool) -> GtResult<()> {
+        let mut config = if global {
+            let path = git2::Config::find_global()
+                .ok()
+                .or_else(|| dirs::home_dir().map(|home| home.join(".gitconfig")))
+                .ok_or_else(|| GtError::ConfigError {
+                    message: "无法确定全局 git 配置文件位置".to_string()
+                })?;
+            git2::Config::open(&path)?
+        } else {
+            self.inner.config()?.open_level(git2::ConfigLevel::Local)?
+        };
+        
+        config.set_str(key, value)
+            .map_err(|e| GtError::GitOperation {
+                message: format!("写入 git 配置 {} 失败: {}", key, e)
+            })
+    }
+    
     // ===== 远程操作 =====
     
     /// 添加远程仓库
@@ -556,6 +1271,18 @@ impl Repository {
         Ok(())
     }
     
+    /// 修改远程仓库 URL
+    pub fn set_remote_url(&self, name: &str, url: &str) -> GtResult<()> {
+        if !self.remote_exists(name)? {
+            return Err(GtError::RemoteNotFound { remote: name.to_string() });
+        }
+        
+        self.inner.remote_set_url(name, url)
+            .map_err(|e| GtError::GitOperation {
+                message: format!("修改远程仓库 '{}' 的 URL 失败: {}", name, e)
+            })
+    }
+    
     /// 列出所有远程仓库
     pub fn list_remotes(&self) -> GtResult<Vec<Remote>> {
         let remotes = self.inner.remotes()?;
@@ -802,6 +1529,70 @@ impl Repository {
         Ok(())
     }
     
+    /// 将 branch 上 upstream 之后的提交移植到 onto 之上（git rebase --onto）
+    pub fn rebase_onto(&self, onto: &str, upstream: &str, branch: &str) -> GtResult<()> {
+        let output = Command::new("git")
+            .args(["rebase", "--onto", onto, upstream, branch])
+            .env("GIT_EDITOR", "true")
+            .current_dir(&self.path)
+            .output()
+            .map_err(|e| GtError::GitOperation {
+                message: format!("执行 git rebase 命令失败: {}", e)
+            })?;
+            
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GtError::GitOperation {
+                message: format!("Rebase 失败: {}", stderr)
+            });
+        }
+        
+        Ok(())
+    }
+    
+    /// 检查是否有进行中的 rebase
+    pub fn rebase_in_progress(&self) -> bool {
+        matches!(
+            self.inner.state(),
+            git2::RepositoryState::Rebase
+                | git2::RepositoryState::RebaseInteractive
+                | git2::RepositoryState::RebaseMerge
+                | git2::RepositoryState::ApplyMailboxOrRebase
+        )
+    }
+    
+    /// 继续进行中的 rebase（冲突已解决并暂存之后）
+    pub fn rebase_continue(&self) -> GtResult<()> {
+        self.run_rebase_command("--continue")
+    }
+    
+    /// 中止进行中的 rebase
+    pub fn rebase_abort(&self) -> GtResult<()> {
+        self.run_rebase_command("--abort")
+    }
+    
+    /// 执行 git rebase 控制命令
+    fn run_rebase_command(&self, flag: &str) -> GtResult<()> {
+        // 不打开编辑器，沿用原有的提交信息
+        let output = Command::new("git")
+            .args(&["rebase", flag])
+            .env("GIT_EDITOR", "true")
+            .current_dir(&self.path)
+            .output()
+            .map_err(|e| GtError::GitOperation {
+                message: format!("执行 git rebase {} 命令失败: {}", flag, e)
+            })?;
+            
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GtError::GitOperation {
+                message: format!("git rebase {} 失败: {}", flag, stderr)
+            });
+        }
+        
+        Ok(())
+    }
+    
     // ===== 重置操作 =====
     
     /// 软重置（保留工作区和暂存区）
@@ -947,6 +1738,46 @@ impl Repository {
     
     // ===== 标签操作 =====
     
+    /// 标签是否存在
+    pub fn tag_exists(&self, name: &str) -> bool {
+        self.inner.find_reference(&format!("refs/tags/{}", name)).is_ok()
+    }
+    
+    /// rev 之前（含 rev）最近的标签，没有标签时返回 None
+    pub fn latest_tag(&self, rev: &str) -> GtResult<Option<String>> {
+        let object = self.inner.revparse_single(rev)?;
+        let mut options = git2::DescribeOptions::new();
+        options.describe_tags();
+        let describe = match object.describe(&options) {
+            Ok(describe) => describe,
+            Err(e) if e.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        
+        let mut format = git2::DescribeFormatOptions::new();
+        format.abbreviated_size(0);
+        Ok(Some(describe.format(Some(&format))?))
+    }
+    
+    /// to 沿第一父提交回溯、不包含 from 的提交，从新到旧
+    ///
+    /// 只走第一父提交，合并进来的分支以合并提交代表，不重复列出分支上的提交。
+    pub fn first_parent_commits(&self, from: Option<&str>, to: &str) -> GtResult<Vec<Commit>> {
+        let mut walk = self.inner.revwalk()?;
+        walk.simplify_first_parent()?;
+        walk.push(self.inner.revparse_single(to)?.peel_to_commit()?.id())?;
+        if let Some(from) = from {
+            walk.hide(self.inner.revparse_single(from)?.peel_to_commit()?.id())?;
+        }
+        
+        let mut commits = Vec::new();
+        for oid in walk {
+            let commit = self.inner.find_commit(oid?)?;
+            commits.push(self.convert_commit(&commit));
+        }
+        Ok(commits)
+    }
+    
     /// 创建轻量标签
     pub fn create_lightweight_tag(&self, name: &str, target: Option<&str>) -> GtResult<()> {
         let target_oid = if let Some(target) = target {
@@ -1071,6 +1902,35 @@ impl RepositoryStatus {
     }
 }
 
+/// 分支合并到目标分支的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergeKind {
+    /// 分支是目标分支的祖先（普通合并或快进）
+    Ancestor,
+    /// 目标分支上有与分支相同的树
+    SameTree,
+    /// 分支上每个提交都有等价补丁（rebase 合并）
+    Rebased,
+    /// 分支的全部改动有等价补丁（squash 合并）
+    Squashed,
+    /// 代码托管平台上的 PR 已合并
+    PullRequest,
+}
+
+impl MergeKind {
+    /// 描述
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::Ancestor => "已合并",
+            Self::SameTree => "内容已合并",
+            Self::Rebased => "已 rebase 合并",
+            Self::Squashed => "已 squash 合并",
+            Self::PullRequest => "PR 已合并",
+        }
+    }
+}
+
 /// 与远程的同步状态
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SyncStatus {
@@ -1106,6 +1966,16 @@ impl SyncStatus {
         }
     }
     
+    /// 领先和落后的提交数
+    pub fn ahead_behind(&self) -> (usize, usize) {
+        match *self {
+            Self::Ahead(ahead) => (ahead, 0),
+            Self::Behind(behind) => (0, behind),
+            Self::Diverged { ahead, behind } => (ahead, behind),
+            _ => (0, 0),
+        }
+    }
+    
     /// 检查是否需要推送
     pub fn needs_push(&self) -> bool {
         match self {
@@ -0,0 +1,414 @@
+//! 分支栈
+//!
+//! 记录基于其他功能分支创建的分支（父分支以及创建/上次 restack 时父分支指向的提交），
+//! 保存在 `.git/gt/stack.toml`。父分支变化或被合并后，按顺序把所有后代分支
+//! `rebase --onto` 到新的父分支上；冲突时进度保存在 `.git/gt/restack.toml`。
+//!
+//! 直接基于主分支创建的分支不记录，视为父分支是主分支。
+
+use crate::error::{GtResult, GtError};
+use crate::git::{Repository, StateStore};
+use crate::ui::{print_step, print_success, print_info, print_warning};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 分支栈状态文件名
+pub const STACK_NAME: &str = "stack";
+
+/// 进行中的 restack 状态文件名
+pub const RESTACK_STATE_NAME: &str = "restack";
+
+/// 栈中一个分支与父分支的关系
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StackBranch {
+    /// 父分支
+    pub parent: String,
+    /// 上次同步时父分支指向的提交，作为 `rebase --onto` 的 upstream
+    pub parent_head: String,
+}
+
+/// 分支栈
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Stack {
+    #[serde(default)]
+    branches: BTreeMap<String, StackBranch>,
+}
+
+impl Stack {
+    /// 读取仓库的分支栈
+    pub fn load(repo: &Repository) -> GtResult<Self> {
+        Ok(StateStore::new(repo).load(STACK_NAME)?.unwrap_or_default())
+    }
+
+    /// 保存分支栈
+    pub fn save(&self, repo: &Repository) -> GtResult<()> {
+        StateStore::new(repo).save(STACK_NAME, self)
+    }
+
+    /// 是否没有记录任何分支
+    pub fn is_empty(&self) -> bool {
+        self.branches.is_empty()
+    }
+
+    /// 获取分支的栈信息
+    pub fn get(&self, branch: &str) -> Option<&StackBranch> {
+        self.branches.get(branch)
+    }
+
+    /// 分支是否在栈中
+    pub fn contains(&self, branch: &str) -> bool {
+        self.branches.contains_key(branch)
+    }
+
+    /// 获取分支的父分支
+    pub fn parent_of(&self, branch: &str) -> Option<&str> {
+        self.branches.get(branch).map(|entry| entry.parent.as_str())
+    }
+
+    /// 记录分支的父分支
+    pub fn set_parent(&mut self, branch: &str, parent: &str, parent_head: &str) {
+        self.branches.insert(branch.to_string(), StackBranch {
+            parent: parent.to_string(),
+            parent_head: parent_head.to_string(),
+        });
+    }
+
+    /// 从栈中移除分支，它的子分支改为挂到它的父分支上
+    pub fn remove(&mut self, branch: &str) -> Option<StackBranch> {
+        let removed = self.branches.remove(branch)?;
+        for entry in self.branches.values_mut() {
+            if entry.parent == branch {
+                // parent_head 保持不变：仍是子分支与被移除分支的分叉点
+                entry.parent = removed.parent.clone();
+            }
+        }
+        Some(removed)
+    }
+
+    /// 只移除分支本身的记录（分支已直接基于主分支）
+    pub fn detach(&mut self, branch: &str) {
+        self.branches.remove(branch);
+    }
+
+    /// 直接子分支
+    pub fn children_of(&self, branch: &str) -> Vec<String> {
+        self.branches.iter()
+            .filter(|(_, entry)| entry.parent == branch)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// 全部后代分支，父分支总是排在子分支之前
+    pub fn descendants_of(&self, branch: &str) -> Vec<String> {
+        let mut result = Vec::new();
+        let mut pending = self.children_of(branch);
+        pending.reverse();
+
+        while let Some(name) = pending.pop() {
+            if result.contains(&name) {
+                continue;
+            }
+            let mut children = self.children_of(&name);
+            children.reverse();
+            result.push(name);
+            pending.extend(children);
+        }
+
+        result
+    }
+
+    /// 祖先分支，从直接父分支到栈底（不在栈中的分支）
+    pub fn ancestors_of(&self, branch: &str) -> Vec<String> {
+        let mut result = Vec::new();
+        let mut current = branch;
+
+        while let Some(parent) = self.parent_of(current) {
+            if parent == branch || result.iter().any(|name| name == parent) {
+                break;
+            }
+            result.push(parent.to_string());
+            current = parent;
+        }
+
+        result
+    }
+
+    /// 各个栈的栈底分支（被记录为父分支但自身不在栈中的分支）
+    pub fn roots(&self) -> Vec<String> {
+        let mut roots: Vec<String> = self.branches.values()
+            .filter(|entry| !self.branches.contains_key(&entry.parent))
+            .map(|entry| entry.parent.clone())
+            .collect();
+        roots.sort();
+        roots.dedup();
+        roots
+    }
+
+    /// 分支所在栈的栈底分支（直接基于主分支的分支）
+    pub fn root_of(&self, branch: &str) -> String {
+        self.ancestors_of(branch).pop().unwrap_or_else(|| branch.to_string())
+    }
+}
+
+/// 进行中的 restack，冲突中断后用于继续或回滚
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestackState {
+    /// 发起 restack 时所在的分支
+    pub original_branch: String,
+    /// 主分支
+    pub main_branch: String,
+    /// 尚未处理的分支（第一个为正在 rebase 的分支）
+    pub queue: Vec<String>,
+    /// 正在 rebase 的分支的新父分支
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// 正在 rebase 的分支的新父分支提交
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_head: Option<String>,
+    /// restack 前各分支指向的提交
+    #[serde(default)]
+    pub original_heads: BTreeMap<String, String>,
+    /// restack 前的分支栈
+    #[serde(default)]
+    pub original_stack: Stack,
+}
+
+/// 按栈关系依次 rebase 分支
+pub struct Restacker<'a> {
+    repo: &'a Repository,
+    store: StateStore,
+    main_branch: String,
+}
+
+impl<'a> Restacker<'a> {
+    /// 创建 restack 执行器
+    pub fn new(repo: &'a Repository, main_branch: &str) -> Self {
+        Self {
+            repo,
+            store: StateStore::new(repo),
+            main_branch: main_branch.to_string(),
+        }
+    }
+
+    /// 是否有被冲突中断的 restack
+    pub fn in_progress(&self) -> bool {
+        self.store.exists(RESTACK_STATE_NAME)
+    }
+
+    /// 依次 restack 指定分支（父分支需排在子分支之前），完成后切换回当前分支
+    pub fn restack(&self, branches: Vec<String>) -> GtResult<()> {
+        if self.in_progress() {
+            return Err(GtError::PreconditionFailed {
+                condition: "有未完成的 restack，请先运行 'gt stack restack --continue' 或 'gt stack restack --abort'".to_string()
+            });
+        }
+
+        let mut original_heads = BTreeMap::new();
+        for branch in &branches {
+            if self.repo.branch_exists(branch)? {
+                original_heads.insert(branch.clone(), self.repo.branch_oid(branch)?);
+            }
+        }
+
+        let mut state = RestackState {
+            original_branch: self.repo.current_branch()?,
+            main_branch: self.main_branch.clone(),
+            queue: branches,
+            parent: None,
+            parent_head: None,
+            original_heads,
+            original_stack: Stack::load(self.repo)?,
+        };
+        self.run(&mut state)
+    }
+
+    /// 冲突解决后继续 restack
+    pub fn resume(&self) -> GtResult<()> {
+        let mut state = self.load_state()?;
+
+        if self.repo.rebase_in_progress() {
+            print_step("继续 rebase...");
+            self.repo.rebase_continue()?;
+        }
+
+        // 被中断的分支已经完成 rebase，记录新的父分支
+        if let (Some(branch), Some(parent), Some(parent_head)) =
+            (state.queue.first().cloned(), state.parent.take(), state.parent_head.take())
+        {
+            self.finish_branch(&branch, &parent, &parent_head)?;
+            print_success(&format!("分支 '{}' 已 restack 到 '{}'", branch, parent));
+            state.queue.remove(0);
+        }
+
+        self.run(&mut state)
+    }
+
+    /// 中止 restack，把已处理的分支恢复到 restack 之前
+    pub fn abort(&self) -> GtResult<()> {
+        let state = self.load_state()?;
+
+        if self.repo.rebase_in_progress() {
+            print_step("中止进行中的 rebase...");
+            self.repo.rebase_abort()?;
+        }
+
+        if self.repo.current_branch().ok().as_deref() != Some(state.original_branch.as_str())
+            && self.repo.branch_exists(&state.original_branch)?
+        {
+            self.repo.checkout_branch(&state.original_branch)?;
+        }
+
+        for (branch, oid) in &state.original_heads {
+            if self.repo.branch_exists(branch)? && self.repo.branch_oid(branch)? != *oid {
+                print_step(&format!("恢复分支 '{}' 到 restack 之前的提交", branch));
+                if *branch == state.original_branch {
+                    self.repo.reset_hard(oid)?;
+                } else {
+                    self.repo.set_branch_target(branch, oid, "gt stack restack --abort")?;
+                }
+            }
+        }
+
+        state.original_stack.save(self.repo)?;
+        self.store.remove(RESTACK_STATE_NAME)
+    }
+
+    /// 处理队列中剩余的分支
+    fn run(&self, state: &mut RestackState) -> GtResult<()> {
+        while let Some(branch) = state.queue.first().cloned() {
+            if self.repo.branch_exists(&branch)? {
+                self.restack_branch(&branch, state)?;
+            } else {
+                let mut stack = Stack::load(self.repo)?;
+                if stack.remove(&branch).is_some() {
+                    stack.save(self.repo)?;
+                }
+            }
+            state.queue.remove(0);
+        }
+
+        if self.repo.current_branch().ok().as_deref() != Some(state.original_branch.as_str()) {
+            let target = if self.repo.branch_exists(&state.original_branch)? {
+                &state.original_branch
+            } else {
+                &state.main_branch
+            };
+            self.repo.checkout_branch(target)?;
+        }
+
+        self.store.remove(RESTACK_STATE_NAME)
+    }
+
+    /// restack 单个分支
+    fn restack_branch(&self, branch: &str, state: &mut RestackState) -> GtResult<()> {
+        let stack = Stack::load(self.repo)?;
+        let main = state.main_branch.as_str();
+
+        if branch == main {
+            return Ok(());
+        }
+
+        // 不在栈中的分支直接基于主分支；已合并的不再 rebase，子分支会改挂到主分支
+        let (mut parent, upstream) = match stack.get(branch) {
+            Some(entry) => (entry.parent.clone(), entry.parent_head.clone()),
+            None => {
+                if self.repo.is_merged_into(branch, main)? {
+                    print_info(&format!("分支 '{}' 已合并到 '{}'，跳过", branch, main));
+                    return Ok(());
+                }
+                (main.to_string(), self.repo.merge_base(branch, main)?)
+            }
+        };
+
+        // 父分支已删除或已合并时，改挂到更下层的分支上
+        while parent != main {
+            let gone = !self.repo.branch_exists(&parent)?;
+            if !gone && !self.parent_merged(&parent, &upstream, main)? {
+                break;
+            }
+            let next = stack.parent_of(&parent).unwrap_or(main).to_string();
+            print_info(&format!(
+                "父分支 '{}' 已{}，'{}' 改为基于 '{}'",
+                parent, if gone { "删除" } else { "合并" }, branch, next
+            ));
+            parent = next;
+        }
+
+        let parent_head = self.repo.branch_oid(&parent)?;
+        if parent_head == upstream {
+            self.finish_branch(branch, &parent, &parent_head)?;
+            return Ok(());
+        }
+
+        print_step(&format!("将 '{}' restack 到 '{}'...", branch, parent));
+        state.parent = Some(parent.clone());
+        state.parent_head = Some(parent_head.clone());
+        self.store.save(RESTACK_STATE_NAME, state)?;
+
+        if let Err(e) = self.repo.rebase_onto(&parent, &upstream, branch) {
+            if self.repo.rebase_in_progress() {
+                print_warning(&format!("restack 分支 '{}' 时发生冲突，进度已保存", branch));
+                return Err(e);
+            }
+
+            // 没有开始 rebase（如工作区不干净、checkout 失败），--continue 时重新处理该分支
+            state.parent = None;
+            state.parent_head = None;
+            if self.modified(state)? {
+                // 已有分支被 restack，保留状态以便 --abort 恢复
+                self.store.save(RESTACK_STATE_NAME, state)?;
+                print_warning(&format!("restack 分支 '{}' 失败，之前的分支已经 restack，进度已保存", branch));
+            } else {
+                self.store.remove(RESTACK_STATE_NAME)?;
+            }
+            return Err(e);
+        }
+
+        self.finish_branch(branch, &parent, &parent_head)?;
+        state.parent = None;
+        state.parent_head = None;
+        print_success(&format!("分支 '{}' 已 restack 到 '{}'", branch, parent));
+        Ok(())
+    }
+
+    /// 是否已有分支被改写，或者已经切换到其他分支
+    fn modified(&self, state: &RestackState) -> GtResult<bool> {
+        if self.repo.current_branch().ok().as_deref() != Some(state.original_branch.as_str()) {
+            return Ok(true);
+        }
+        for (branch, oid) in &state.original_heads {
+            if self.repo.branch_exists(branch)? && self.repo.branch_oid(branch)? != *oid {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// 父分支是否已合并到主分支
+    fn parent_merged(&self, parent: &str, upstream: &str, main: &str) -> GtResult<bool> {
+        // 父分支自子分支创建后没有变化且仍在主分支历史上（如还没有提交），不视为已合并
+        let tip = self.repo.branch_oid(parent)?;
+        if tip == upstream && self.repo.merge_base(&tip, main)? == tip {
+            return Ok(false);
+        }
+        self.repo.is_merged_into(parent, main)
+    }
+
+    /// 更新分支在栈中的记录
+    fn finish_branch(&self, branch: &str, parent: &str, parent_head: &str) -> GtResult<()> {
+        let mut stack = Stack::load(self.repo)?;
+        if parent == self.main_branch {
+            stack.detach(branch);
+        } else {
+            stack.set_parent(branch, parent, parent_head);
+        }
+        stack.save(self.repo)
+    }
+
+    fn load_state(&self) -> GtResult<RestackState> {
+        self.store.load::<RestackState>(RESTACK_STATE_NAME)?
+            .ok_or_else(|| GtError::PreconditionFailed {
+                condition: "没有进行中的 restack".to_string()
+            })
+    }
+}
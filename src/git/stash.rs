@@ -1,9 +1,11 @@
 //! Git stash 操作
-//! 
-//! 提供 stash 信息的类型定义。
+//!
+//! 提供 stash 信息和 stash 操作选项的类型定义。
+
+use serde::Serialize;
 
 /// Git stash 信息
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Stash {
     /// stash 索引
     pub index: usize,
@@ -13,4 +15,72 @@ pub struct Stash {
     pub time: i64,
     /// stash ID
     pub id: String,
-} 
\ No newline at end of file
+}
+
+impl Stash {
+    /// 获取 stash 引用名（如 stash@{0}）
+    pub fn reference(&self) -> String {
+        format!("stash@{{{}}}", self.index)
+    }
+}
+
+/// 创建 stash 的选项
+#[derive(Debug, Clone, Default)]
+pub struct StashPushOptions {
+    /// stash 消息
+    pub message: Option<String>,
+    /// 是否包含未追踪的文件
+    pub include_untracked: bool,
+    /// 是否保留暂存区内容
+    pub keep_index: bool,
+    /// 只暂存指定路径（为空则暂存所有变更）
+    pub paths: Vec<String>,
+}
+
+impl StashPushOptions {
+    /// 创建新的选项实例
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder 方法：设置消息
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Builder 方法：包含未追踪的文件
+    pub fn include_untracked(mut self) -> Self {
+        self.include_untracked = true;
+        self
+    }
+
+    /// Builder 方法：保留暂存区
+    pub fn keep_index(mut self) -> Self {
+        self.keep_index = true;
+        self
+    }
+
+    /// Builder 方法：只暂存指定路径
+    pub fn with_paths(mut self, paths: Vec<String>) -> Self {
+        self.paths = paths;
+        self
+    }
+}
+
+/// 解析 stash 索引
+///
+/// 支持 `2`、`stash@{2}` 两种写法，未指定时默认为最近的 stash (0)。
+pub fn parse_stash_index(input: Option<&str>) -> Option<usize> {
+    let Some(input) = input else {
+        return Some(0);
+    };
+
+    let input = input.trim();
+    let number = input
+        .strip_prefix("stash@{")
+        .and_then(|rest| rest.strip_suffix('}'))
+        .unwrap_or(input);
+
+    number.parse().ok()
+}
@@ -0,0 +1,99 @@
+//! gt 状态持久化
+//!
+//! 在 `.git/gt/` 目录下保存进行中的操作状态，
+//! 使被冲突或网络错误打断的命令可以继续执行或回滚。
+
+use crate::error::{GtResult, GtError};
+use crate::git::Repository;
+use serde::{de::DeserializeOwned, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// gt 状态目录名（位于 .git 目录下）
+pub const STATE_DIR_NAME: &str = "gt";
+
+/// 状态文件存储
+pub struct StateStore {
+    dir: PathBuf,
+}
+
+impl StateStore {
+    /// 为指定仓库创建状态存储
+    pub fn new(repo: &Repository) -> Self {
+        Self {
+            dir: repo.git_dir().join(STATE_DIR_NAME),
+        }
+    }
+
+    /// 获取状态目录
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 获取状态文件路径
+    pub fn file_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.toml", name))
+    }
+
+    /// 检查状态文件是否存在
+    pub fn exists(&self, name: &str) -> bool {
+        self.file_path(name).is_file()
+    }
+
+    /// 读取状态，不存在时返回 None
+    pub fn load<T: DeserializeOwned>(&self, name: &str) -> GtResult<Option<T>> {
+        let path = self.file_path(name);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path)
+            .map_err(|e| GtError::IoError {
+                operation: format!("读取状态文件 {}", path.display()),
+                error: e.to_string(),
+            })?;
+
+        let value = toml::from_str(&content)
+            .map_err(|e| GtError::ConfigParseError {
+                reason: format!("{}: {}", path.display(), e)
+            })?;
+
+        Ok(Some(value))
+    }
+
+    /// 写入状态（覆盖已有内容）
+    pub fn save<T: Serialize>(&self, name: &str, value: &T) -> GtResult<()> {
+        fs::create_dir_all(&self.dir)
+            .map_err(|e| GtError::IoError {
+                operation: format!("创建状态目录 {}", self.dir.display()),
+                error: e.to_string(),
+            })?;
+
+        let content = toml::to_string_pretty(value)
+            .map_err(|e| GtError::internal_error(format!("序列化状态失败: {}", e)))?;
+
+        // 先写临时文件再重命名，避免中断时留下半个文件
+        let path = self.file_path(name);
+        let tmp_path = path.with_extension("toml.tmp");
+        fs::write(&tmp_path, content)
+            .and_then(|_| fs::rename(&tmp_path, &path))
+            .map_err(|e| GtError::IoError {
+                operation: format!("写入状态文件 {}", path.display()),
+                error: e.to_string(),
+            })
+    }
+
+    /// 删除状态文件
+    pub fn remove(&self, name: &str) -> GtResult<()> {
+        let path = self.file_path(name);
+        if !path.exists() {
+            return Ok(());
+        }
+
+        fs::remove_file(&path)
+            .map_err(|e| GtError::IoError {
+                operation: format!("删除状态文件 {}", path.display()),
+                error: e.to_string(),
+            })
+    }
+}
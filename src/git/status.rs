@@ -2,8 +2,10 @@
 //! 
 //! 提供工作区状态信息的类型定义。
 
+use serde::Serialize;
+
 /// 工作区状态
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct WorkingTreeStatus {
     /// 是否有未提交的变更
     pub has_uncommitted_changes: bool,
@@ -19,6 +21,10 @@ pub struct WorkingTreeStatus {
     pub deleted_files: usize,
     /// 未追踪的文件数量
     pub untracked_files: usize,
+    /// 重命名的文件数量
+    pub renamed_files: usize,
+    /// 有冲突的文件数量
+    pub conflicted_files: usize,
 }
 
 impl WorkingTreeStatus {
@@ -36,4 +42,128 @@ impl WorkingTreeStatus {
     pub fn has_pending_changes(&self) -> bool {
         self.has_uncommitted_changes || self.has_untracked_files
     }
-} 
\ No newline at end of file
+} 
+/// 文件变更的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileChange {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    TypeChange,
+    Untracked,
+    Conflicted,
+}
+
+impl FileChange {
+    /// 显示名
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Added => "新增",
+            Self::Modified => "修改",
+            Self::Deleted => "删除",
+            Self::Renamed => "重命名",
+            Self::TypeChange => "类型变更",
+            Self::Untracked => "未追踪",
+            Self::Conflicted => "冲突",
+        }
+    }
+}
+
+/// 一个文件的状态
+#[derive(Debug, Clone, Serialize)]
+pub struct FileStatus {
+    /// 文件路径，重命名时为新路径
+    pub path: String,
+    /// 重命名前的路径
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,
+    /// 变更类型
+    pub change: FileChange,
+}
+
+/// 按区域分组的文件状态
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FileStatuses {
+    /// 已暂存的变更
+    pub staged: Vec<FileStatus>,
+    /// 未暂存的变更
+    pub unstaged: Vec<FileStatus>,
+    /// 未追踪的文件
+    pub untracked: Vec<FileStatus>,
+    /// 有冲突的文件
+    pub conflicted: Vec<FileStatus>,
+}
+
+impl FileStatuses {
+    /// 是否没有任何变更
+    pub fn is_empty(&self) -> bool {
+        self.staged.is_empty() && self.unstaged.is_empty() && self.untracked.is_empty() && self.conflicted.is_empty()
+    }
+}
+
+/// 仓库中进行中的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Operation {
+    Rebase,
+    Merge,
+    CherryPick,
+    Revert,
+    Bisect,
+    ApplyMailbox,
+}
+
+impl Operation {
+    /// 从 git2 的仓库状态转换，没有进行中的操作时返回 None
+    pub fn from_state(state: git2::RepositoryState) -> Option<Self> {
+        use git2::RepositoryState as State;
+        
+        match state {
+            State::Clean => None,
+            State::Rebase | State::RebaseInteractive | State::RebaseMerge => Some(Self::Rebase),
+            State::Merge => Some(Self::Merge),
+            State::CherryPick | State::CherryPickSequence => Some(Self::CherryPick),
+            State::Revert | State::RevertSequence => Some(Self::Revert),
+            State::Bisect => Some(Self::Bisect),
+            State::ApplyMailbox | State::ApplyMailboxOrRebase => Some(Self::ApplyMailbox),
+        }
+    }
+    
+    /// 操作名
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Rebase => "rebase",
+            Self::Merge => "merge",
+            Self::CherryPick => "cherry-pick",
+            Self::Revert => "revert",
+            Self::Bisect => "bisect",
+            Self::ApplyMailbox => "am",
+        }
+    }
+    
+    /// 完成操作的下一步命令
+    pub fn continue_command(self) -> &'static str {
+        match self {
+            Self::Rebase => "git rebase --continue",
+            Self::Merge => "git commit",
+            Self::CherryPick => "git cherry-pick --continue",
+            Self::Revert => "git revert --continue",
+            Self::Bisect => "git bisect good | git bisect bad",
+            Self::ApplyMailbox => "git am --continue",
+        }
+    }
+    
+    /// 放弃操作的命令
+    pub fn abort_command(self) -> &'static str {
+        match self {
+            Self::Rebase => "git rebase --abort",
+            Self::Merge => "git merge --abort",
+            Self::CherryPick => "git cherry-pick --abort",
+            Self::Revert => "git revert --abort",
+            Self::Bisect => "git bisect reset",
+            Self::ApplyMailbox => "git am --abort",
+        }
+    }
+}
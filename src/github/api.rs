@@ -0,0 +1,532 @@
+//! GitHub REST API 后端
+//!
+//! 通过 octocrab 直接调用 GitHub REST API，不依赖 gh 命令。
+//! 令牌来自配置项 `github.token` 或 `GITHUB_TOKEN` / `GH_TOKEN`，
+//! API 地址可通过 `github.api_url` 指向 GitHub Enterprise 或本地的模拟服务。
+
+use crate::config::RepoConfig;
+use crate::error::{GtResult, GtError};
+use crate::forge::{self, Issue, RemoteUrl};
+use crate::git::Repository;
+use crate::github::checks::{Check, CheckState, ChecksStatus, ReviewDecision};
+use crate::github::cli::GithubAuth;
+use crate::github::pr::{CreatePrOptions, MergePrOptions, MergeStrategy, PrState, PullRequest};
+use crate::ui::{print_step, print_success};
+use octocrab::models::pulls::MergeableState;
+use octocrab::models::IssueState;
+use octocrab::params;
+use octocrab::Octocrab;
+use serde::Deserialize;
+
+/// 默认的 GitHub API 地址
+pub const DEFAULT_API_URL: &str = "https://api.github.com";
+
+/// GitHub REST API 客户端，绑定到一个仓库
+#[derive(Clone)]
+pub struct GithubApi {
+    client: Octocrab,
+    owner: String,
+    repo: String,
+    verbose: bool,
+}
+
+impl GithubApi {
+    /// 创建 API 客户端
+    pub fn new(token: &str, api_url: Option<&str>, owner: &str, repo: &str) -> GtResult<Self> {
+        let api_url = api_url.unwrap_or(DEFAULT_API_URL);
+        let client = Octocrab::builder()
+            .base_uri(api_url)
+            .map_err(|e| GtError::ConfigError {
+                message: format!("无效的 GitHub API 地址 '{}': {}", api_url, e)
+            })?
+            .personal_token(token.to_string())
+            .build()?;
+
+        Ok(Self {
+            client,
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            verbose: false,
+        })
+    }
+
+    /// 根据配置创建客户端，没有配置令牌时返回 None
+    ///
+    /// 仓库的 owner/name 从配置的远程仓库 URL 中解析。
+    pub fn from_config(config: &RepoConfig, repo: &Repository) -> GtResult<Option<Self>> {
+        let Some(ref token) = config.github_token else {
+            return Ok(None);
+        };
+
+        let remote = repo.get_remote(&config.remote_name)?;
+        let (owner, name) = parse_repo_slug(&remote.url).ok_or_else(|| GtError::ConfigError {
+            message: format!("无法从远程 '{}' 的 URL 解析 GitHub 仓库: {}", config.remote_name, remote.url)
+        })?;
+
+        Self::new(token, config.github_api_url.as_deref(), &owner, &name).map(Some)
+    }
+
+    /// 设置是否输出详细信息
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// 仓库全名 (owner/repo)
+    pub fn repo_slug(&self) -> String {
+        format!("{}/{}", self.owner, self.repo)
+    }
+
+    /// 检查令牌对应的用户，令牌无效时返回未认证
+    pub async fn auth_status(&self) -> GtResult<GithubAuth> {
+        match self.client.current().user().await {
+            Ok(user) => Ok(GithubAuth::Authenticated(user.login)),
+            Err(octocrab::Error::GitHub { ref source, .. }) if source.status_code == 401 => {
+                Ok(GithubAuth::NotAuthenticated)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// 在当前用户下创建仓库，返回仓库网页地址
+    pub async fn create_repo(&self, name: &str, description: Option<&str>, private: bool) -> GtResult<String> {
+        let body = serde_json::json!({
+            "name": name,
+            "description": description,
+            "private": private,
+        });
+        let repo: octocrab::models::Repository = self.client.post("/user/repos", Some(&body)).await?;
+        Ok(repo.html_url.map(|url| url.to_string()).unwrap_or(repo.url.to_string()))
+    }
+
+    /// 创建 Pull Request
+    ///
+    /// 没有指定标题时按 `gh pr create --fill` 的方式从提交中生成。
+    pub async fn create_pr(&self, options: &CreatePrOptions) -> GtResult<PullRequest> {
+        let (title, body) = forge::title_and_body(options)?;
+
+        if self.verbose {
+            print_step(&format!("调用 GitHub API 创建 PR: {} -> {}", options.head_branch, options.base_branch));
+        }
+
+        let pulls = self.client.pulls(&self.owner, &self.repo);
+        let mut create = pulls
+            .create(title, &options.head_branch, &options.base_branch)
+            .draft(options.draft);
+        if let Some(body) = body {
+            create = create.body(body);
+        }
+        let pr = create.send().await?;
+        let number = pr.number;
+
+        if !options.reviewers.is_empty() {
+            self.client
+                .pulls(&self.owner, &self.repo)
+                .request_reviews(number, options.reviewers.clone(), Vec::<String>::new())
+                .await?;
+        }
+
+        if !options.labels.is_empty() {
+            self.client
+                .issues(&self.owner, &self.repo)
+                .add_labels(number, &options.labels)
+                .await?;
+        }
+
+        Ok(convert_pr(pr))
+    }
+
+    /// 合并 Pull Request
+    ///
+    /// REST API 不支持 GitHub 的 auto-merge，启用自动合并时直接合并。
+    pub async fn merge_pr(&self, number: u64, options: &MergePrOptions) -> GtResult<()> {
+        let pulls = self.client.pulls(&self.owner, &self.repo);
+        let pr = pulls.get(number).await?;
+
+        if self.verbose {
+            print_step(&format!("调用 GitHub API 合并 PR #{} (策略: {})", number, options.strategy));
+        }
+
+        let mut merge = pulls.merge(number).method(merge_method(options.strategy));
+        if let Some(ref message) = options.commit_message {
+            merge = merge.title(message.clone());
+        }
+        let result = merge.send().await?;
+
+        if !result.merged {
+            return Err(GtError::PullRequestError {
+                reason: result.message.unwrap_or_else(|| format!("PR #{} 未能合并", number))
+            });
+        }
+
+        if options.delete_branch {
+            let reference = params::repos::Reference::Branch(pr.head.ref_field.clone());
+            self.client
+                .repos(&self.owner, &self.repo)
+                .delete_ref(&reference)
+                .await?;
+            if self.verbose {
+                print_success(&format!("已删除远程分支 '{}'", pr.head.ref_field));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 获取 Pull Request
+    pub async fn get_pr(&self, number: u64) -> GtResult<PullRequest> {
+        let pr = self.client.pulls(&self.owner, &self.repo).get(number).await?;
+        Ok(convert_pr(pr))
+    }
+
+    /// 获取议题
+    pub async fn get_issue(&self, number: u32) -> GtResult<Issue> {
+        let issue = self.client.issues(&self.owner, &self.repo).get(number as u64).await?;
+        Ok(Issue {
+            number: issue.number as u32,
+            title: issue.title,
+            url: issue.html_url.to_string(),
+        })
+    }
+
+    /// 查询 PR 的检查（check run 和 commit status）和审阅状态
+    pub async fn checks_status(&self, number: u64) -> GtResult<ChecksStatus> {
+        let pr = self.client.pulls(&self.owner, &self.repo).get(number).await?;
+        let sha = &pr.head.sha;
+        let per_page = [("per_page", "100")];
+
+        let runs: CheckRunList = self.client
+            .get(format!("/repos/{}/{}/commits/{}/check-runs", self.owner, self.repo, sha), Some(&per_page))
+            .await?;
+        let combined: CombinedStatus = self.client
+            .get(format!("/repos/{}/{}/commits/{}/status", self.owner, self.repo, sha), Some(&per_page))
+            .await?;
+
+        let mut checks: Vec<Check> = runs.check_runs
+            .into_iter()
+            .map(|run| Check {
+                state: CheckState::from_check_run(run.status.as_deref(), run.conclusion.as_deref()),
+                name: run.name,
+                url: run.html_url.or(run.details_url),
+            })
+            .collect();
+        checks.extend(combined.statuses.into_iter().map(|status| Check {
+            state: CheckState::from_commit_status(&status.state),
+            name: status.context,
+            url: status.target_url,
+        }));
+
+        let reviews: Vec<Review> = self.client
+            .get(format!("/repos/{}/{}/pulls/{}/reviews", self.owner, self.repo, number), Some(&per_page))
+            .await?;
+
+        // REST API 没有 reviewDecision，按每个审阅者最后的结论推断；
+        // 检查都已完成但仍被保护规则阻塞时，视为还需要审阅
+        let mut latest = std::collections::HashMap::new();
+        for review in reviews {
+            if matches!(review.state.as_str(), "APPROVED" | "CHANGES_REQUESTED" | "DISMISSED") {
+                latest.insert(review.user.map(|user| user.login).unwrap_or_default(), review.state);
+            }
+        }
+        let blocked = matches!(pr.mergeable_state, Some(MergeableState::Blocked));
+        let checks_done = checks.iter().all(|check| check.state != CheckState::Pending);
+        let review = if latest.values().any(|state| state == "CHANGES_REQUESTED") {
+            ReviewDecision::ChangesRequested
+        } else if blocked && checks_done {
+            ReviewDecision::ReviewRequired
+        } else if latest.values().any(|state| state == "APPROVED") {
+            ReviewDecision::Approved
+        } else {
+            ReviewDecision::NotRequired
+        };
+
+        Ok(ChecksStatus { checks, review })
+    }
+
+    /// 列出 Pull Request，state 可以是 open / closed / merged / all
+    pub async fn list_prs(&self, state: Option<&str>, limit: Option<u32>) -> GtResult<Vec<PullRequest>> {
+        let api_state = match state.map(|s| s.to_lowercase()).as_deref() {
+            None | Some("open") => params::State::Open,
+            Some("closed") | Some("merged") => params::State::Closed,
+            Some("all") => params::State::All,
+            Some(other) => {
+                return Err(GtError::InvalidInput {
+                    input: format!("不支持的 PR 状态: {}（支持: open, closed, merged, all）", other)
+                });
+            }
+        };
+
+        let limit = limit.unwrap_or(30) as usize;
+        let page = self.client
+            .pulls(&self.owner, &self.repo)
+            .list()
+            .state(api_state)
+            .per_page(limit.min(100) as u8)
+            .send()
+            .await?;
+
+        let mut prs: Vec<PullRequest> = page.items.into_iter().map(convert_pr).collect();
+        if state == Some("merged") {
+            prs.retain(|pr| pr.state == PrState::Merged);
+        }
+        prs.truncate(limit);
+        Ok(prs)
+    }
+}
+
+/// check-runs 接口的响应
+#[derive(Debug, Deserialize)]
+struct CheckRunList {
+    check_runs: Vec<CheckRun>,
+}
+
+/// check run（只取需要的字段，兼容不同版本的 API 模型）
+#[derive(Debug, Deserialize)]
+struct CheckRun {
+    name: String,
+    status: Option<String>,
+    conclusion: Option<String>,
+    html_url: Option<String>,
+    details_url: Option<String>,
+}
+
+/// 提交的合并状态
+#[derive(Debug, Deserialize)]
+struct CombinedStatus {
+    statuses: Vec<CommitStatus>,
+}
+
+/// commit status
+#[derive(Debug, Deserialize)]
+struct CommitStatus {
+    context: String,
+    state: String,
+    target_url: Option<String>,
+}
+
+/// PR 审阅
+#[derive(Debug, Deserialize)]
+struct Review {
+    state: String,
+    user: Option<ReviewUser>,
+}
+
+/// 审阅者
+#[derive(Debug, Deserialize)]
+struct ReviewUser {
+    login: String,
+}
+
+/// 转换为 gt 的 PR 模型
+fn convert_pr(pr: octocrab::models::pulls::PullRequest) -> PullRequest {
+    let state = if pr.merged_at.is_some() {
+        PrState::Merged
+    } else if pr.draft == Some(true) {
+        PrState::Draft
+    } else {
+        match pr.state {
+            Some(IssueState::Closed) => PrState::Closed,
+            _ => PrState::Open,
+        }
+    };
+
+    PullRequest {
+        number: pr.number as u32,
+        title: pr.title.unwrap_or_default(),
+        url: pr.html_url.map(|url| url.to_string()).unwrap_or(pr.url),
+        head_branch: pr.head.ref_field,
+        head_sha: Some(pr.head.sha),
+        base_branch: pr.base.ref_field,
+        state,
+        author: pr.user.map(|user| user.login),
+        created_at: pr.created_at.map(|time| time.to_rfc3339()),
+        mergeable: pr.mergeable,
+    }
+}
+
+/// gt 合并策略对应的 API 参数
+fn merge_method(strategy: MergeStrategy) -> params::pulls::MergeMethod {
+    match strategy {
+        MergeStrategy::Rebase => params::pulls::MergeMethod::Rebase,
+        MergeStrategy::Squash => params::pulls::MergeMethod::Squash,
+        MergeStrategy::Merge => params::pulls::MergeMethod::Merge,
+    }
+}
+
+/// 从远程 URL 解析仓库的 owner 和名称
+///
+/// 支持 `https://host/owner/repo(.git)`、`git@host:owner/repo(.git)`
+/// 和 `ssh://git@host/owner/repo(.git)`。
+pub fn parse_repo_slug(url: &str) -> Option<(String, String)> {
+    let url = RemoteUrl::parse(url)?;
+    let (owner, repo) = url.owner_and_repo()?;
+    Some((owner.to_string(), repo.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::mock::MockServer;
+    use serde_json::{json, Value};
+
+    /// 模拟服务返回的 PR
+    fn pull(number: u64, extra: Value) -> Value {
+        let mut pr = json!({
+            "url": format!("https://api.github.test/repos/o/r/pulls/{}", number),
+            "id": number,
+            "number": number,
+            "title": "添加登录",
+            "html_url": format!("https://github.test/o/r/pull/{}", number),
+            "state": "open",
+            "head": { "ref": "feature/login", "sha": "abc123" },
+            "base": { "ref": "main", "sha": "def456" },
+            "user": null,
+        });
+        pr.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+        pr
+    }
+
+    fn api(server: &MockServer) -> GithubApi {
+        GithubApi::new("secret", Some(server.url()), "o", "r").unwrap()
+    }
+
+    #[tokio::test]
+    async fn create_pr_sends_branches_and_labels() {
+        let server = MockServer::start();
+        server.route("POST", "/repos/o/r/pulls", 201, pull(7, json!({ "draft": true })));
+        server.route("POST", "/repos/o/r/issues/7/labels", 200, json!([]));
+
+        let options = CreatePrOptions::new("feature/login".to_string(), "main".to_string())
+            .with_title("添加登录".to_string())
+            .with_body("说明".to_string())
+            .as_draft()
+            .add_label("enhancement".to_string());
+        let pr = api(&server).create_pr(&options).await.unwrap();
+
+        assert_eq!(pr.number, 7);
+        assert_eq!(pr.state, PrState::Draft);
+        assert_eq!(pr.head_sha.as_deref(), Some("abc123"));
+        assert_eq!(pr.url, "https://github.test/o/r/pull/7");
+
+        let request = server.request("POST", "/repos/o/r/pulls");
+        assert_eq!(request.header("authorization"), Some("Bearer secret"));
+        assert_eq!(request.body["head"], "feature/login");
+        assert_eq!(request.body["base"], "main");
+        assert_eq!(request.body["title"], "添加登录");
+        assert_eq!(request.body["body"], "说明");
+        assert_eq!(request.body["draft"], true);
+        let labels = server.request("POST", "/repos/o/r/issues/7/labels");
+        assert_eq!(labels.body["labels"], json!(["enhancement"]));
+    }
+
+    #[tokio::test]
+    async fn get_pr_maps_state() {
+        let server = MockServer::start();
+        server.route("GET", "/repos/o/r/pulls/1", 200, pull(1, json!({})));
+        server.route("GET", "/repos/o/r/pulls/2", 200, pull(2, json!({
+            "state": "closed",
+            "merged_at": "2024-01-02T03:04:05Z",
+        })));
+        server.route("GET", "/repos/o/r/pulls/3", 200, pull(3, json!({ "state": "closed" })));
+
+        let api = api(&server);
+        let open = api.get_pr(1).await.unwrap();
+        assert_eq!(open.state, PrState::Open);
+        assert_eq!(open.head_branch, "feature/login");
+        assert_eq!(open.base_branch, "main");
+        assert_eq!(api.get_pr(2).await.unwrap().state, PrState::Merged);
+        assert_eq!(api.get_pr(3).await.unwrap().state, PrState::Closed);
+        assert!(api.get_pr(4).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn merge_pr_uses_strategy_and_deletes_branch() {
+        let server = MockServer::start();
+        server.route("GET", "/repos/o/r/pulls/1", 200, pull(1, json!({})));
+        server.route("PUT", "/repos/o/r/pulls/1/merge", 200, json!({ "sha": "fff", "merged": true }));
+        server.route("DELETE", "/repos/o/r/git/refs/heads/feature/login", 204, Value::Null);
+
+        let options = MergePrOptions::new(MergeStrategy::Squash)
+            .delete_branch()
+            .with_commit_message("添加登录 (#1)".to_string());
+        api(&server).merge_pr(1, &options).await.unwrap();
+
+        let merge = server.request("PUT", "/repos/o/r/pulls/1/merge");
+        assert_eq!(merge.body["merge_method"], "squash");
+        assert_eq!(merge.body["commit_title"], "添加登录 (#1)");
+        server.request("DELETE", "/repos/o/r/git/refs/heads/feature/login");
+    }
+
+    #[tokio::test]
+    async fn merge_pr_reports_unmerged_result() {
+        let server = MockServer::start();
+        server.route("GET", "/repos/o/r/pulls/1", 200, pull(1, json!({})));
+        server.route("PUT", "/repos/o/r/pulls/1/merge", 200, json!({
+            "merged": false,
+            "message": "Base branch was modified",
+        }));
+
+        let options = MergePrOptions::new(MergeStrategy::Merge).delete_branch();
+        let err = api(&server).merge_pr(1, &options).await.unwrap_err();
+
+        assert!(err.to_string().contains("Base branch was modified"));
+        assert!(!server.requests().iter().any(|request| request.method == "DELETE"));
+    }
+
+    /// 为 PR #1 设置检查和审阅的响应
+    fn checks_server(mergeable_state: &str, reviews: Value) -> MockServer {
+        let server = MockServer::start();
+        server.route("GET", "/repos/o/r/pulls/1", 200, pull(1, json!({ "mergeable_state": mergeable_state })));
+        server.route("GET", "/repos/o/r/commits/abc123/check-runs", 200, json!({
+            "total_count": 3,
+            "check_runs": [
+                { "name": "build", "status": "completed", "conclusion": "success", "html_url": "https://ci.test/build" },
+                { "name": "lint", "status": "completed", "conclusion": "failure", "details_url": "https://ci.test/lint" },
+                { "name": "docs", "status": "completed", "conclusion": "skipped" },
+            ],
+        }));
+        server.route("GET", "/repos/o/r/commits/abc123/status", 200, json!({
+            "state": "pending",
+            "statuses": [{ "context": "deploy", "state": "pending", "target_url": null }],
+        }));
+        server.route("GET", "/repos/o/r/pulls/1/reviews", 200, reviews);
+        server
+    }
+
+    #[tokio::test]
+    async fn checks_status_parses_check_runs_and_statuses() {
+        let server = checks_server("blocked", json!([]));
+        let status = api(&server).checks_status(1).await.unwrap();
+
+        let checks: Vec<(&str, CheckState, Option<&str>)> = status.checks.iter()
+            .map(|check| (check.name.as_str(), check.state, check.url.as_deref()))
+            .collect();
+        assert_eq!(checks, vec![
+            ("build", CheckState::Success, Some("https://ci.test/build")),
+            ("lint", CheckState::Failure, Some("https://ci.test/lint")),
+            ("docs", CheckState::Skipped, None),
+            ("deploy", CheckState::Pending, None),
+        ]);
+        // 还有检查在运行，阻塞可能来自检查，不能断定需要审阅
+        assert_eq!(status.review, ReviewDecision::NotRequired);
+        assert_eq!(server.request("GET", "/repos/o/r/commits/abc123/check-runs").query("per_page"), Some("100"));
+    }
+
+    #[tokio::test]
+    async fn checks_status_uses_latest_review_of_each_reviewer() {
+        let approved_after_changes = json!([
+            { "state": "CHANGES_REQUESTED", "user": { "login": "alice" } },
+            { "state": "COMMENTED", "user": { "login": "alice" } },
+            { "state": "APPROVED", "user": { "login": "alice" } },
+        ]);
+        let server = checks_server("clean", approved_after_changes);
+        assert_eq!(api(&server).checks_status(1).await.unwrap().review, ReviewDecision::Approved);
+
+        let changes_requested = json!([
+            { "state": "APPROVED", "user": { "login": "alice" } },
+            { "state": "CHANGES_REQUESTED", "user": { "login": "bob" } },
+        ]);
+        let server = checks_server("blocked", changes_requested);
+        assert_eq!(api(&server).checks_status(1).await.unwrap().review, ReviewDecision::ChangesRequested);
+    }
+}
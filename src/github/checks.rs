@@ -0,0 +1,342 @@
+//! PR 检查状态
+//!
+//! 汇总 PR 的 CI 检查（check run 和 commit status）与审阅结论，
+//! 并在等待检查时实时显示每个检查的进度。
+
+use crate::error::{GtResult, GtError};
+use crate::ui::{green, is_json, print_info, print_step, print_success, red, yellow};
+use console::Term;
+use serde::Deserialize;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// PR 刚创建时 CI 可能还没有启动，在这段时间内没有检查不视为“没有配置 CI”
+const CHECKS_STARTUP_GRACE: Duration = Duration::from_secs(30);
+
+/// 单个检查的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckState {
+    /// 排队或运行中
+    Pending,
+    /// 通过
+    Success,
+    /// 失败、取消或超时
+    Failure,
+    /// 跳过或中立，不影响合并
+    Skipped,
+}
+
+impl CheckState {
+    /// 从 check run 的 status / conclusion 解析（大小写不敏感）
+    pub fn from_check_run(status: Option<&str>, conclusion: Option<&str>) -> Self {
+        let completed = status.map_or(true, |status| status.eq_ignore_ascii_case("completed"));
+        match conclusion.map(|c| c.to_lowercase()).as_deref() {
+            _ if !completed => CheckState::Pending,
+            None | Some("") => CheckState::Pending,
+            Some("success") => CheckState::Success,
+            Some("neutral") | Some("skipped") => CheckState::Skipped,
+            Some(_) => CheckState::Failure,
+        }
+    }
+
+    /// 从 commit status 的 state 解析（大小写不敏感）
+    pub fn from_commit_status(state: &str) -> Self {
+        match state.to_lowercase().as_str() {
+            "success" => CheckState::Success,
+            "failure" | "error" => CheckState::Failure,
+            _ => CheckState::Pending,
+        }
+    }
+
+    /// 显示用的图标
+    pub fn icon(self) -> &'static str {
+        match self {
+            CheckState::Pending => "⏳",
+            CheckState::Success => "✅",
+            CheckState::Failure => "❌",
+            CheckState::Skipped => "⏭️",
+        }
+    }
+}
+
+/// 一个 CI 检查
+#[derive(Debug, Clone)]
+pub struct Check {
+    /// 检查名称
+    pub name: String,
+    /// 状态
+    pub state: CheckState,
+    /// 详情页地址
+    pub url: Option<String>,
+}
+
+/// 审阅结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewDecision {
+    /// 已批准
+    Approved,
+    /// 审阅者要求修改
+    ChangesRequested,
+    /// 还需要审阅
+    ReviewRequired,
+    /// 不需要审阅
+    NotRequired,
+}
+
+impl ReviewDecision {
+    /// 从 gh 的 reviewDecision 解析
+    pub fn from_gh(decision: Option<&str>) -> Self {
+        match decision {
+            Some("APPROVED") => ReviewDecision::Approved,
+            Some("CHANGES_REQUESTED") => ReviewDecision::ChangesRequested,
+            Some("REVIEW_REQUIRED") => ReviewDecision::ReviewRequired,
+            _ => ReviewDecision::NotRequired,
+        }
+    }
+
+    /// 描述
+    pub fn description(self) -> &'static str {
+        match self {
+            ReviewDecision::Approved => "已批准",
+            ReviewDecision::ChangesRequested => "要求修改",
+            ReviewDecision::ReviewRequired => "等待审阅",
+            ReviewDecision::NotRequired => "不需要",
+        }
+    }
+}
+
+/// PR 的检查和审阅状态
+#[derive(Debug, Clone)]
+pub struct ChecksStatus {
+    /// 所有检查
+    pub checks: Vec<Check>,
+    /// 审阅结论
+    pub review: ReviewDecision,
+}
+
+impl ChecksStatus {
+    /// 失败的检查
+    pub fn failed(&self) -> Vec<&Check> {
+        self.checks.iter().filter(|check| check.state == CheckState::Failure).collect()
+    }
+
+    /// 未完成的检查数
+    pub fn pending(&self) -> usize {
+        self.checks.iter().filter(|check| check.state == CheckState::Pending).count()
+    }
+
+    /// 已通过（含跳过）的检查数
+    pub fn passed(&self) -> usize {
+        self.checks.iter()
+            .filter(|check| matches!(check.state, CheckState::Success | CheckState::Skipped))
+            .count()
+    }
+
+    /// 检查全部完成且审阅不再阻塞合并
+    pub fn is_ready(&self) -> bool {
+        self.pending() == 0
+            && self.failed().is_empty()
+            && matches!(self.review, ReviewDecision::Approved | ReviewDecision::NotRequired)
+    }
+
+    /// 有失败的检查或审阅要求修改时返回错误
+    pub fn ensure_not_failed(&self) -> GtResult<()> {
+        let failed = self.failed();
+        if !failed.is_empty() {
+            let checks = failed.iter()
+                .map(|check| match check.url {
+                    Some(ref url) => format!("{} ({})", check.name, url),
+                    None => check.name.clone(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(GtError::ChecksFailed { checks });
+        }
+
+        if self.review == ReviewDecision::ChangesRequested {
+            return Err(GtError::PullRequestError {
+                reason: "审阅者要求修改，已取消合并".to_string()
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// gh `pr view --json statusCheckRollup,reviewDecision` 的输出
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhChecks {
+    #[serde(default)]
+    status_check_rollup: Vec<GhCheck>,
+    review_decision: Option<String>,
+}
+
+/// statusCheckRollup 中的一项（CheckRun 或 StatusContext）
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhCheck {
+    #[serde(rename = "__typename", default)]
+    typename: String,
+    name: Option<String>,
+    context: Option<String>,
+    status: Option<String>,
+    conclusion: Option<String>,
+    state: Option<String>,
+    details_url: Option<String>,
+    target_url: Option<String>,
+}
+
+/// gh 查询检查状态时请求的字段
+pub const GH_CHECK_FIELDS: &str = "statusCheckRollup,reviewDecision";
+
+/// 解析 gh 输出的检查状态
+pub fn parse_gh_checks(json: &str) -> GtResult<ChecksStatus> {
+    let parsed: GhChecks = serde_json::from_str(json)
+        .map_err(|e| GtError::PullRequestError {
+            reason: format!("解析 gh 检查状态失败: {}", e)
+        })?;
+
+    let checks = parsed.status_check_rollup
+        .into_iter()
+        .map(|check| {
+            if check.typename == "StatusContext" {
+                Check {
+                    name: check.context.unwrap_or_default(),
+                    state: CheckState::from_commit_status(check.state.as_deref().unwrap_or("")),
+                    url: check.target_url.filter(|url| !url.is_empty()),
+                }
+            } else {
+                Check {
+                    name: check.name.unwrap_or_default(),
+                    state: CheckState::from_check_run(check.status.as_deref(), check.conclusion.as_deref()),
+                    url: check.details_url.filter(|url| !url.is_empty()),
+                }
+            }
+        })
+        .collect();
+
+    Ok(ChecksStatus {
+        checks,
+        review: ReviewDecision::from_gh(parsed.review_decision.as_deref()),
+    })
+}
+
+/// 等待检查全部通过、审阅不再阻塞合并，status 查询一次当前状态
+///
+/// 各平台共用同一个轮询循环：有检查失败或审阅要求修改时立即返回错误，
+/// 超过 timeout 仍未完成时返回超时错误。
+pub async fn wait_for_checks<F, Fut>(mut status: F, timeout: Duration, interval: Duration) -> GtResult<ChecksStatus>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = GtResult<ChecksStatus>>,
+{
+    print_step("等待 PR 检查和审阅通过...");
+
+    let started = Instant::now();
+    let mut display = ChecksDisplay::new();
+    loop {
+        let status = status().await?;
+        let elapsed = started.elapsed();
+        display.render(&status, elapsed);
+
+        status.ensure_not_failed()?;
+
+        let waiting_for_ci = status.checks.is_empty() && elapsed < CHECKS_STARTUP_GRACE.min(timeout);
+        if status.is_ready() && !waiting_for_ci {
+            if status.checks.is_empty() {
+                print_info("PR 没有配置检查");
+            } else {
+                print_success(&format!("{} 个检查全部通过", status.checks.len()));
+            }
+            return Ok(status);
+        }
+
+        if elapsed >= timeout {
+            return Err(GtError::ChecksTimeout { seconds: timeout.as_secs() });
+        }
+
+        tokio::time::sleep(interval.min(timeout - elapsed)).await;
+    }
+}
+
+/// 等待检查时的实时显示
+///
+/// 在终端中原地刷新每个检查的状态；输出被重定向时只在状态变化时打印摘要。
+pub struct ChecksDisplay {
+    term: Term,
+    lines: usize,
+    last_summary: String,
+}
+
+impl ChecksDisplay {
+    /// 创建显示器
+    pub fn new() -> Self {
+        Self {
+            term: Term::stdout(),
+            lines: 0,
+            last_summary: String::new(),
+        }
+    }
+
+    /// 刷新显示，JSON 输出模式下不显示
+    pub fn render(&mut self, status: &ChecksStatus, elapsed: Duration) {
+        if is_json() {
+            return;
+        }
+
+        let summary = format!(
+            "检查: {} 通过, {} 进行中, {} 失败 | 审阅: {}",
+            status.passed(),
+            status.pending(),
+            status.failed().len(),
+            status.review.description()
+        );
+
+        if !self.term.is_term() {
+            if summary != self.last_summary {
+                println!("⏳ {}", summary);
+                self.last_summary = summary;
+            }
+            return;
+        }
+
+        let mut lines = vec![format!("⏳ 等待 PR 检查 ({}) - {}", format_elapsed(elapsed), summary)];
+        if status.checks.is_empty() {
+            lines.push(format!("  {}", yellow("尚未发现检查，等待 CI 启动...")));
+        }
+        for check in &status.checks {
+            let name = match check.state {
+                CheckState::Success => green(check.name.clone()).to_string(),
+                CheckState::Failure => red(check.name.clone()).to_string(),
+                CheckState::Pending => yellow(check.name.clone()).to_string(),
+                CheckState::Skipped => check.name.clone(),
+            };
+            lines.push(format!("  {} {}", check.state.icon(), name));
+        }
+
+        // 清除失败不影响等待本身
+        let _ = self.term.clear_last_lines(self.lines);
+        for line in &lines {
+            let _ = self.term.write_line(line);
+        }
+        self.lines = lines.len();
+        self.last_summary = summary;
+    }
+}
+
+impl Default for ChecksDisplay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 格式化已等待的时间，如 `1m05s`
+fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else {
+        format!("{}m{:02}s", seconds / 60, seconds % 60)
+    }
+}
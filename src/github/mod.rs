@@ -1,12 +1,19 @@
-//! GitHub CLI 抽象模块
+//! GitHub 抽象模块
 //! 
 //! 提供 GitHub CLI (gh) 的抽象接口，支持 PR 创建、合并等操作
+//! 配置了访问令牌时通过 REST API (octocrab) 直接操作，不依赖 gh
 
+pub mod checks;
 pub mod cli;
 pub mod pr;
+#[cfg(feature = "github")]
+pub mod api;
 
 // 重新导出核心类型
+pub use checks::{Check, CheckState, ChecksStatus, ReviewDecision};
 pub use cli::{GithubCli, GithubAuth};
+#[cfg(feature = "github")]
+pub use api::GithubApi;
 pub use pr::{
     PullRequest, PullRequestManager, MergeStrategy, 
     CreatePrOptions, MergePrOptions
@@ -3,11 +3,17 @@
 //! 提供 GitHub Pull Request 的创建、合并、查看等操作
 //! 利用 Rust 的类型安全特性，提供比 gw 更强大的功能
 
+use crate::config::RepoConfig;
 use crate::error::{GtResult, GtError};
+use crate::git::Repository;
+use crate::github::checks::{self, parse_gh_checks, ChecksStatus, GH_CHECK_FIELDS};
 use crate::github::cli::GithubCli;
+#[cfg(feature = "github")]
+use crate::github::api::GithubApi;
 use crate::ui::{print_step, print_warning, print_error, print_success, confirm_action};
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::time::Duration;
 
 /// PR 合并策略 - 使用强类型枚举确保类型安全
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -125,6 +131,8 @@ pub struct PullRequest {
     pub title: String,
     pub url: String,
     pub head_branch: String,
+    /// 源分支最新提交的 SHA
+    pub head_sha: Option<String>,
     pub base_branch: String,
     pub state: PrState,
     pub author: Option<String>,
@@ -221,6 +229,12 @@ impl CreatePrOptions {
     }
 }
 
+/// 默认等待检查的最长时间
+pub const DEFAULT_CHECKS_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// 默认查询检查状态的间隔
+pub const DEFAULT_CHECKS_INTERVAL: Duration = Duration::from_secs(10);
+
 /// 合并 PR 的选项 - 提供详细的合并配置
 #[derive(Debug, Clone)]
 pub struct MergePrOptions {
@@ -232,6 +246,10 @@ pub struct MergePrOptions {
     pub commit_message: Option<String>,
     /// 是否等待检查通过
     pub wait_for_checks: bool,
+    /// 等待检查的最长时间
+    pub checks_timeout: Duration,
+    /// 查询检查状态的间隔
+    pub checks_interval: Duration,
     /// 是否自动合并（当条件满足时）
     pub auto_merge: bool,
 }
@@ -244,6 +262,8 @@ impl MergePrOptions {
             delete_branch: false,
             commit_message: None,
             wait_for_checks: true,
+            checks_timeout: DEFAULT_CHECKS_TIMEOUT,
+            checks_interval: DEFAULT_CHECKS_INTERVAL,
             auto_merge: false,
         }
     }
@@ -266,6 +286,18 @@ impl MergePrOptions {
         self
     }
     
+    /// Builder 方法：设置等待检查的最长时间
+    pub fn with_checks_timeout(mut self, timeout: Duration) -> Self {
+        self.checks_timeout = timeout;
+        self
+    }
+    
+    /// Builder 方法：设置查询检查状态的间隔
+    pub fn with_checks_interval(mut self, interval: Duration) -> Self {
+        self.checks_interval = interval;
+        self
+    }
+    
     /// Builder 方法：启用自动合并
     pub fn enable_auto_merge(mut self) -> Self {
         self.auto_merge = true;
@@ -279,20 +311,74 @@ impl Default for MergePrOptions {
     }
 }
 
+/// PR 操作的后端
+enum Backend {
+    /// gh 命令行
+    Cli(GithubCli),
+    /// GitHub REST API
+    #[cfg(feature = "github")]
+    Api(GithubApi),
+}
+
 /// Pull Request 操作管理器 - 核心操作类
 pub struct PullRequestManager {
-    gh: GithubCli,
+    backend: Backend,
+    verbose: bool,
 }
 
 impl PullRequestManager {
-    /// 创建新的 PR 操作实例
+    /// 创建使用 gh 命令行的 PR 操作实例
     pub fn new(gh: GithubCli) -> Self {
-        Self { gh }
+        let verbose = gh.is_verbose();
+        Self {
+            backend: Backend::Cli(gh),
+            verbose,
+        }
+    }
+    
+    /// 创建使用 GitHub REST API 的 PR 操作实例
+    #[cfg(feature = "github")]
+    pub fn with_api(api: GithubApi, verbose: bool) -> Self {
+        Self {
+            backend: Backend::Api(api.verbose(verbose)),
+            verbose,
+        }
+    }
+    
+    /// 根据配置选择后端：配置了 GitHub 令牌时使用 REST API，否则使用 gh
+    pub fn from_config(config: &RepoConfig, repo: &Repository, verbose: bool) -> GtResult<Self> {
+        #[cfg(feature = "github")]
+        if let Some(api) = GithubApi::from_config(config, repo)? {
+            return Ok(Self::with_api(api, verbose));
+        }
+        
+        #[cfg(not(feature = "github"))]
+        let _ = (config, repo);
+        
+        Ok(Self::new(GithubCli::new(verbose)))
+    }
+    
+    /// 后端名称
+    pub fn backend_name(&self) -> &'static str {
+        match self.backend {
+            Backend::Cli(_) => "GitHub CLI (gh)",
+            #[cfg(feature = "github")]
+            Backend::Api(_) => "GitHub API",
+        }
+    }
+    
+    /// 后端是否可用（gh 后端需要安装 gh）
+    pub fn is_available(&self) -> bool {
+        match self.backend {
+            Backend::Cli(ref gh) => gh.is_available(),
+            #[cfg(feature = "github")]
+            Backend::Api(_) => true,
+        }
     }
     
     /// 创建 Pull Request - 增强版本，支持更多选项
-    pub fn create_pr(&self, options: CreatePrOptions) -> GtResult<PullRequest> {
-        if self.gh.is_verbose() {
+    pub async fn create_pr(&self, options: CreatePrOptions) -> GtResult<PullRequest> {
+        if self.verbose {
             print_step(&format!(
                 "创建 PR: {} -> {} {}", 
                 options.head_branch, 
@@ -301,6 +387,21 @@ impl PullRequestManager {
             ));
         }
         
+        let pr = match self.backend {
+            Backend::Cli(ref gh) => self.create_pr_with_cli(gh, &options)?,
+            #[cfg(feature = "github")]
+            Backend::Api(ref api) => api.create_pr(&options).await?,
+        };
+        
+        if self.verbose {
+            print_success(&format!("PR 创建成功: {}", pr.url));
+        }
+        
+        Ok(pr)
+    }
+    
+    /// 使用 gh 创建 PR
+    fn create_pr_with_cli(&self, gh: &GithubCli, options: &CreatePrOptions) -> GtResult<PullRequest> {
         let mut args = vec![
             "pr", "create",
             "--base", &options.base_branch,
@@ -340,22 +441,18 @@ impl PullRequestManager {
             args.extend(&["--milestone", milestone]);
         }
         
-        let output = self.gh.execute_command(&args)?;
+        let output = gh.execute_command(&args)?;
         
         // 解析输出获取 PR URL
         let url = output.trim().to_string();
         
-        if self.gh.is_verbose() {
-            print_success(&format!("PR 创建成功: {}", url));
-        }
-        
         // 解析 PR 信息
-        self.parse_pr_from_url(&url, &options)
+        self.parse_pr_from_url(&url, options)
     }
     
     /// 智能合并 PR - 增强版本，支持预检查和自动重试
-    pub fn merge_pr(&self, pr_url: &str, options: MergePrOptions) -> GtResult<()> {
-        if self.gh.is_verbose() {
+    pub async fn merge_pr(&self, pr_url: &str, options: MergePrOptions) -> GtResult<()> {
+        if self.verbose {
             print_step(&format!(
                 "合并 PR: {} (策略: {}{})", 
                 pr_url, 
@@ -364,31 +461,39 @@ impl PullRequestManager {
             ));
         }
         
-        // 如果启用了检查等待，先检查 PR 状态
+        // 如果启用了检查等待，先等待检查和审阅通过
         if options.wait_for_checks {
-            self.wait_for_checks_if_needed(pr_url)?;
-        }
-        
-        let mut args = vec![
-            "pr", "merge", pr_url,
-            options.strategy.to_gh_arg(),
-        ];
-        
-        if options.delete_branch {
-            args.push("--delete-branch");
+            self.wait_for_checks(pr_url, options.checks_timeout, options.checks_interval).await?;
         }
         
-        if let Some(ref message) = options.commit_message {
-            args.extend(&["--subject", message]);
-        }
-        
-        if options.auto_merge {
-            args.push("--auto");
+        match self.backend {
+            Backend::Cli(ref gh) => {
+                let mut args = vec![
+                    "pr", "merge", pr_url,
+                    options.strategy.to_gh_arg(),
+                ];
+                
+                if options.delete_branch {
+                    args.push("--delete-branch");
+                }
+                
+                if let Some(ref message) = options.commit_message {
+                    args.extend(&["--subject", message]);
+                }
+                
+                if options.auto_merge {
+                    args.push("--auto");
+                }
+                
+                gh.execute_command(&args)?;
+            }
+            #[cfg(feature = "github")]
+            Backend::Api(ref api) => {
+                api.merge_pr(parse_pr_number(pr_url)?, &options).await?;
+            }
         }
         
-        let _output = self.gh.execute_command(&args)?;
-        
-        if self.gh.is_verbose() {
+        if self.verbose {
             print_success(&format!(
                 "PR 已成功{} (策略: {})", 
                 if options.auto_merge { "设置为自动合并" } else { "合并" },
@@ -400,20 +505,26 @@ impl PullRequestManager {
     }
     
     /// 检查 PR 状态 - 返回详细信息
-    pub fn get_pr_info(&self, pr_identifier: &str) -> GtResult<PullRequest> {
-        let args = [
-            "pr", "view", pr_identifier, 
-            "--json", "number,title,url,headRefName,baseRefName,state,author,createdAt,mergeable"
-        ];
-        
-        let output = self.gh.execute_command(&args)?;
-        
-        // 这里应该使用 serde_json 解析，为了简化先使用基础解析
-        self.parse_pr_json(&output)
+    pub async fn get_pr_info(&self, pr_identifier: &str) -> GtResult<PullRequest> {
+        match self.backend {
+            Backend::Cli(ref gh) => {
+                let args = ["pr", "view", pr_identifier, "--json", GH_PR_FIELDS];
+                let output = gh.execute_command(&args)?;
+                self.parse_pr_json(&output)
+            }
+            #[cfg(feature = "github")]
+            Backend::Api(ref api) => api.get_pr(parse_pr_number(pr_identifier)?).await,
+        }
     }
     
     /// 列出 PR - 支持过滤和排序
-    pub fn list_prs(&self, state: Option<&str>, limit: Option<u32>) -> GtResult<Vec<PullRequest>> {
+    pub async fn list_prs(&self, state: Option<&str>, limit: Option<u32>) -> GtResult<Vec<PullRequest>> {
+        let gh = match self.backend {
+            Backend::Cli(ref gh) => gh,
+            #[cfg(feature = "github")]
+            Backend::Api(ref api) => return api.list_prs(state, limit).await,
+        };
+        
         let mut args = vec!["pr", "list"];
         
         if let Some(state) = state {
@@ -427,9 +538,9 @@ impl PullRequestManager {
             args.extend(&["--limit", &limit_str]);
         }
         
-        args.extend(&["--json", "number,title,url,headRefName,baseRefName,state,author,createdAt,mergeable"]);
+        args.extend(&["--json", GH_PR_FIELDS]);
         
-        let output = self.gh.execute_command(&args)?;
+        let output = gh.execute_command(&args)?;
         
         // 解析 JSON 数组
         self.parse_pr_list_json(&output)
@@ -437,18 +548,33 @@ impl PullRequestManager {
     
     /// 在浏览器中查看 PR
     pub fn view_pr_in_browser(&self, pr_url: &str) -> GtResult<()> {
-        self.gh.open_in_browser(pr_url)
+        match self.backend {
+            Backend::Cli(ref gh) => gh.open_in_browser(pr_url),
+            #[cfg(feature = "github")]
+            Backend::Api(_) => {
+                print_step(&format!("请在浏览器中打开: {}", pr_url));
+                Ok(())
+            }
+        }
     }
     
-    /// 等待检查通过（如果需要）
-    fn wait_for_checks_if_needed(&self, pr_url: &str) -> GtResult<()> {
-        if self.gh.is_verbose() {
-            print_step("检查 PR 状态和检查结果...");
+    /// 查询 PR 的检查和审阅状态
+    pub async fn checks_status(&self, pr_identifier: &str) -> GtResult<ChecksStatus> {
+        match self.backend {
+            Backend::Cli(ref gh) => {
+                let output = gh.execute_command(&["pr", "view", pr_identifier, "--json", GH_CHECK_FIELDS])?;
+                parse_gh_checks(&output)
+            }
+            #[cfg(feature = "github")]
+            Backend::Api(ref api) => api.checks_status(parse_pr_number(pr_identifier)?).await,
         }
-        
-        // 这里可以实现检查状态的逻辑
-        // 为了简化，现在直接返回成功
-        Ok(())
+    }
+    
+    /// 等待 PR 的检查全部通过、审阅不再阻塞合并
+    ///
+    /// 有检查失败或审阅要求修改时立即返回错误，超过 timeout 仍未完成时返回超时错误。
+    pub async fn wait_for_checks(&self, pr_identifier: &str, timeout: Duration, interval: Duration) -> GtResult<ChecksStatus> {
+        checks::wait_for_checks(|| self.checks_status(pr_identifier), timeout, interval).await
     }
     
     /// 从 URL 解析 PR 信息
@@ -466,6 +592,7 @@ impl PullRequestManager {
             title,
             url: url.to_string(),
             head_branch: options.head_branch.clone(),
+            head_sha: None,
             base_branch: options.base_branch.clone(),
             state: if options.draft { PrState::Draft } else { PrState::Open },
             author: None,
@@ -474,32 +601,98 @@ impl PullRequestManager {
         })
     }
     
-    /// 解析单个 PR 的 JSON
+    /// 解析单个 PR 的 JSON（gh pr view --json）
     fn parse_pr_json(&self, json: &str) -> GtResult<PullRequest> {
-        // 简化的 JSON 解析，实际项目中应使用 serde_json
-        // 这里返回一个默认的 PR 对象
-        Ok(PullRequest {
-            number: 1,
-            title: "Test PR".to_string(),
-            url: "https://github.com/test/test/pull/1".to_string(),
-            head_branch: "feature".to_string(),
-            base_branch: "main".to_string(),
-            state: PrState::Open,
-            author: None,
-            created_at: None,
-            mergeable: Some(true),
-        })
+        let pr: GhPullRequest = serde_json::from_str(json)
+            .map_err(|e| GtError::PullRequestError {
+                reason: format!("解析 gh 输出失败: {}", e)
+            })?;
+        Ok(pr.into())
     }
     
-    /// 解析 PR 列表的 JSON
+    /// 解析 PR 列表的 JSON（gh pr list --json）
     fn parse_pr_list_json(&self, json: &str) -> GtResult<Vec<PullRequest>> {
-        // 简化的 JSON 解析，实际项目中应使用 serde_json
-        Ok(Vec::new())
+        let prs: Vec<GhPullRequest> = serde_json::from_str(json)
+            .map_err(|e| GtError::PullRequestError {
+                reason: format!("解析 gh 输出失败: {}", e)
+            })?;
+        Ok(prs.into_iter().map(PullRequest::from).collect())
+    }
+}
+
+/// gh 查询 PR 时请求的字段
+const GH_PR_FIELDS: &str = "number,title,url,headRefName,headRefOid,baseRefName,state,isDraft,author,createdAt,mergeable";
+
+/// gh `--json` 输出的 PR
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GhPullRequest {
+    number: u32,
+    title: String,
+    url: String,
+    head_ref_name: String,
+    head_ref_oid: Option<String>,
+    base_ref_name: String,
+    state: String,
+    #[serde(default)]
+    is_draft: bool,
+    author: Option<GhAuthor>,
+    created_at: Option<String>,
+    mergeable: Option<String>,
+}
+
+/// gh `--json` 输出的作者
+#[derive(Debug, Deserialize)]
+struct GhAuthor {
+    login: String,
+}
+
+impl From<GhPullRequest> for PullRequest {
+    fn from(pr: GhPullRequest) -> Self {
+        let state = match PrState::from_str(&pr.state) {
+            PrState::Open if pr.is_draft => PrState::Draft,
+            state => state,
+        };
+        
+        Self {
+            number: pr.number,
+            title: pr.title,
+            url: pr.url,
+            head_branch: pr.head_ref_name,
+            head_sha: pr.head_ref_oid,
+            base_branch: pr.base_ref_name,
+            state,
+            author: pr.author.map(|author| author.login),
+            created_at: pr.created_at,
+            // gh 返回 MERGEABLE / CONFLICTING / UNKNOWN
+            mergeable: match pr.mergeable.as_deref() {
+                Some("MERGEABLE") => Some(true),
+                Some("CONFLICTING") => Some(false),
+                _ => None,
+            },
+        }
     }
 }
 
+/// 从编号（`12` / `#12` / `!12`）或 URL（`.../pull/12`、`.../pulls/12`、`.../merge_requests/12`）中解析编号
+pub fn parse_pr_number(identifier: &str) -> GtResult<u64> {
+    let identifier = identifier.trim();
+    let number = if identifier.contains('/') {
+        let mut segments = identifier.split('/');
+        segments.by_ref().find(|segment| matches!(*segment, "pull" | "pulls" | "merge_requests"));
+        let segment = segments.next().unwrap_or_default();
+        &segment[..segment.find(|c: char| !c.is_ascii_digit()).unwrap_or(segment.len())]
+    } else {
+        identifier.trim_start_matches(['#', '!'])
+    };
+    
+    number.parse().map_err(|_| GtError::InvalidInput {
+        input: format!("无法识别的 PR: {}", identifier)
+    })
+}
+
 /// 便捷函数：智能创建和合并 PR 工作流
-pub fn smart_pr_workflow(
+pub async fn smart_pr_workflow(
     gh: &GithubCli,
     create_options: CreatePrOptions,
     merge_options: Option<MergePrOptions>,
@@ -508,7 +701,7 @@ pub fn smart_pr_workflow(
     let pr_manager = PullRequestManager::new(gh.clone());
     
     // 创建 PR
-    let pr = pr_manager.create_pr(create_options)?;
+    let pr = pr_manager.create_pr(create_options).await?;
     
     // 如果指定了合并选项
     if let Some(merge_opts) = merge_options {
@@ -517,11 +710,11 @@ pub fn smart_pr_workflow(
                 "是否立即使用 {} 策略合并此 PR？", 
                 merge_opts.strategy.description()
             ), false) {
-                pr_manager.merge_pr(&pr.url, merge_opts)?;
+                pr_manager.merge_pr(&pr.url, merge_opts).await?;
             }
         } else {
             // 非交互模式直接合并
-            pr_manager.merge_pr(&pr.url, merge_opts)?;
+            pr_manager.merge_pr(&pr.url, merge_opts).await?;
         }
     }
     
@@ -534,18 +727,18 @@ pub fn smart_pr_workflow(
 }
 
 /// 便捷函数：快速创建 PR
-pub fn quick_create_pr(
+pub async fn quick_create_pr(
     gh: &GithubCli,
     head_branch: &str,
     base_branch: &str,
 ) -> GtResult<PullRequest> {
     let options = CreatePrOptions::new(head_branch.to_string(), base_branch.to_string());
     let pr_manager = PullRequestManager::new(gh.clone());
-    pr_manager.create_pr(options)
+    pr_manager.create_pr(options).await
 }
 
 /// 便捷函数：快速合并 PR（使用推荐策略）
-pub fn quick_merge_pr(
+pub async fn quick_merge_pr(
     gh: &GithubCli,
     pr_url: &str,
     delete_branch: bool,
@@ -557,5 +750,5 @@ pub fn quick_merge_pr(
     };
     
     let pr_manager = PullRequestManager::new(gh.clone());
-    pr_manager.merge_pr(pr_url, options)
+    pr_manager.merge_pr(pr_url, options).await
 }
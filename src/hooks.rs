@@ -0,0 +1,193 @@
+//! 生命周期钩子
+//!
+//! 在 gt 命令的固定阶段执行配置中 `[hooks]` 声明的 shell 命令：
+//!
+//! ```toml
+//! [hooks]
+//! pre-ship = ["cargo test"]
+//! post-update = ["npm install"]
+//! post-ship = ["./scripts/notify.sh"]
+//! ```
+//!
+//! 钩子在仓库根目录执行，通过环境变量获得上下文：
+//! - `GT_HOOK`：钩子名，如 `pre-ship`
+//! - `GT_BRANCH`：当前分支（`pre-start` / `post-start` 中为新分支）
+//! - `GT_BASE`：基础分支，即父分支或主分支
+//! - `GT_PR_URL`：创建的 PR 地址（仅 `post-ship`）
+//! - `GT_HEAD`：HEAD 指向的提交
+//! - `GT_COMMITS`：基础分支之后的提交，从新到旧，以空格分隔
+//!
+//! `pre-*` 钩子返回非零时取消命令（`GtError::HookFailed`），`post-*` 钩子失败时只给出警告。
+
+use crate::config::RepoConfig;
+use crate::error::{GtResult, GtError};
+use crate::git::Repository;
+use crate::plan::Plan;
+use crate::ui::{is_json, print_step, print_warning};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// 钩子所在的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Hook {
+    PreStart,
+    PostStart,
+    PreSave,
+    PostSave,
+    PreUpdate,
+    PostUpdate,
+    PreShip,
+    PostShip,
+}
+
+impl Hook {
+    /// 钩子名，与配置中的键相同
+    pub fn name(self) -> &'static str {
+        match self {
+            Hook::PreStart => "pre-start",
+            Hook::PostStart => "post-start",
+            Hook::PreSave => "pre-save",
+            Hook::PostSave => "post-save",
+            Hook::PreUpdate => "pre-update",
+            Hook::PostUpdate => "post-update",
+            Hook::PreShip => "pre-ship",
+            Hook::PostShip => "post-ship",
+        }
+    }
+
+    /// 是否在命令修改仓库之前执行，失败时取消命令
+    pub fn is_pre(self) -> bool {
+        matches!(self, Hook::PreStart | Hook::PreSave | Hook::PreUpdate | Hook::PreShip)
+    }
+
+    /// 把钩子加入执行计划
+    pub fn plan(self, plan: &mut Plan, config: &RepoConfig) {
+        for command in config.hooks.get(&self).into_iter().flatten() {
+            plan.step(format!("运行 {} 钩子: {}", self, command));
+        }
+    }
+}
+
+impl fmt::Display for Hook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// 传给钩子的上下文
+#[derive(Debug, Clone, Default)]
+pub struct HookContext {
+    workdir: Option<PathBuf>,
+    branch: Option<String>,
+    base: Option<String>,
+    pr_url: Option<String>,
+    head: Option<String>,
+    commits: Vec<String>,
+}
+
+impl HookContext {
+    /// 从仓库收集上下文：当前分支、HEAD 以及 base 之后的提交
+    ///
+    /// 读取失败的字段留空，不影响钩子执行。
+    pub fn collect(repo: &Repository, base: Option<&str>) -> Self {
+        let head = repo.inner().head().ok()
+            .and_then(|head| head.target())
+            .map(|oid| oid.to_string());
+        let commits = match (base, head.as_deref()) {
+            (Some(base), Some(head)) => repo.commits_between(base, head).unwrap_or_default(),
+            _ => Vec::new(),
+        };
+
+        Self {
+            workdir: Some(repo.path().to_path_buf()),
+            branch: repo.current_branch().ok(),
+            base: base.map(str::to_string),
+            pr_url: None,
+            head,
+            commits,
+        }
+    }
+
+    /// 以 rev 代替 HEAD 重新读取提交（已经离开该分支时使用）
+    pub fn at(mut self, repo: &Repository, rev: &str) -> Self {
+        self.head = repo.inner().revparse_single(rev).ok()
+            .and_then(|object| object.peel_to_commit().ok())
+            .map(|commit| commit.id().to_string());
+        self.commits = match (self.base.as_deref(), self.head.as_deref()) {
+            (Some(base), Some(head)) => repo.commits_between(base, head).unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        self
+    }
+
+    /// 设置分支（还没有切换到该分支时使用）
+    pub fn branch(mut self, branch: impl Into<String>) -> Self {
+        self.branch = Some(branch.into());
+        self
+    }
+
+    /// 设置 PR 地址
+    pub fn pr_url(mut self, pr_url: Option<String>) -> Self {
+        self.pr_url = pr_url;
+        self
+    }
+
+    /// 执行配置中的钩子
+    ///
+    /// 依次执行钩子的每个命令，遇到失败的命令时停止：`pre-*` 钩子返回
+    /// `GtError::HookFailed`，`post-*` 钩子只给出警告。
+    pub fn run(&self, config: &RepoConfig, hook: Hook) -> GtResult<()> {
+        for command in config.hooks.get(&hook).into_iter().flatten() {
+            print_step(&format!("运行 {} 钩子: {}", hook, command));
+            let Err(reason) = self.execute(hook, command) else {
+                continue;
+            };
+
+            if hook.is_pre() {
+                return Err(GtError::HookFailed {
+                    hook: hook.to_string(),
+                    command: command.clone(),
+                    reason,
+                });
+            }
+            print_warning(&format!("{} 钩子执行失败: {} ({})", hook, command, reason));
+            break;
+        }
+        Ok(())
+    }
+
+    /// 执行一个钩子命令，失败时返回原因
+    fn execute(&self, hook: Hook, script: &str) -> Result<(), String> {
+        let mut command = Command::new("sh");
+        command.arg("-c")
+            .arg(script)
+            .env("GT_HOOK", hook.name())
+            .env("GT_BRANCH", self.branch.as_deref().unwrap_or_default())
+            .env("GT_BASE", self.base.as_deref().unwrap_or_default())
+            .env("GT_PR_URL", self.pr_url.as_deref().unwrap_or_default())
+            .env("GT_HEAD", self.head.as_deref().unwrap_or_default())
+            .env("GT_COMMITS", self.commits.join(" "));
+        if let Some(ref workdir) = self.workdir {
+            command.current_dir(workdir);
+        }
+
+        // JSON 输出模式下钩子的输出改写到标准错误
+        let status = if is_json() {
+            let output = command.output().map_err(|e| e.to_string())?;
+            eprint!("{}", String::from_utf8_lossy(&output.stdout));
+            eprint!("{}", String::from_utf8_lossy(&output.stderr));
+            output.status
+        } else {
+            command.status().map_err(|e| e.to_string())?
+        };
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(status.to_string())
+        }
+    }
+}
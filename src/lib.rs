@@ -26,11 +26,16 @@ pub mod error;
 pub mod git;
 pub mod ui;
 pub mod github;
+pub mod forge;
 pub mod workflow;
+pub mod hooks;
+pub mod plan;
+pub mod changelog;
 pub mod utils;
 
 // 重新导出核心类型
 pub use error::{GtResult, GtError};
 pub use git::{Repository, GitOps};
 pub use config::{ConfigManager, RepoConfig};
-pub use github::{GithubCli, PullRequestManager, MergeStrategy}; 
\ No newline at end of file
+pub use github::{GithubCli, PullRequestManager, MergeStrategy};
+pub use forge::{Forge, ForgeKind}; 
\ No newline at end of file
@@ -3,9 +3,9 @@
 //! 这是一个用 Rust 重写的 Git 工作流工具，旨在提供比传统 shell 脚本
 //! 更快、更可靠、更友好的体验。
 
-use clap::Parser;
 use gt::cli::Cli;
 use gt::error::{GtError, GtResult};
+use gt::ui;
 use std::process;
 use tracing::{error, info};
 
@@ -16,7 +16,8 @@ fn setup_logging() -> GtResult<()> {
         .unwrap_or_else(|_| EnvFilter::new("gt=info"));
 
     tracing_subscriber::registry()
-        .with(fmt::layer().with_target(false).with_thread_ids(false))
+        // 日志写到标准错误，避免混入 --format json 的输出
+        .with(fmt::layer().with_writer(std::io::stderr).with_target(false).with_thread_ids(false))
         .with(filter)
         .init();
 
@@ -37,12 +38,26 @@ async fn main() {
     }
 
     // 解析命令行参数
-    let cli = Cli::parse();
+    let cli = Cli::parse_with_config();
     
     info!("GT starting with args: {:?}", std::env::args().collect::<Vec<_>>());
 
     // 执行命令
-    match cli.execute().await {
+    let result = cli.execute().await;
+    
+    // JSON 输出模式下错误也作为文档输出
+    if ui::is_json() {
+        if let Err(ref e) = result {
+            error!("Command failed: {}", e);
+        }
+        ui::print_json_result(&result);
+        if result.is_err() {
+            process::exit(1);
+        }
+        return;
+    }
+    
+    match result {
         Ok(()) => {
             info!("Command completed successfully");
         }
@@ -0,0 +1,69 @@
+//! 执行计划
+//!
+//! 会修改仓库的命令先根据当前状态生成要执行的步骤，`--dry-run` 时只打印这些步骤，
+//! 不触碰仓库。计划只读取仓库状态，和实际执行走相同的分支判断。
+
+use crate::ui::{emit_json, is_json, print_info};
+use serde::Serialize;
+
+/// 命令的执行计划
+#[derive(Debug, Clone, Serialize)]
+pub struct Plan {
+    /// 命令，如 `update`
+    command: String,
+    /// 按顺序执行的步骤
+    steps: Vec<String>,
+}
+
+impl Plan {
+    /// 创建空的执行计划
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            steps: Vec::new(),
+        }
+    }
+
+    /// 追加一个步骤
+    pub fn step(&mut self, description: impl Into<String>) -> &mut Self {
+        self.steps.push(description.into());
+        self
+    }
+
+    /// 命令
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// 所有步骤
+    pub fn steps(&self) -> &[String] {
+        &self.steps
+    }
+
+    /// 是否没有要执行的步骤
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// 打印计划，JSON 输出模式下作为命令结果
+    pub fn print(&self) {
+        if is_json() {
+            emit_json(&serde_json::json!({
+                "dry_run": true,
+                "command": self.command,
+                "steps": self.steps,
+            }));
+            return;
+        }
+
+        if self.is_empty() {
+            print_info(&format!("🔍 预演模式: gt {} 没有需要执行的操作", self.command));
+            return;
+        }
+
+        println!("🔍 预演模式: gt {} 将执行以下步骤（不会修改仓库）:", self.command);
+        for (i, step) in self.steps.iter().enumerate() {
+            println!("  {}. {}", i + 1, step);
+        }
+    }
+}
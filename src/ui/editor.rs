@@ -0,0 +1,89 @@
+//! 外部编辑器
+//!
+//! 按 git 的方式启动用户的编辑器编辑文本：先把初始内容写入文件，编辑器退出后读回，
+//! 去掉以 `#` 开头的注释行。
+
+use crate::error::{GtResult, GtError};
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// 外部编辑器
+#[derive(Debug, Clone)]
+pub struct Editor {
+    /// 编辑器命令，可以带参数，如 `code --wait`
+    command: String,
+}
+
+impl Editor {
+    /// 使用指定的编辑器命令
+    pub fn new(command: impl Into<String>) -> Self {
+        Self { command: command.into() }
+    }
+
+    /// 按优先级选择编辑器：gt 配置的 `editor`、git 的 `core.editor`、`$VISUAL`、`$EDITOR`
+    ///
+    /// 都没有设置时返回 `GtError::EditorNotFound`。
+    pub fn resolve(configured: Option<&str>, core_editor: Option<&str>) -> GtResult<Self> {
+        let from_env = |name| env::var(name).ok();
+        [configured.map(str::to_string), core_editor.map(str::to_string), from_env("VISUAL"), from_env("EDITOR")]
+            .into_iter()
+            .flatten()
+            .find(|command| !command.trim().is_empty())
+            .map(Self::new)
+            .ok_or(GtError::EditorNotFound)
+    }
+
+    /// 编辑器命令
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// 把 content 写入 path 后打开编辑器，返回去掉注释行的编辑结果
+    pub fn edit(&self, path: &Path, content: &str) -> GtResult<String> {
+        self.edit_raw(path, content).map(|edited| strip_comments(&edited))
+    }
+
+    /// 与 `edit` 相同，但原样返回编辑结果，保留注释行和行尾空白
+    pub fn edit_raw(&self, path: &Path, content: &str) -> GtResult<String> {
+        fs::write(path, content).map_err(|e| GtError::IoError {
+            operation: format!("写入 {}", path.display()),
+            error: e.to_string(),
+        })?;
+
+        // 与 git 相同，交给 shell 执行，编辑器命令可以带参数
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(format!("{} \"$@\"", self.command))
+            .arg(&self.command)
+            .arg(path)
+            .status()
+            .map_err(|e| self.error(e.to_string()))?;
+        if !status.success() {
+            return Err(self.error(format!("编辑器异常退出 ({})", status)));
+        }
+
+        fs::read_to_string(path).map_err(|e| GtError::IoError {
+            operation: format!("读取 {}", path.display()),
+            error: e.to_string(),
+        })
+    }
+
+    /// 构造编辑器错误
+    fn error(&self, message: String) -> GtError {
+        GtError::EditorError {
+            editor: self.command.clone(),
+            message,
+        }
+    }
+}
+
+/// 去掉以 `#` 开头的注释行以及首尾的空行，并去掉每行末尾的空白
+pub fn strip_comments(text: &str) -> String {
+    let lines: Vec<&str> = text.lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(str::trim_end)
+        .collect();
+    lines.join("\n").trim_matches('\n').to_string()
+}
@@ -1,8 +1,12 @@
 pub mod colors;
+pub mod editor;
+pub mod output;
 pub mod print;
 pub mod prompt;
 
 // 重新导出常用功能
 pub use colors::*;
+pub use editor::*;
+pub use output::*;
 pub use print::*;
 pub use prompt::*; 
\ No newline at end of file
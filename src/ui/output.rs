@@ -0,0 +1,85 @@
+//! 输出格式
+//!
+//! `--format json` 时不再打印装饰性的文本，每个命令结束时向标准输出写出一个 JSON 文档：
+//!
+//! ```text
+//! {"ok": true, "data": <命令的结果，没有时为 null>}
+//! {"ok": false, "error": {"variant": "BranchNotFound", "message": "...", "suggestion": "..."}}
+//! ```
+//!
+//! 警告和交互提示改写到标准错误，保证标准输出只有这一个文档。
+
+use crate::error::{ErrorHandler, GtError, GtResult};
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::{Mutex, OnceLock};
+
+/// 输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum OutputFormat {
+    /// 面向人的彩色文本
+    #[default]
+    Text,
+    /// 面向脚本的 JSON 文档
+    Json,
+}
+
+/// 进程级的输出格式，由 CLI 入口设置一次
+static OUTPUT_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// 当前命令通过 `emit_json` 提交的结果
+static DOCUMENT: Mutex<Option<Value>> = Mutex::new(None);
+
+/// 设置输出格式（只有第一次设置生效）
+pub fn set_output_format(format: OutputFormat) {
+    let _ = OUTPUT_FORMAT.set(format);
+}
+
+/// 当前的输出格式
+pub fn output_format() -> OutputFormat {
+    OUTPUT_FORMAT.get().copied().unwrap_or_default()
+}
+
+/// 是否输出 JSON
+pub fn is_json() -> bool {
+    output_format() == OutputFormat::Json
+}
+
+/// 提交当前命令的结果，作为 JSON 文档的 `data` 字段
+///
+/// 文本模式下什么也不做；多次调用时以最后一次为准。
+pub fn emit_json<T: Serialize + ?Sized>(data: &T) {
+    if !is_json() {
+        return;
+    }
+    let value = serde_json::to_value(data).unwrap_or(Value::Null);
+    if let Ok(mut document) = DOCUMENT.lock() {
+        *document = Some(value);
+    }
+}
+
+/// 错误的 JSON 表示
+pub fn error_json(error: &GtError) -> Value {
+    json!({
+        "variant": error.variant_name(),
+        "message": error.to_string(),
+        "suggestion": ErrorHandler::default().get_suggestion(error),
+    })
+}
+
+/// 输出命令结果的 JSON 文档
+pub fn print_json_result(result: &GtResult<()>) {
+    let document = match result {
+        Ok(()) => {
+            let data = DOCUMENT.lock().ok().and_then(|mut document| document.take());
+            json!({ "ok": true, "data": data })
+        }
+        Err(error) => json!({ "ok": false, "error": error_json(error) }),
+    };
+
+    match serde_json::to_string_pretty(&document) {
+        Ok(text) => println!("{}", text),
+        Err(e) => eprintln!("序列化输出失败: {}", e),
+    }
+}
@@ -3,6 +3,7 @@
 //! 提供不同级别的消息打印功能，对应 gw 的 utils_print.sh
 
 use super::colors::{Color, ColoredString};
+use super::output::is_json;
 use std::io::{self, Write};
 
 /// 消息级别
@@ -38,6 +39,8 @@ impl MessageLevel {
 }
 
 /// 打印带前缀的消息
+/// 
+/// JSON 输出模式下只保留写到标准错误的警告和错误。
 fn print_message(level: MessageLevel, message: &str) {
     let colored_prefix = ColoredString::new(level.prefix(), level.color());
     
@@ -45,6 +48,7 @@ fn print_message(level: MessageLevel, message: &str) {
         MessageLevel::Warning | MessageLevel::Error => {
             eprintln!("{} {}", colored_prefix, message);
         }
+        _ if is_json() => {}
         _ => {
             println!("{} {}", colored_prefix, message);
         }
@@ -78,6 +82,10 @@ pub fn print_error(message: &str) {
 
 /// 打印分隔线
 pub fn print_separator(title: Option<&str>) {
+    if is_json() {
+        return;
+    }
+    
     let cyan = ColoredString::new("===", Color::Cyan);
     
     match title {
@@ -88,6 +96,10 @@ pub fn print_separator(title: Option<&str>) {
 
 /// 打印进度条（简单版本）
 pub fn print_progress(current: usize, total: usize, message: &str) {
+    if is_json() {
+        return;
+    }
+    
     let percentage = if total > 0 { (current * 100) / total } else { 0 };
     let progress_bar = "=".repeat(percentage / 5);
     let empty_bar = " ".repeat(20 - progress_bar.len());
@@ -3,8 +3,23 @@
 //! 提供用户输入、确认、选择等交互功能
 
 use super::colors::{Color, ColoredString};
+use super::output::is_json;
 use std::io::{self, Write};
 
+/// 打印交互提示，JSON 输出模式下写到标准错误
+macro_rules! prompt_print {
+    ($($arg:tt)*) => {
+        if is_json() { eprint!($($arg)*) } else { print!($($arg)*) }
+    };
+}
+
+/// 打印一行交互提示，JSON 输出模式下写到标准错误
+macro_rules! prompt_println {
+    ($($arg:tt)*) => {
+        if is_json() { eprintln!($($arg)*) } else { println!($($arg)*) }
+    };
+}
+
 /// 确认操作（Y/n）
 pub fn confirm_action(message: &str, default_yes: bool) -> bool {
     let prompt = if default_yes {
@@ -13,7 +28,7 @@ pub fn confirm_action(message: &str, default_yes: bool) -> bool {
         ColoredString::new("[y/N]", Color::Yellow)
     };
     
-    print!("{} {} ", 
+    prompt_print!("{} {} ", 
         ColoredString::new(message, Color::Yellow), 
         prompt
     );
@@ -42,7 +57,7 @@ pub fn prompt_input(message: &str, default: Option<&str>) -> String {
         message.to_string()
     };
     
-    print!("{}: ", ColoredString::new(&prompt_msg, Color::Cyan));
+    prompt_print!("{}: ", ColoredString::new(&prompt_msg, Color::Cyan));
     io::stdout().flush().unwrap();
     
     let mut input = String::new();
@@ -59,6 +74,18 @@ pub fn prompt_input(message: &str, default: Option<&str>) -> String {
     }
 }
 
+/// 提示用户输入一个字母，返回输入的第一个字符（小写），直接回车时返回 `'\n'`，输入结束（EOF）时返回 None
+pub fn prompt_key(message: &str, keys: &str) -> Option<char> {
+    prompt_print!("{} ", ColoredString::new(&format!("{} [{}]?", message, keys), Color::Blue));
+    io::stdout().flush().unwrap();
+    
+    let mut input = String::new();
+    match io::stdin().read_line(&mut input) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(input.trim().chars().next().unwrap_or('\n').to_ascii_lowercase()),
+    }
+}
+
 /// 多选一对话框，返回选择的索引
 pub fn select_option<T>(
     title: &str, 
@@ -68,7 +95,7 @@ pub fn select_option<T>(
 where 
     T: Clone 
 {
-    println!("{}", ColoredString::new(title, Color::Cyan));
+    prompt_println!("{}", ColoredString::new(title, Color::Cyan));
     
     for (i, (_, description)) in options.iter().enumerate() {
         let prefix = if Some(i) == default_index {
@@ -77,7 +104,7 @@ where
             ColoredString::new(&format!("{})", i + 1), Color::Blue)
         };
         
-        println!("   {} {}", prefix, description);
+        prompt_println!("   {} {}", prefix, description);
     }
     
     let prompt = if let Some(default) = default_index {
@@ -86,7 +113,7 @@ where
         format!("请选择 [1-{}]", options.len())
     };
     
-    print!("{}: ", ColoredString::new(&prompt, Color::Yellow));
+    prompt_print!("{}: ", ColoredString::new(&prompt, Color::Yellow));
     io::stdout().flush().unwrap();
     
     let mut input = String::new();
@@ -104,7 +131,7 @@ where
                 }
             }
             
-            println!("{}", ColoredString::new("无效的选择", Color::Red));
+            prompt_println!("{}", ColoredString::new("无效的选择", Color::Red));
             None
         }
         Err(_) => None,
@@ -114,21 +141,21 @@ where
 /// 交互式文件选择器
 pub fn select_files(title: &str, files: &[String]) -> Vec<String> {
     if files.is_empty() {
-        println!("{}", ColoredString::new("没有可选择的文件", Color::Yellow));
+        prompt_println!("{}", ColoredString::new("没有可选择的文件", Color::Yellow));
         return Vec::new();
     }
     
-    println!("{}", ColoredString::new(title, Color::Cyan));
-    println!("输入文件编号（用空格分隔多个编号），或输入 'a' 选择全部，输入 'q' 取消：");
+    prompt_println!("{}", ColoredString::new(title, Color::Cyan));
+    prompt_println!("输入文件编号（用空格分隔多个编号），或输入 'a' 选择全部，输入 'q' 取消：");
     
     for (i, file) in files.iter().enumerate() {
-        println!("[{}] {}", 
+        prompt_println!("[{}] {}", 
             ColoredString::new(&format!("{}", i), Color::Blue), 
             file
         );
     }
     
-    print!("{}: ", 
+    prompt_print!("{}: ", 
         ColoredString::new(&format!("请选择 (0-{}, a=全部, q=取消)", files.len() - 1), Color::Yellow)
     );
     io::stdout().flush().unwrap();
@@ -139,7 +166,7 @@ pub fn select_files(title: &str, files: &[String]) -> Vec<String> {
             let input = input.trim();
             
             if input == "q" {
-                println!("{}", ColoredString::new("已取消选择", Color::Yellow));
+                prompt_println!("{}", ColoredString::new("已取消选择", Color::Yellow));
                 return Vec::new();
             }
             
@@ -153,7 +180,7 @@ pub fn select_files(title: &str, files: &[String]) -> Vec<String> {
                     if index < files.len() {
                         selected.push(files[index].clone());
                     } else {
-                        println!("{}", 
+                        prompt_println!("{}", 
                             ColoredString::new(&format!("忽略无效选择: {}", part), Color::Yellow)
                         );
                     }
@@ -161,12 +188,12 @@ pub fn select_files(title: &str, files: &[String]) -> Vec<String> {
             }
             
             if !selected.is_empty() {
-                println!("{} {}:", 
+                prompt_println!("{} {}:", 
                     ColoredString::new("已选择", Color::Green),
                     ColoredString::new(&format!("{} 个文件", selected.len()), Color::Bold)
                 );
                 for file in &selected {
-                    println!(" - {}", file);
+                    prompt_println!(" - {}", file);
                 }
             }
             
@@ -179,7 +206,7 @@ pub fn select_files(title: &str, files: &[String]) -> Vec<String> {
 /// 等待用户按 Enter 继续
 pub fn wait_for_enter(message: Option<&str>) {
     let msg = message.unwrap_or("按 Enter 键继续...");
-    print!("{}", ColoredString::new(msg, Color::Cyan));
+    prompt_print!("{}", ColoredString::new(msg, Color::Cyan));
     io::stdout().flush().unwrap();
     
     let mut input = String::new();
@@ -18,20 +18,41 @@ pub fn current_timestamp() -> String {
     chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string()
 }
 
-/// 验证分支名称
+/// 将 Unix 时间戳格式化为本地时间
+pub fn format_timestamp(seconds: i64) -> String {
+    chrono::DateTime::from_timestamp(seconds, 0)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// 验证分支名称（git 的引用名规则）
 pub fn validate_branch_name(name: &str) -> GtResult<()> {
-    if name.is_empty() {
-        return Err(GtError::InvalidBranchName { 
-            name: name.to_string() 
+    crate::git::naming::check_ref_format(name).map_err(|reason| GtError::BranchNamePolicy {
+        name: name.to_string(),
+        reason,
+    })
+} 
+/// 把参数原样交给 git 子命令，输出直接显示在终端
+pub fn run_git(subcommand: &str, args: &[String]) -> GtResult<()> {
+    if crate::ui::is_json() {
+        return Err(GtError::InvalidInput {
+            input: format!("--format json 不支持向 git {} 透传参数", subcommand)
         });
     }
     
-    // Git 分支名称基本验证
-    if name.contains("..") || name.starts_with('-') || name.ends_with('.') {
-        return Err(GtError::InvalidBranchName { 
-            name: name.to_string() 
+    let status = std::process::Command::new("git")
+        .arg(subcommand)
+        .args(args)
+        .status()
+        .map_err(|e| GtError::GitOperation {
+            message: format!("执行 git {} 失败: {}", subcommand, e)
+        })?;
+    
+    if !status.success() {
+        return Err(GtError::GitOperation {
+            message: format!("git {} {} 执行失败", subcommand, args.join(" "))
         });
     }
     
     Ok(())
-} 
\ No newline at end of file
+}
@@ -1,27 +1,186 @@
 //! 工作流引擎
-//! 
-//! 提供 Git 工作流的核心逻辑。
+//!
+//! 把命令拆成一串有类型的步骤，每个步骤包含前置检查、动作和补偿回滚。
+//! 引擎按顺序执行并显示进度，某一步失败时按相反顺序回滚已经完成的步骤，
+//! 避免仓库停在一半的状态。
+//!
+//! 以下情况不会自动回滚：
+//! - 步骤失败后仓库停在可以继续的状态（如 rebase 冲突），交给命令自己的 `--continue` / `--abort`
+//! - 推送、创建 PR 等外部可见的步骤，以及它之前的步骤
 
-use crate::error::{GtResult, GtError};
+use crate::error::GtResult;
+use crate::plan::Plan;
+use crate::ui::{print_error, print_info, print_step, print_success, print_warning};
+use async_trait::async_trait;
+
+/// 工作流中的一个步骤，`C` 是在步骤之间共享的上下文
+#[async_trait(?Send)]
+pub trait Step<C> {
+    /// 步骤描述，用于进度显示和执行计划
+    fn description(&self, ctx: &C) -> String;
+
+    /// 前置检查，失败时不执行这一步
+    fn check(&self, _ctx: &C) -> GtResult<()> {
+        Ok(())
+    }
+
+    /// 执行动作
+    async fn run(&mut self, ctx: &mut C) -> GtResult<()>;
+
+    /// 撤销动作的效果
+    ///
+    /// 动作失败时也会调用，需要能处理只执行了一部分的情况。
+    fn rollback(&mut self, _ctx: &mut C) -> GtResult<()> {
+        Ok(())
+    }
+
+    /// 效果能否撤销，推送、创建 PR 等外部可见的步骤返回 false
+    fn reversible(&self) -> bool {
+        true
+    }
+
+    /// 动作失败后仓库是否停在可以继续的状态，此时不回滚
+    fn paused(&self, _ctx: &C) -> bool {
+        false
+    }
+}
 
 /// 工作流引擎
-pub struct WorkflowEngine {
-    // TODO: 添加字段
+pub struct WorkflowEngine<'a, C> {
+    /// 命令名，用于提示
+    name: String,
+    steps: Vec<Box<dyn Step<C> + 'a>>,
+    /// 上次执行是否停在中间状态（没有回滚或回滚失败）
+    interrupted: bool,
 }
 
-impl WorkflowEngine {
-    /// 创建新的工作流引擎
-    pub fn new() -> Self {
+impl<'a, C> WorkflowEngine<'a, C> {
+    /// 创建空的工作流
+    pub fn new(name: impl Into<String>) -> Self {
         Self {
-            // TODO: 初始化字段
+            name: name.into(),
+            steps: Vec::new(),
+            interrupted: false,
         }
     }
-    
-    /// 执行工作流步骤
-    pub async fn execute_step(&self, _step: &str) -> GtResult<()> {
-        // TODO: 实现工作流步骤执行逻辑
-        Err(GtError::NotImplemented { 
-            feature: "workflow execution".to_string() 
-        })
+
+    /// 追加一个步骤
+    pub fn step(mut self, step: impl Step<C> + 'a) -> Self {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    /// 步骤数
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// 是否没有步骤
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// 上次执行失败后是否停在中间状态，需要继续或手动处理
+    pub fn interrupted(&self) -> bool {
+        self.interrupted
+    }
+
+    /// 从第 start 步开始的执行计划
+    pub fn plan(&self, ctx: &C, start: usize) -> Plan {
+        let mut plan = Plan::new(self.name.clone());
+        for step in self.steps.iter().skip(start) {
+            plan.step(step.description(ctx));
+        }
+        plan
+    }
+
+    /// 执行所有步骤
+    pub async fn run(&mut self, ctx: &mut C) -> GtResult<()> {
+        self.run_from(ctx, 0).await
+    }
+
+    /// 从第 start 步开始执行，之前的步骤视为已经完成
+    pub async fn run_from(&mut self, ctx: &mut C, start: usize) -> GtResult<()> {
+        self.interrupted = false;
+        let total = self.steps.len();
+
+        for index in start..total {
+            let step = &mut self.steps[index];
+            let description = step.description(ctx);
+            print_step(&format!("[{}/{}] {}", index + 1, total, description));
+
+            if let Err(e) = step.check(ctx) {
+                print_warning(&format!("前置检查未通过: {}", description));
+                self.rollback_after_failure(ctx, index);
+                return Err(e);
+            }
+
+            let Err(e) = step.run(ctx).await else {
+                continue;
+            };
+
+            if step.paused(ctx) {
+                self.interrupted = true;
+                return Err(e);
+            }
+
+            print_warning(&format!("步骤失败: {}", description));
+            // 失败的步骤可能已经执行了一部分
+            if step.reversible() {
+                print_step(&format!("回滚: {}", description));
+                if let Err(rollback_error) = step.rollback(ctx) {
+                    print_error(&format!("回滚 '{}' 失败: {}", description, rollback_error));
+                    self.report_interrupted();
+                    return Err(e);
+                }
+            }
+            self.rollback_after_failure(ctx, index);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// 按相反顺序回滚前 count 个步骤，遇到不可撤销的步骤时停止
+    pub fn rollback(&mut self, ctx: &mut C, count: usize) -> GtResult<()> {
+        self.rollback_steps(ctx, count).map(|_| ())
+    }
+
+    /// 回滚前 count 个步骤，返回是否全部撤销
+    fn rollback_steps(&mut self, ctx: &mut C, count: usize) -> GtResult<bool> {
+        let count = count.min(self.steps.len());
+        for step in self.steps[..count].iter_mut().rev() {
+            let description = step.description(ctx);
+            if !step.reversible() {
+                print_info(&format!("'{}' 已生效且无法撤销，不再回滚之前的步骤", description));
+                return Ok(false);
+            }
+
+            print_step(&format!("回滚: {}", description));
+            if let Err(e) = step.rollback(ctx) {
+                print_error(&format!("回滚 '{}' 失败: {}", description, e));
+                return Err(e);
+            }
+        }
+        Ok(true)
+    }
+
+    /// 第 failed 步失败后回滚已完成的步骤
+    fn rollback_after_failure(&mut self, ctx: &mut C, failed: usize) {
+        if failed == 0 {
+            return;
+        }
+
+        match self.rollback_steps(ctx, failed) {
+            Ok(true) => print_success(&format!("已回滚 gt {} 完成的步骤", self.name)),
+            Ok(false) => {}
+            Err(_) => self.report_interrupted(),
+        }
+    }
+
+    /// 回滚失败，仓库停在中间状态
+    fn report_interrupted(&mut self) {
+        self.interrupted = true;
+        print_info("仓库停在中间状态，请手动检查");
     }
 }